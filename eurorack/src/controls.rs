@@ -6,8 +6,17 @@ use daisy::hal;
 use hal::adc::{Adc, Enabled};
 use hal::pac::{ADC1, ADC2};
 
-use achordion_lib::config::Config;
+use achordion_lib::config::{self, Config};
+use achordion_lib::glide::Glide;
+use achordion_lib::instrument::{AmplitudeMode, USER_SCALES};
+use achordion_lib::modulation;
+use achordion_lib::quantizer::diatonic::Algorithm;
+use achordion_lib::scales::scale::Scale;
+use achordion_lib::sequencer::{Sequencer, Step};
 use achordion_lib::store::Parameters;
+use achordion_lib::strum::Order;
+use achordion_lib::tuning::temperament::Temperament;
+use achordion_lib::warp;
 
 use crate::system::Button;
 use crate::system::Probe;
@@ -17,6 +26,15 @@ use crate::system::{Pot1, Pot2, Pot3, Pot4};
 // V/OCT CV spans from -5.0 to 5.0 V.
 const VOCT_CV_RANGE: f32 = 10.0;
 
+// The fourth page of the configuration menu has no options, its pots set the
+// envelope instead and its LEDs show the active stage of the envelope.
+const ENVELOPE_PAGE: usize = 3;
+
+// The sixth page of the configuration menu holds options of modulation. Its
+// pots set the amount of cross modulation, the index and the ratio of
+// frequency modulation.
+const MODULATION_PAGE: usize = 5;
+
 pub struct ControlsConfig {
     pub adc1: Adc<ADC1, Enabled>,
     pub adc2: Adc<ADC2, Enabled>,
@@ -55,12 +73,27 @@ pub struct Controls {
     last_chord_pot_reading: f32,
     last_detune_pot_reading: f32,
 
+    voicing: f32,
+    inversion: f32,
+    wavetable_bank_cv: Option<f32>,
+
     note_source: NoteSource,
 
     calibration_target: CalibrationTarget,
     calibration_state: CalibrationState,
 
     configuration_state: ConfigurationState,
+    option_cursor: Option<usize>,
+    user_scale_cursor: Option<u8>,
+    user_scale_membership: Option<bool>,
+
+    sequencer: Sequencer,
+    applied_step: Option<Step>,
+    button_used: bool,
+    button_clicked: bool,
+    record_requested: bool,
+    frozen: bool,
+    freeze_toggled: bool,
 }
 
 #[derive(Clone, Copy)]
@@ -84,7 +117,7 @@ enum CalibrationState {
 #[derive(Clone, Copy)]
 enum ConfigurationState {
     Inactive,
-    Active,
+    Active(usize),
 }
 
 #[derive(Clone, Copy)]
@@ -140,12 +173,27 @@ impl Controls {
             last_chord_pot_reading: 0.0,
             last_detune_pot_reading: 0.0,
 
+            voicing: 0.0,
+            wavetable_bank_cv: None,
+            inversion: 0.5,
+
             note_source: NoteSource::Pot,
 
             calibration_target: CalibrationTarget::None,
             calibration_state: CalibrationState::Inactive,
 
             configuration_state: ConfigurationState::Inactive,
+            option_cursor: None,
+            user_scale_cursor: None,
+            user_scale_membership: None,
+
+            sequencer: Sequencer::new(parameters.sequence),
+            applied_step: None,
+            button_used: false,
+            button_clicked: false,
+            record_requested: false,
+            frozen: false,
+            freeze_toggled: false,
         };
 
         // Initial probe tick, so the signal has enough time to propagate to all
@@ -195,10 +243,30 @@ impl Controls {
         self.parameters.detune
     }
 
+    pub fn voicing(&self) -> f32 {
+        self.voicing
+    }
+
+    pub fn inversion(&self) -> f32 {
+        self.inversion
+    }
+
     pub fn wavetable_bank(&self) -> f32 {
         self.parameters.bank
     }
 
+    // With the bank controlled by WAVETABLE CV, banks are swept continuously,
+    // offset by the knob.
+    pub fn continuous_wavetable_bank(&self) -> Option<f32> {
+        if !self.wavetable_bank_controlled_by_wavetable_cv() {
+            None
+        } else if let Some(wavetable_bank) = self.wavetable_bank_cv {
+            Some((wavetable_bank + self.parameters.bank).clamp(0.0, 1.0))
+        } else {
+            Some(self.parameters.bank)
+        }
+    }
+
     pub fn scale_root(&self) -> f32 {
         self.parameters.scale_root
     }
@@ -272,11 +340,76 @@ impl Controls {
         self.parameters.config
     }
 
+    pub fn config_page(&self) -> usize {
+        match self.configuration_state {
+            ConfigurationState::Active(page) => page,
+            ConfigurationState::Inactive => 0,
+        }
+    }
+
+    // Returns the option of the current page under the cursor and whether it
+    // is enabled.
+    pub fn config_option(&self) -> Option<(usize, bool)> {
+        let cursor = self.option_cursor?;
+        Some((
+            cursor,
+            self.parameters.config.page_leds(self.config_page())[cursor],
+        ))
+    }
+
+    pub fn envelope_page_open(&self) -> bool {
+        matches!(
+            self.configuration_state,
+            ConfigurationState::Active(ENVELOPE_PAGE)
+        )
+    }
+
+    pub fn amplitude_mode(&self) -> AmplitudeMode {
+        if self.parameters.config.rms_amplitude() {
+            AmplitudeMode::Rms
+        } else if self.parameters.config.balanced_amplitude() {
+            AmplitudeMode::Balanced
+        } else {
+            AmplitudeMode::Stable
+        }
+    }
+
+    // Later temperaments take precedence when more of them are enabled.
+    pub fn temperament(&self) -> Temperament {
+        if self.parameters.config.werckmeister_temperament() {
+            Temperament::Werckmeister
+        } else if self.parameters.config.meantone_temperament() {
+            Temperament::Meantone
+        } else if self.parameters.config.pythagorean_temperament() {
+            Temperament::Pythagorean
+        } else {
+            Temperament::Equal
+        }
+    }
+
     pub fn update(&mut self) {
         self.sample();
         self.reconcile();
     }
 
+    // The lowest bits of pot readings are dominated by noise, so they differ
+    // after each power-up, unlike anything stored in the flash. Gathered from
+    // several readings, they make a seed for random generators.
+    pub fn noise(&mut self) -> u32 {
+        let mut noise: u32 = 0;
+        for _ in 0..8 {
+            for sample in [
+                self.pot1.raw_sample(&mut self.adc2),
+                self.pot2.raw_sample(&mut self.adc1),
+                self.pot3.raw_sample(&mut self.adc2),
+                self.pot4.raw_sample(&mut self.adc1),
+            ] {
+                noise = noise.rotate_left(4) ^ sample;
+            }
+        }
+        noise
+    }
+
     fn sample(&mut self) {
         self.pot1.start_sampling(&mut self.adc2);
         self.pot2.start_sampling(&mut self.adc1);
@@ -309,6 +442,14 @@ impl Controls {
     }
 
     fn reconcile(&mut self) {
+        // The button is used as a modifier when it is held while any of the
+        // alternative controls is adjusted or while a submenu is open.
+        if self.button.active()
+            && (self.pots_active() || self.cv4.was_plugged() || !self.active_submenu().is_none())
+        {
+            self.button_used = true;
+        }
+
         self.reconcile_note();
         self.reconcile_wavetable();
         self.reconcile_wavetable_bank();
@@ -318,6 +459,8 @@ impl Controls {
         self.reconcile_solo();
         self.reconcile_scale_root();
         self.reconcile_scale_mode();
+        self.reconcile_voicing();
+        self.reconcile_inversion();
         self.reconcile_solo_quantization();
         self.reconcile_chord_quantization();
 
@@ -328,6 +471,12 @@ impl Controls {
         if self.active_submenu().is_none() || self.active_submenu().is_configuration() {
             self.reconcile_configuration();
         }
+
+        self.reconcile_clicks();
+
+        if !self.chord_sequencer() {
+            self.sequencer.stop();
+        }
     }
 
     fn reconcile_note(&mut self) {
@@ -342,6 +491,9 @@ impl Controls {
             let octave_offset = (pot * 4.95).trunc() - 5.0;
             let note = self.cv1_sample_to_voct(self.cv1.value());
             self.note_source = NoteSource::Cv;
+            if self.chord_root_held() {
+                return;
+            }
             if note < 0.5 / 12.0 {
                 0.0
             } else {
@@ -353,13 +505,32 @@ impl Controls {
         };
     }
 
+    // While held, the last sampled chord root is kept. Sample and hold takes
+    // a new sample on each trigger, track and hold follows the input while
+    // the gate is high.
+    fn chord_root_held(&mut self) -> bool {
+        if !self.cv2.connected() || self.tonic_controlled_by_solo_cv() {
+            return false;
+        }
+        if self.track_and_hold_chord_root() {
+            !self.cv2.gate()
+        } else if self.sample_and_hold_chord_root() {
+            !self.cv2.was_triggered()
+        } else {
+            false
+        }
+    }
+
     fn reconcile_wavetable(&mut self) {
         if !self.button.active() {
             self.last_wavetable_pot_reading = self.pot1.value();
         }
         let pot = self.last_wavetable_pot_reading;
 
-        self.parameters.wavetable = if self.cv3.connected() {
+        self.parameters.wavetable = if self.cv3.connected()
+            && !self.inversion_controlled_by_wavetable_cv()
+            && !self.wavetable_bank_controlled_by_wavetable_cv()
+        {
             // CV is centered around zero, suited for LFO.
             let wavetable = self.cv3.value() * 2.0 - 1.0;
             let offset = pot;
@@ -373,6 +544,14 @@ impl Controls {
         if self.wavetable_bank_pot_active() {
             self.parameters.bank = self.pot1.value();
         };
+
+        self.wavetable_bank_cv =
+            if self.cv3.connected() && self.wavetable_bank_controlled_by_wavetable_cv() {
+                // CV is centered around zero, suited for LFO.
+                Some(self.cv3.value() * 2.0 - 1.0)
+            } else {
+                None
+            };
     }
 
     fn reconcile_chord(&mut self) {
@@ -420,7 +599,10 @@ impl Controls {
         };
         let pot = self.last_detune_pot_reading;
 
-        self.parameters.detune = if self.cv4.connected() && !self.mode_controlled_by_detune_cv() {
+        self.parameters.detune = if self.cv4.connected()
+            && !self.mode_controlled_by_detune_cv()
+            && !self.voicing_controlled_by_detune_cv()
+        {
             // CV is centered around zero, suited for LFO.
             let detune = self.cv4.value() * 2.0 - 1.0;
             let offset = pot;
@@ -431,7 +613,7 @@ impl Controls {
     }
 
     fn reconcile_solo(&mut self) {
-        if self.cv2.connected() && !self.tonic_controlled_by_solo_cv() {
+        if self.cv2.connected() && self.parameters.config.solo_controlled_by_solo_cv() {
             let note = self.cv2_sample_to_voct(self.cv2.value());
             let offset = -2.0;
             self.parameters.solo = note + offset;
@@ -469,25 +651,217 @@ impl Controls {
         };
     }
 
+    fn reconcile_voicing(&mut self) {
+        self.voicing = if self.cv4.connected() && self.voicing_controlled_by_detune_cv() {
+            // Close voicing is at 0 V, positive voltage spreads the chord.
+            (self.cv4.value() * 2.0 - 1.0).min(0.9999).max(0.0)
+        } else {
+            0.0
+        };
+    }
+
+    fn reconcile_inversion(&mut self) {
+        // CV is centered around zero, where the chord stays in root position.
+        self.inversion = if self.cv3.connected() && self.inversion_controlled_by_wavetable_cv() {
+            self.cv3.value()
+        } else if self.cv2.connected() && self.inversion_controlled_by_solo_cv() {
+            self.cv2.value()
+        } else {
+            0.5
+        };
+    }
+
     fn reconcile_configuration(&mut self) {
         if self.pots_active() {
             self.button.long_click_reset();
         }
 
+        // Clicking the button moves to the next page of options, leaving the
+        // menu after the last one.
         if self.button.long_clicked() {
-            self.configuration_state = ConfigurationState::Active;
-        } else if matches!(self.configuration_state, ConfigurationState::Active)
-            && self.button.clicked()
-        {
-            self.configuration_state = ConfigurationState::Inactive;
+            self.configuration_state = ConfigurationState::Active(0);
+            self.option_cursor = None;
+        } else if let ConfigurationState::Active(page) = self.configuration_state {
+            if self.button.single_clicked() {
+                self.option_cursor = None;
+                if page + 1 < config::PAGES {
+                    self.configuration_state = ConfigurationState::Active(page + 1);
+                } else {
+                    self.configuration_state = ConfigurationState::Inactive;
+                    self.user_scale_cursor = None;
+                    self.user_scale_membership = None;
+                }
+            }
+        }
+
+        if let ConfigurationState::Active(ENVELOPE_PAGE) = self.configuration_state {
+            self.reconcile_envelope();
+            return;
+        }
+
+        // Pot 1 moves the cursor through options of the page, each taking an
+        // equal part of its travel. Double clicking the button toggles the
+        // option under the cursor.
+        if let ConfigurationState::Active(page) = self.configuration_state {
+            let options = config::OPTIONS_PER_PAGE[page] as f32;
+            if self.pot1.active() {
+                let cursor = (self.pot1.value() * options).clamp(0.0, options - 1.0) as usize;
+                self.option_cursor = Some(cursor);
+                self.user_scale_cursor = None;
+                self.user_scale_membership = None;
+            }
+            if let Some(cursor) = self.option_cursor {
+                if self.button.double_clicked() {
+                    self.parameters.config =
+                        self.parameters.config.with_option_toggled(page, cursor);
+                }
+            }
+        }
+
+        if let ConfigurationState::Active(MODULATION_PAGE) = self.configuration_state {
+            self.reconcile_modulation();
+            return;
+        }
+
+        // While in the configuration menu, pot 2 selects a pitch class of the
+        // user scale and pot 4 adds or removes it. Pot 3 sets the hysteresis
+        // of quantization on the first page, glide time on the second, strum
+        // spacing on the third and the amount of phase warp on the fifth. With
+        // hard sync enabled, it sets the sync ratio instead of the warp.
+        if matches!(self.configuration_state, ConfigurationState::Active(_)) {
+            if self.pot2.active() {
+                let cursor = (self.pot2.value() * 12.0).clamp(0.0, 11.0) as u8;
+                if self.user_scale_cursor != Some(cursor) {
+                    self.user_scale_cursor = Some(cursor);
+                    self.user_scale_membership = None;
+                }
+                self.option_cursor = None;
+            }
+            if self.pot4.active() && self.user_scale_cursor.is_some() {
+                self.user_scale_membership = Some(self.pot4.value() > 0.5);
+            }
+            if self.pot3.active() {
+                match self.config_page() {
+                    0 => self.parameters.quantization_hysteresis = self.pot3.value(),
+                    1 => self.parameters.glide = self.pot3.value(),
+                    2 => self.parameters.strum = self.pot3.value(),
+                    4 if self.parameters.config.hard_sync() => {
+                        self.parameters.sync_ratio = self.pot3.value()
+                    }
+                    4 => self.parameters.warp = self.pot3.value(),
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    // Clicks of the button that was not used as a modifier have their own
+    // functions. A single click records the current chord into the sequence,
+    // a double click toggles the freeze.
+    fn reconcile_clicks(&mut self) {
+        if self.button.released() {
+            self.button_clicked = !self.button_used && self.active_submenu().is_none();
+            self.button_used = false;
+        }
+
+        self.record_requested =
+            self.button.single_clicked() && self.button_clicked && self.chord_sequencer();
+
+        self.freeze_toggled = self.button.double_clicked() && self.button_clicked;
+        if self.freeze_toggled {
+            self.frozen = !self.frozen;
+        }
+    }
+
+    pub fn frozen(&self) -> bool {
+        self.frozen
+    }
+
+    pub fn freeze_toggled(&self) -> bool {
+        self.freeze_toggled
+    }
+
+    pub fn record_requested(&self) -> bool {
+        self.record_requested
+    }
+
+    // Returns the position of the recorded step, None if the sequence is full.
+    pub fn record_step(&mut self, step: Step) -> Option<usize> {
+        let position = self.sequencer.record(step);
+        self.parameters.sequence = self.sequencer.sequence();
+        position
+    }
+
+    pub fn advance_sequence(&mut self) -> Option<usize> {
+        if self.chord_sequencer() {
+            self.sequencer.trigger()
+        } else {
+            None
+        }
+    }
+
+    pub fn sequenced_step(&self) -> Option<Step> {
+        self.sequencer.step()
+    }
+
+    // Returns the sequenced step only if it differs from the one returned
+    // last time, so the instrument does not need to apply it on every tick.
+    pub fn changed_sequenced_step(&mut self) -> Option<Step> {
+        let step = self.sequencer.step();
+        if step == self.applied_step {
+            return None;
+        }
+        self.applied_step = step;
+        step
+    }
+
+    fn reconcile_envelope(&mut self) {
+        if self.pot1.active() {
+            self.parameters.attack = self.pot1.value();
+        }
+        if self.pot2.active() {
+            self.parameters.decay = self.pot2.value();
+        }
+        if self.pot3.active() {
+            self.parameters.sustain = self.pot3.value();
+        }
+        if self.pot4.active() {
+            self.parameters.release = self.pot4.value();
+        }
+    }
+
+    fn reconcile_modulation(&mut self) {
+        if self.pot2.active() {
+            self.parameters.cross_modulation = self.pot2.value();
+        }
+        if self.pot3.active() {
+            self.parameters.modulation_index = self.pot3.value();
         }
+        if self.pot4.active() {
+            self.parameters.modulation_ratio = self.pot4.value();
+        }
+    }
+
+    pub fn user_scales(&self) -> [u16; USER_SCALES] {
+        self.parameters.user_scales
+    }
 
-        if matches!(self.configuration_state, ConfigurationState::Active) && self.pot1.active() {
-            const OPTIONS: i32 = 4;
-            let scale = f32::powi(2.0, OPTIONS);
-            let config = (self.pot1.value() * scale - 0.01) as u8;
-            self.parameters.config = Config::from(config);
+    // Apply pending edits to the given user scale. Returns the pitch class
+    // under the cursor and whether it is part of the scale. Edits that would
+    // leave the scale without its tonic or with too few notes are ignored.
+    pub fn edit_user_scale(&mut self, index: usize) -> Option<(u8, bool)> {
+        let cursor = self.user_scale_cursor?;
+        let bit = 1 << cursor;
+
+        if let Some(enabled) = self.user_scale_membership.take() {
+            let mask = self.parameters.user_scales[index];
+            let mask = if enabled { mask | bit } else { mask & !bit };
+            if Scale::from_mask(mask).is_some() {
+                self.parameters.user_scales[index] = mask;
+            }
         }
+
+        Some((cursor, self.parameters.user_scales[index] & bit != 0))
     }
 
     fn reconcile_calibration(&mut self) {
@@ -618,12 +992,267 @@ impl Controls {
         self.parameters.config.modes_ordered_by_brightness()
     }
 
+    // Voicing takes precedence when both are assigned to DETUNE CV.
     pub fn mode_controlled_by_detune_cv(&self) -> bool {
         self.parameters.config.mode_controlled_by_detune_cv()
+            && !self.voicing_controlled_by_detune_cv()
+    }
+
+    pub fn voicing_controlled_by_detune_cv(&self) -> bool {
+        self.parameters.config.voicing_controlled_by_detune_cv()
     }
 
+    // Inversion takes precedence over other uses of TONE' CV.
     pub fn tonic_controlled_by_solo_cv(&self) -> bool {
         self.parameters.config.tonic_controlled_by_solo_cv()
+            && !self.inversion_controlled_by_solo_cv()
+    }
+
+    // The chord sequencer, generator, progression and transformations are
+    // advanced by the trigger, so they assign TONE' CV to it too.
+    pub fn trigger_controlled_by_solo_cv(&self) -> bool {
+        (self.parameters.config.trigger_controlled_by_solo_cv()
+            || self.chord_sequencer()
+            || self.chord_generator().is_some()
+            || self.chord_progression().is_some()
+            || self.transformations().is_some())
+            && !self.inversion_controlled_by_solo_cv()
+    }
+
+    pub fn gate_controlled_by_solo_cv(&self) -> bool {
+        self.parameters.config.gate_controlled_by_solo_cv()
+            && !self.inversion_controlled_by_solo_cv()
+    }
+
+    pub fn inversion_controlled_by_wavetable_cv(&self) -> bool {
+        self.parameters
+            .config
+            .inversion_controlled_by_wavetable_cv()
+    }
+
+    // Inversion takes precedence over the wavetable bank.
+    pub fn wavetable_bank_controlled_by_wavetable_cv(&self) -> bool {
+        self.parameters
+            .config
+            .wavetable_bank_controlled_by_wavetable_cv()
+            && !self.inversion_controlled_by_wavetable_cv()
+    }
+
+    // When both inputs are assigned to inversion, WAVETABLE CV is used.
+    pub fn inversion_controlled_by_solo_cv(&self) -> bool {
+        self.parameters.config.inversion_controlled_by_solo_cv()
+            && !self.inversion_controlled_by_wavetable_cv()
+    }
+
+    // Holding the chord root shares TONE' CV with the trigger and the gate,
+    // but not with the inversion.
+    pub fn sample_and_hold_chord_root(&self) -> bool {
+        self.parameters.config.sample_and_hold_chord_root()
+            && !self.inversion_controlled_by_solo_cv()
+    }
+
+    // Track and hold takes precedence over sample and hold.
+    pub fn track_and_hold_chord_root(&self) -> bool {
+        self.parameters.config.track_and_hold_chord_root()
+            && !self.inversion_controlled_by_solo_cv()
+    }
+
+    pub fn chord_sequencer(&self) -> bool {
+        self.parameters.config.chord_sequencer()
+    }
+
+    // While generating chords, the CHORD knob and CV set the adventure. The
+    // chord sequencer and progression take precedence.
+    pub fn chord_generator(&self) -> Option<f32> {
+        if self.parameters.config.chord_generator()
+            && !self.chord_sequencer()
+            && self.chord_progression().is_none()
+        {
+            Some(self.parameters.chord.clamp(0.0, 1.0))
+        } else {
+            None
+        }
+    }
+
+    // Returns the phrase length in bars. The chord sequencer takes precedence.
+    pub fn chord_progression(&self) -> Option<usize> {
+        if !self.parameters.config.chord_progression() || self.chord_sequencer() {
+            None
+        } else if self.parameters.config.eight_bar_phrases() {
+            Some(8)
+        } else {
+            Some(4)
+        }
+    }
+
+    // While transforming triads, the CHORD knob and CV select the sequence of
+    // transformations. Other ways of picking chords take precedence.
+    pub fn transformations(&self) -> Option<f32> {
+        if self.parameters.config.neo_riemannian_transformations()
+            && !self.chord_sequencer()
+            && self.chord_generator().is_none()
+            && self.chord_progression().is_none()
+        {
+            Some(self.parameters.chord.clamp(0.0, 1.0))
+        } else {
+            None
+        }
+    }
+
+    pub fn sequence_scale_mode(&self) -> bool {
+        self.parameters.config.sequence_scale_mode()
+    }
+
+    pub fn just_intonation(&self) -> bool {
+        self.parameters.config.just_intonation()
+    }
+
+    pub fn quantization(&self) -> Algorithm {
+        if self.parameters.config.hysteresis_quantization() {
+            Algorithm::Hysteresis(self.parameters.quantization_hysteresis)
+        } else if self.parameters.config.white_key_quantization() {
+            Algorithm::WhiteKeys
+        } else {
+            Algorithm::Center
+        }
+    }
+
+    // Glide time grows quadratically with the pot, to offer finer control over
+    // short glides.
+    pub fn glide(&self) -> Glide {
+        const MAX_GLIDE_TIME: f32 = 2.0;
+        let time = self.parameters.glide * self.parameters.glide * MAX_GLIDE_TIME;
+        if self.parameters.config.constant_rate_glide() {
+            Glide::ConstantRate(time)
+        } else {
+            Glide::ConstantTime(time)
+        }
+    }
+
+    // Strum spacing in milliseconds, zero when the pot is turned fully down.
+    pub fn strum(&self) -> f32 {
+        const MAX_STRUM_SPACING: f32 = 60.0;
+        if self.parameters.strum < 0.02 {
+            0.0
+        } else {
+            self.parameters.strum * MAX_STRUM_SPACING
+        }
+    }
+
+    pub fn warp(&self) -> f32 {
+        self.parameters.warp
+    }
+
+    // Casio-style phase distortion is used unless another warp is selected.
+    pub fn warp_mode(&self) -> warp::Mode {
+        if self.parameters.config.window_warp() {
+            warp::Mode::Window
+        } else if self.parameters.config.sync_warp() {
+            warp::Mode::Sync
+        } else {
+            warp::Mode::Distortion
+        }
+    }
+
+    // Chord voices run up to 8 times faster than their frequency, while being
+    // reset by the chord root.
+    pub fn hard_sync(&self) -> Option<f32> {
+        const MAX_SYNC_RATIO: f32 = 8.0;
+        if self.parameters.config.hard_sync() {
+            Some(1.0 + self.parameters.sync_ratio * (MAX_SYNC_RATIO - 1.0))
+        } else {
+            None
+        }
+    }
+
+    // Returns the index and the ratio of the internal modulator. The index
+    // goes up to 5, with finer control over low values. Ratios are picked from
+    // a list, so the modulator stays in tune with the chord.
+    pub fn frequency_modulation(&self) -> Option<(f32, f32)> {
+        const MAX_INDEX: f32 = 5.0;
+        const RATIOS: [f32; 12] = [0.25, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.5, 4.0, 5.0, 6.0, 7.0];
+        if self.parameters.config.frequency_modulation() {
+            let index = self.parameters.modulation_index;
+            let ratio = ((self.parameters.modulation_ratio * RATIOS.len() as f32) as usize)
+                .min(RATIOS.len() - 1);
+            Some((index * index * MAX_INDEX, RATIOS[ratio]))
+        } else {
+            None
+        }
+    }
+
+    // Ring modulation takes precedence over amplitude modulation.
+    pub fn cross_modulation(&self) -> Option<modulation::Mode> {
+        if self.parameters.config.ring_modulation() {
+            Some(modulation::Mode::Ring)
+        } else if self.parameters.config.amplitude_modulation() {
+            Some(modulation::Mode::Amplitude)
+        } else {
+            None
+        }
+    }
+
+    pub fn cross_modulation_amount(&self) -> f32 {
+        self.parameters.cross_modulation
+    }
+
+    pub fn strum_order(&self) -> Order {
+        if self.parameters.config.random_strum_order() {
+            Order::Random
+        } else if self.parameters.config.strum_down() {
+            Order::Down
+        } else {
+            Order::Up
+        }
+    }
+
+    // Gate may share TONE' CV with the trigger, but not with the tonic.
+    pub fn gate(&mut self) -> Option<bool> {
+        if self.cv2.connected()
+            && self.gate_controlled_by_solo_cv()
+            && !self.tonic_controlled_by_solo_cv()
+        {
+            Some(self.cv2.gate())
+        } else {
+            None
+        }
+    }
+
+    // Envelope times grow quadratically with the pot, up to 4 seconds.
+    pub fn attack(&self) -> f32 {
+        envelope_time(self.parameters.attack)
+    }
+
+    pub fn decay(&self) -> f32 {
+        envelope_time(self.parameters.decay)
+    }
+
+    pub fn sustain(&self) -> f32 {
+        self.parameters.sustain
+    }
+
+    pub fn release(&self) -> f32 {
+        envelope_time(self.parameters.release)
+    }
+
+    pub fn voice_matching(&self) -> bool {
+        self.parameters.config.voice_matching()
+    }
+
+    // Chords are voiced within two octaves around their root.
+    pub fn voice_leading(&self) -> Option<u8> {
+        if self.parameters.config.voice_leading() {
+            Some(24)
+        } else {
+            None
+        }
+    }
+
+    pub fn triggered(&mut self) -> bool {
+        self.cv2.connected()
+            && self.trigger_controlled_by_solo_cv()
+            && !self.tonic_controlled_by_solo_cv()
+            && self.cv2.was_triggered()
     }
 
     fn cv1_sample_to_voct(&self, transposed_sample: f32) -> f32 {
@@ -642,6 +1271,11 @@ impl Controls {
     }
 }
 
+fn envelope_time(pot: f32) -> f32 {
+    const MAX_ENVELOPE_TIME: f32 = 4.0;
+    pot * pot * MAX_ENVELOPE_TIME
+}
+
 fn calculate_calibration(c_a: f32, c_b: f32) -> Result<(f32, f32), ()> {
     let (c_a, c_b) = if c_a < c_b { (c_a, c_b) } else { (c_b, c_a) };
 
@@ -25,7 +25,7 @@ use rtic::cyccnt::Instant;
 use rtic::cyccnt::U32Ext as _;
 
 use achordion_lib::display::{self as display_lib, Action as DisplayAction, CalibrationPhase};
-use achordion_lib::instrument::Instrument;
+use achordion_lib::instrument::{Instrument, ScaleMode};
 use achordion_lib::store::Parameters;
 
 use crate::bank::WAVETABLE_BANKS;
@@ -120,7 +120,7 @@ const APP: () = {
         }
     }
 
-    #[task(schedule = [fade_in, reconcile_controls], spawn = [backup_countdown, blink], resources = [display, instrument], priority = 2)]
+    #[task(schedule = [fade_in, reconcile_controls], spawn = [backup_countdown, blink], resources = [controls, display, instrument], priority = 2)]
     fn initialize(mut cx: initialize::Context) {
         let display = cx.resources.display;
         bank::setup(display);
@@ -130,6 +130,7 @@ const APP: () = {
             SAMPLE_RATE,
         );
         instrument.set_amplitude(0.0);
+        instrument.set_seed(cx.resources.controls.noise());
 
         cx.resources
             .instrument
@@ -196,15 +197,15 @@ const APP: () = {
         let mut configuration_action = None;
         let mut any_actions = None;
         let mut pot_actions = None;
-        let mut chord_degrees = None;
+        let mut chord_action = None;
 
         cx.resources.instrument.lock(|instrument| {
             let instrument = instrument.as_mut().unwrap();
             calibration_action = Some(reconcile_calibration(controls));
-            configuration_action = Some(reconcile_configuration(controls));
+            configuration_action = Some(reconcile_configuration(controls, instrument));
             any_actions = Some(reconcile_all_changes(controls, instrument));
             pot_actions = Some(reconcile_pot_activity(controls, instrument));
-            chord_degrees = Some(instrument.chord_degrees());
+            chord_action = Some(chord_display_action(controls, instrument));
         });
 
         if let Some(display_action) = activity.reconcile(
@@ -212,7 +213,7 @@ const APP: () = {
             configuration_action.unwrap(),
             pot_actions.unwrap(),
             any_actions.unwrap(),
-            DisplayAction::SetChord(chord_degrees.unwrap()),
+            chord_action.unwrap(),
         ) {
             display.set(display_lib::reduce(display_action));
         };
@@ -321,19 +322,72 @@ fn reconcile_calibration(controls: &mut Controls) -> Option<DisplayAction> {
     }
 }
 
-fn reconcile_configuration(controls: &mut Controls) -> Option<DisplayAction> {
-    if controls.config_open() {
-        Some(DisplayAction::SetConfiguration(controls.config().into()))
-    } else {
-        None
+fn reconcile_configuration(
+    controls: &mut Controls,
+    instrument: &mut Instrument,
+) -> Option<DisplayAction> {
+    if !controls.config_open() {
+        return None;
+    }
+
+    if let ScaleMode::User(index) = instrument.scale_mode() {
+        if let Some((pitch_class, enabled)) = controls.edit_user_scale(index) {
+            return Some(DisplayAction::SetUserScaleNote(pitch_class, enabled));
+        }
     }
+
+    if controls.envelope_page_open() {
+        return Some(DisplayAction::SetEnvelopeStage(instrument.envelope_stage()));
+    }
+
+    if let Some((index, enabled)) = controls.config_option() {
+        return Some(DisplayAction::SetConfigurationOption(index, enabled));
+    }
+
+    Some(DisplayAction::SetConfiguration(
+        controls.config().page_leds(controls.config_page()),
+    ))
 }
 
 fn reconcile_all_changes(
     controls: &mut Controls,
     instrument: &mut Instrument,
-) -> [Option<DisplayAction>; 9] {
-    let new_chord_root_degree = if controls.note_from_pot() {
+) -> [Option<DisplayAction>; 18] {
+    let triggered = controls.triggered();
+
+    let freeze_action = if controls.freeze_toggled() {
+        Some(chord_display_action(controls, instrument))
+    } else {
+        None
+    };
+
+    // While frozen, the chord, wavetable and detune are kept, ignoring both
+    // knobs and CV. Only settings of the configuration menu, gate and trigger
+    // are followed, though the trigger does not generate new chords.
+    if controls.frozen() {
+        reconcile_settings(controls, instrument);
+        if triggered
+            && controls.chord_generator().is_none()
+            && controls.chord_progression().is_none()
+            && controls.transformations().is_none()
+        {
+            instrument.trigger();
+        }
+
+        let mut actions = [None; 18];
+        actions[0] = freeze_action;
+        return actions;
+    }
+
+    let [step_action, step_root_action, step_degrees_action, step_style_action, step_mode_action] =
+        reconcile_sequencer(controls, instrument, triggered);
+    let sequenced_step = controls.sequenced_step();
+    let sequenced = sequenced_step.is_some();
+    let sequenced_scale_mode = sequenced_step.and_then(|step| step.scale_mode()).is_some();
+
+    let new_chord_root_degree = if sequenced {
+        None
+    } else if controls.note_from_pot() {
         instrument.set_chord_root_linear(controls.note())
     } else {
         instrument.set_chord_root_voct(controls.note())
@@ -344,9 +398,19 @@ fn reconcile_all_changes(
         None
     };
 
-    let new_degrees = instrument.set_chord_degrees(controls.chord());
+    let chord_generator = controls.chord_generator();
+    instrument.set_chord_generator(chord_generator);
+    instrument.set_chord_progression(controls.chord_progression());
+    let transformations = controls.transformations();
+    instrument.set_transformations(transformations);
+
+    let new_degrees = if sequenced || chord_generator.is_some() || transformations.is_some() {
+        None
+    } else {
+        instrument.set_chord_degrees(controls.chord())
+    };
     let degrees_action = if let Some(new_degrees) = new_degrees {
-        Some(DisplayAction::SetChord(new_degrees))
+        Some(chord_degrees_action(instrument, new_degrees))
     } else {
         None
     };
@@ -369,17 +433,29 @@ fn reconcile_all_changes(
         None
     };
 
-    let new_scale_mode = instrument.set_scale_mode(
-        controls.scale_mode(),
-        controls.modes_ordered_by_brightness(),
-    );
+    for (index, mask) in controls.user_scales().iter().enumerate() {
+        instrument.set_user_scale(index, *mask);
+    }
+
+    let new_scale_mode = if sequenced_scale_mode {
+        None
+    } else {
+        instrument.set_scale_mode(
+            controls.scale_mode(),
+            controls.modes_ordered_by_brightness(),
+        )
+    };
     let scale_mode_action = if let Some(new_scale_mode) = new_scale_mode {
         Some(DisplayAction::SetScaleMode(new_scale_mode))
     } else {
         None
     };
 
-    let new_wavetable_bank = instrument.set_wavetable_bank(controls.wavetable_bank());
+    let new_wavetable_bank = if let Some(wavetable_bank) = controls.continuous_wavetable_bank() {
+        instrument.set_wavetable_bank_continuous(wavetable_bank)
+    } else {
+        instrument.set_wavetable_bank(controls.wavetable_bank())
+    };
     let wavetable_bank_action = if let Some(new_wavetable_bank) = new_wavetable_bank {
         Some(DisplayAction::SetWavetableBank(new_wavetable_bank))
     } else {
@@ -393,13 +469,31 @@ fn reconcile_all_changes(
         None
     };
 
-    let new_style = instrument.set_style(controls.style());
+    let new_style = if sequenced {
+        None
+    } else {
+        instrument.set_style(controls.style())
+    };
     let style_action = if let Some(new_style) = new_style {
         Some(DisplayAction::SetStyle(new_style))
     } else {
         None
     };
 
+    let new_inversion = instrument.set_inversion(controls.inversion());
+    let inversion_action = if let Some(new_inversion) = new_inversion {
+        Some(DisplayAction::SetInversion(new_inversion))
+    } else {
+        None
+    };
+
+    let new_voicing = instrument.set_voicing(controls.voicing());
+    let voicing_action = if let Some(new_voicing) = new_voicing {
+        Some(DisplayAction::SetVoicing(new_voicing))
+    } else {
+        None
+    };
+
     let new_detune = instrument.set_detune(controls.detune());
     let detune_action = if let Some((new_detune_index, new_detune_phase)) = new_detune {
         Some(DisplayAction::SetDetune(new_detune_index, new_detune_phase))
@@ -407,9 +501,30 @@ fn reconcile_all_changes(
         None
     };
 
-    instrument.set_overdrive(controls.overdrive());
+    reconcile_settings(controls, instrument);
+
+    // Generated chords and progressions may move the chord root on trigger.
+    let trigger_action = if triggered {
+        let chord_root_degree = instrument.chord_root_degree();
+        instrument.trigger();
+        let new_chord_root_degree = instrument.chord_root_degree();
+        if new_chord_root_degree != chord_root_degree {
+            Some(DisplayAction::SetChordRootDegree(new_chord_root_degree))
+        } else {
+            None
+        }
+    } else {
+        None
+    };
 
     [
+        freeze_action,
+        trigger_action,
+        step_action,
+        step_root_action,
+        step_degrees_action,
+        step_style_action,
+        step_mode_action,
         chord_root_action,
         degrees_action,
         solo_action,
@@ -418,17 +533,132 @@ fn reconcile_all_changes(
         wavetable_bank_action,
         wavetable_action,
         style_action,
+        inversion_action,
+        voicing_action,
         detune_action,
     ]
 }
 
+fn reconcile_settings(controls: &mut Controls, instrument: &mut Instrument) {
+    instrument.set_overdrive(controls.overdrive());
+    instrument.set_just_intonation(controls.just_intonation());
+    instrument.set_quantization(controls.quantization());
+    instrument.set_temperament(controls.temperament());
+    instrument.set_amplitude_mode(controls.amplitude_mode());
+    instrument.set_glide(controls.glide());
+    instrument.set_voice_matching(controls.voice_matching());
+    instrument.set_voice_leading(controls.voice_leading());
+    instrument.set_strum(controls.strum());
+    instrument.set_strum_order(controls.strum_order());
+    instrument.set_warp(controls.warp_mode(), controls.warp());
+    instrument.set_hard_sync(controls.hard_sync());
+    instrument.set_frequency_modulation(controls.frequency_modulation());
+    instrument.set_cross_modulation(
+        controls.cross_modulation(),
+        controls.cross_modulation_amount(),
+    );
+    instrument.set_gate(controls.gate());
+    instrument.set_attack(controls.attack());
+    instrument.set_decay(controls.decay());
+    instrument.set_sustain(controls.sustain());
+    instrument.set_release(controls.release());
+}
+
+// The chord is displayed by default. The sharp LED is lit while frozen.
+fn chord_display_action(controls: &Controls, instrument: &Instrument) -> DisplayAction {
+    let chord_degrees = instrument.chord_degrees();
+    if controls.frozen() {
+        DisplayAction::SetFrozenChord(chord_degrees)
+    } else {
+        chord_degrees_action(instrument, chord_degrees)
+    }
+}
+
+// Chords selected by their quality are displayed by their pitch classes.
+fn chord_degrees_action(instrument: &Instrument, chord_degrees: [i8; 5]) -> DisplayAction {
+    if let Some(mask) = instrument.chord_quality() {
+        DisplayAction::SetChordQuality(mask)
+    } else {
+        DisplayAction::SetChord(chord_degrees)
+    }
+}
+
+// Short click of the button records the current chord as a new step of the
+// sequence, trigger moves to the next step. The chord of the playing step is
+// recalled on the instrument.
+fn reconcile_sequencer(
+    controls: &mut Controls,
+    instrument: &mut Instrument,
+    triggered: bool,
+) -> [Option<DisplayAction>; 5] {
+    let mut position = None;
+    if controls.record_requested() {
+        position = controls.record_step(instrument.step(controls.sequence_scale_mode()));
+    }
+    if triggered {
+        position = controls.advance_sequence().or(position);
+    }
+    let step_action = position.map(DisplayAction::SetSequencerStep);
+
+    let step = if let Some(step) = controls.changed_sequenced_step() {
+        step
+    } else {
+        return [step_action, None, None, None, None];
+    };
+
+    let original_chord_root_degree = instrument.chord_root_degree();
+    let original_degrees = instrument.chord_degrees();
+    let original_quality = instrument.chord_quality();
+    let original_style = instrument.style();
+    let original_scale_mode = instrument.scale_mode();
+
+    instrument.set_step(step);
+
+    let chord_root_degree = instrument.chord_root_degree();
+    let chord_root_action = if chord_root_degree != original_chord_root_degree {
+        Some(DisplayAction::SetChordRootDegree(chord_root_degree))
+    } else {
+        None
+    };
+
+    let degrees = instrument.chord_degrees();
+    let degrees_action =
+        if degrees != original_degrees || instrument.chord_quality() != original_quality {
+            Some(chord_degrees_action(instrument, degrees))
+        } else {
+            None
+        };
+
+    let style = instrument.style();
+    let style_action = if style != original_style {
+        Some(DisplayAction::SetStyle(style))
+    } else {
+        None
+    };
+
+    let scale_mode = instrument.scale_mode();
+    let scale_mode_action = if scale_mode != original_scale_mode {
+        Some(DisplayAction::SetScaleMode(scale_mode))
+    } else {
+        None
+    };
+
+    [
+        step_action,
+        chord_root_action,
+        degrees_action,
+        style_action,
+        scale_mode_action,
+    ]
+}
+
 fn reconcile_pot_activity(
     controls: &mut Controls,
     instrument: &mut Instrument,
 ) -> [Option<DisplayAction>; 8] {
     let chord_action = if controls.chord_pot_active() {
         let chord_degrees = instrument.chord_degrees();
-        Some(DisplayAction::SetChord(chord_degrees))
+        Some(chord_degrees_action(instrument, chord_degrees))
     } else {
         None
     };
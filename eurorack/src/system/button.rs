@@ -5,14 +5,21 @@ use super::debounce_buffer::DebounceBuffer;
 use crate::system::hal::hal::digital::v2::InputPin;
 
 const LONG_CLICK: u32 = 5 * crate::SECOND;
+const DOUBLE_CLICK: u32 = crate::SECOND / 3;
 
 pub struct Button<P> {
     pin: P,
     debounce_filter: DebounceBuffer<5>,
     active_for: Option<Duration>,
     active_timestamp: Option<Instant>,
+    pressed: bool,
     clicked: bool,
+    released: bool,
     long_clicked: bool,
+    last_release: Option<Instant>,
+    second_press: bool,
+    single_clicked: bool,
+    double_clicked: bool,
 }
 
 impl<P: InputPin> Button<P> {
@@ -22,8 +29,14 @@ impl<P: InputPin> Button<P> {
             debounce_filter: DebounceBuffer::new(),
             active_for: None,
             active_timestamp: None,
+            pressed: false,
             clicked: false,
+            released: false,
             long_clicked: false,
+            last_release: None,
+            second_press: false,
+            single_clicked: false,
+            double_clicked: false,
         }
     }
 
@@ -33,6 +46,9 @@ impl<P: InputPin> Button<P> {
         let was_active = self.active_for.is_some();
 
         self.clicked = !was_active && is_active;
+        self.released = self.pressed && !is_active;
+        let pressed = !self.pressed && is_active;
+        self.pressed = is_active;
 
         self.long_clicked = false;
         if is_active {
@@ -57,6 +73,37 @@ impl<P: InputPin> Button<P> {
             }
             self.long_click_reset();
         }
+
+        self.reconcile_double_click(pressed);
+    }
+
+    // A press that follows a release within the double click interval makes a
+    // double click. A release that is not followed by one makes a single click
+    // once the interval passes. Releases of long clicks make neither.
+    fn reconcile_double_click(&mut self, pressed: bool) {
+        let now = Instant::now();
+        let within_interval =
+            |release: Instant| now.duration_since(release) < DOUBLE_CLICK.cycles();
+
+        self.single_clicked = false;
+        self.double_clicked = false;
+
+        if pressed {
+            self.double_clicked =
+                matches!(self.last_release, Some(release) if within_interval(release));
+            self.second_press = self.double_clicked;
+            self.last_release = None;
+        } else if self.released {
+            if !self.second_press && !self.long_clicked {
+                self.last_release = Some(now);
+            }
+            self.second_press = false;
+        } else if let Some(release) = self.last_release {
+            if !within_interval(release) {
+                self.single_clicked = true;
+                self.last_release = None;
+            }
+        }
     }
 
     pub fn active(&self) -> bool {
@@ -71,6 +118,18 @@ impl<P: InputPin> Button<P> {
         self.clicked
     }
 
+    pub fn released(&self) -> bool {
+        self.released
+    }
+
+    pub fn single_clicked(&self) -> bool {
+        self.single_clicked
+    }
+
+    pub fn double_clicked(&self) -> bool {
+        self.double_clicked
+    }
+
     pub fn long_clicked(&self) -> bool {
         self.long_clicked
     }
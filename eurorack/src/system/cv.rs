@@ -10,6 +10,11 @@ use achordion_lib::probe::{ProbeDetector, PROBE_SEQUENCE};
 
 const PROBE_SEQUENCE_LEN: usize = PROBE_SEQUENCE.len();
 
+// Gate is detected with a hysteresis, so a noisy signal around the threshold
+// does not produce multiple triggers.
+const GATE_HIGH_VOLTAGE: f32 = 1.0;
+const GATE_LOW_VOLTAGE: f32 = 0.5;
+
 pub struct Cv<A, P> {
     pin: P,
     probe_detector: ProbeDetector<'static, PROBE_SEQUENCE_LEN>,
@@ -17,6 +22,8 @@ pub struct Cv<A, P> {
     input_range: (f32, f32),
     was_plugged: bool,
     was_unplugged: bool,
+    gate: bool,
+    was_triggered: bool,
     _adc: PhantomData<A>,
 }
 
@@ -31,6 +38,8 @@ macro_rules! cv {
                     input_range,
                     was_plugged: false,
                     was_unplugged: false,
+                    gate: false,
+                    was_triggered: false,
                     _adc: PhantomData,
                 }
             }
@@ -58,6 +67,15 @@ macro_rules! cv {
                 let is_connected = self.connected();
                 self.was_plugged = !was_connected && is_connected;
                 self.was_unplugged = was_connected && !is_connected;
+
+                let was_gate = self.gate;
+                let voltage = to_voltage(self.value, self.input_range);
+                self.gate = if was_gate {
+                    voltage > GATE_LOW_VOLTAGE
+                } else {
+                    voltage > GATE_HIGH_VOLTAGE
+                };
+                self.was_triggered = !was_gate && self.gate;
             }
 
             pub fn connected(&mut self) -> bool {
@@ -75,6 +93,14 @@ macro_rules! cv {
             pub fn was_unplugged(&self) -> bool {
                 self.was_unplugged
             }
+
+            pub fn gate(&self) -> bool {
+                self.gate
+            }
+
+            pub fn was_triggered(&self) -> bool {
+                self.was_triggered
+            }
         }
     };
 }
@@ -86,6 +112,10 @@ fn transpose_adc(sample: f32, slope: u32) -> f32 {
     (slope as f32 - sample) / slope as f32
 }
 
+fn to_voltage(value: f32, input_range: (f32, f32)) -> f32 {
+    input_range.0 + value * (input_range.1 - input_range.0)
+}
+
 fn is_high(sample: u32, slope: u32, input_range: (f32, f32)) -> bool {
     // Calculate what does center of probe voltage translates to the range of
     // the input.
@@ -57,6 +57,14 @@ macro_rules! pot {
                 self.movement_detector.write(transposed_sample);
             }
 
+            // Raw reading that bypasses the filters, so its lowest bits keep
+            // the noise of the converter.
+            #[inline(never)]
+            pub fn raw_sample(&mut self, adc: &mut Adc<$adc, Enabled>) -> u32 {
+                adc.start_conversion(&mut self.pin);
+                block!(adc.read_sample()).unwrap()
+            }
+
             pub fn value(&self) -> f32 {
                 self.position_filter.read()
             }
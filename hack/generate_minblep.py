@@ -0,0 +1,95 @@
+#!/usr/bin/env python
+
+import cmath
+import math
+
+ZERO_CROSSINGS = 8
+OVERSAMPLING = 32
+FFT_SIZE = 4096
+OUTPUT = 'lib/src/minblep.rs'
+
+
+def _fft(x, inverse=False):
+    n = len(x)
+    if n == 1:
+        return list(x)
+    sign = 1 if inverse else -1
+    even = _fft(x[0::2], inverse)
+    odd = _fft(x[1::2], inverse)
+    result = [0j] * n
+    for k in range(n // 2):
+        t = cmath.exp(sign * 2j * math.pi * k / n) * odd[k]
+        result[k] = even[k] + t
+        result[k + n // 2] = even[k] - t
+    return result
+
+
+def _ifft(x):
+    return [v / len(x) for v in _fft(x, inverse=True)]
+
+
+def _windowed_sinc():
+    size = 2 * ZERO_CROSSINGS * OVERSAMPLING + 1
+    sinc = []
+    for i in range(size):
+        t = (i / OVERSAMPLING) - ZERO_CROSSINGS
+        value = 1.0 if t == 0 else math.sin(math.pi * t) / (math.pi * t)
+        window = (
+            0.42
+            - 0.5 * math.cos(2 * math.pi * i / (size - 1))
+            + 0.08 * math.cos(4 * math.pi * i / (size - 1))
+        )
+        sinc.append(value * window)
+    return sinc
+
+
+# Folds the real cepstrum of the impulse, keeping its magnitude response while
+# moving all of its energy to the start.
+def _minimum_phase(impulse):
+    padded = [complex(v) for v in impulse] + [0j] * (FFT_SIZE - len(impulse))
+    spectrum = _fft(padded)
+    cepstrum = _ifft([complex(math.log(max(abs(v), 1e-20))) for v in spectrum])
+
+    folded = [0j] * FFT_SIZE
+    folded[0] = cepstrum[0]
+    for i in range(1, FFT_SIZE // 2):
+        folded[i] = 2 * cepstrum[i]
+    folded[FFT_SIZE // 2] = cepstrum[FFT_SIZE // 2]
+
+    spectrum = [cmath.exp(v) for v in _fft(folded)]
+    return [v.real for v in _ifft(spectrum)[:len(impulse)]]
+
+
+def _residual():
+    impulse = _minimum_phase(_windowed_sinc())
+
+    step = []
+    total = 0.0
+    for value in impulse:
+        total += value
+        step.append(total)
+
+    length = 2 * ZERO_CROSSINGS * OVERSAMPLING
+    return [v / total - 1.0 for v in step[:length]] + [0.0]
+
+
+def _format(value):
+    return f'{value:.6f}'.replace('-0.000000', '0.000000')
+
+
+def _save(residual):
+    with open(OUTPUT, 'w') as f:
+        f.write('// Generated by hack/generate_minblep.py, do not edit.\n\n')
+        f.write(f'pub const LENGTH: usize = {2 * ZERO_CROSSINGS};\n')
+        f.write(f'pub const OVERSAMPLING: usize = {OVERSAMPLING};\n\n')
+        f.write('// Difference between a band-limited step and an ideal one.\n')
+        f.write('#[rustfmt::skip]\n')
+        f.write('pub const RESIDUAL: [f32; LENGTH * OVERSAMPLING + 1] = [\n')
+        for i in range(0, len(residual), 8):
+            chunk = ', '.join(_format(v) for v in residual[i:i + 8])
+            f.write(f'    {chunk},\n')
+        f.write('];\n')
+
+
+if __name__ == '__main__':
+    _save(_residual())
@@ -0,0 +1,308 @@
+use crate::note::Note;
+use crate::random;
+
+pub const MAX_OCTAVES: u8 = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Order {
+    Up,
+    Down,
+    UpDown,
+    Random,
+    AsListed,
+}
+
+// Index of the chord tone that should be sounding and the number of octaves
+// it should be shifted up by.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Step {
+    pub index: usize,
+    pub octave: u8,
+}
+
+// The arpeggiator does not keep the chord itself. It only counts received
+// triggers and maps them on whatever chord is currently playing. This way the
+// chord can change in the middle of the pattern without resetting it.
+pub struct Arpeggiator {
+    order: Order,
+    octaves: u8,
+    tick: usize,
+    random: random::Random,
+    random_value: u32,
+}
+
+impl Arpeggiator {
+    pub fn new() -> Self {
+        let mut random = random::Random::new(0);
+        let random_value = random.next_u32();
+        Self {
+            order: Order::Up,
+            octaves: 1,
+            tick: 0,
+            random,
+            random_value,
+        }
+    }
+
+    pub fn set_order(&mut self, order: Order) {
+        self.order = order;
+    }
+
+    pub fn set_octaves(&mut self, octaves: u8) {
+        self.octaves = octaves.clamp(1, MAX_OCTAVES);
+    }
+
+    pub fn reset(&mut self) {
+        self.tick = 0;
+    }
+
+    pub fn trigger(&mut self) {
+        self.tick = self.tick.wrapping_add(1);
+        self.random_value = self.random.next_u32();
+    }
+
+    pub fn step<const D: usize>(&self, notes: [Option<Note>; D]) -> Option<Step> {
+        let mut indices = [0; D];
+        let mut len = 0;
+        for (i, note) in notes.iter().enumerate() {
+            if note.is_some() {
+                indices[len] = i;
+                len += 1;
+            }
+        }
+        if len == 0 {
+            return None;
+        }
+
+        let indices = &mut indices[..len];
+        if !matches!(self.order, Order::AsListed) {
+            // Index is part of the key to keep the order of unisons stable
+            indices.sort_unstable_by_key(|i| (notes[*i], *i));
+        }
+
+        let steps = len * self.octaves as usize;
+        let position = match self.order {
+            Order::Up | Order::AsListed => self.tick % steps,
+            Order::Down => steps - 1 - self.tick % steps,
+            Order::UpDown => {
+                if steps == 1 {
+                    0
+                } else {
+                    // Do not repeat the top and the bottom tone
+                    let period = 2 * (steps - 1);
+                    let position = self.tick % period;
+                    if position < steps {
+                        position
+                    } else {
+                        period - position
+                    }
+                }
+            }
+            Order::Random => self.random_value as usize % steps,
+        };
+
+        Some(Step {
+            index: indices[position % len],
+            octave: (position / len) as u8,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRIAD: [Option<Note>; 5] = [Some(Note::C4), Some(Note::E4), Some(Note::G4), None, None];
+
+    fn collect_steps<const D: usize>(
+        arpeggiator: &mut Arpeggiator,
+        notes: [Option<Note>; D],
+        count: usize,
+    ) -> [(usize, u8); 8] {
+        let mut steps = [(0, 0); 8];
+        for step in steps.iter_mut().take(count) {
+            let current = arpeggiator.step(notes).unwrap();
+            *step = (current.index, current.octave);
+            arpeggiator.trigger();
+        }
+        steps
+    }
+
+    #[test]
+    fn initialize() {
+        let _arpeggiator = Arpeggiator::new();
+    }
+
+    #[test]
+    fn play_up() {
+        let mut arpeggiator = Arpeggiator::new();
+        arpeggiator.set_order(Order::Up);
+
+        let steps = collect_steps(&mut arpeggiator, TRIAD, 4);
+
+        assert_eq!(steps[..4], [(0, 0), (1, 0), (2, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn play_down() {
+        let mut arpeggiator = Arpeggiator::new();
+        arpeggiator.set_order(Order::Down);
+
+        let steps = collect_steps(&mut arpeggiator, TRIAD, 4);
+
+        assert_eq!(steps[..4], [(2, 0), (1, 0), (0, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn play_up_down_without_repeating_edges() {
+        let mut arpeggiator = Arpeggiator::new();
+        arpeggiator.set_order(Order::UpDown);
+
+        let steps = collect_steps(&mut arpeggiator, TRIAD, 6);
+
+        assert_eq!(steps[..6], [(0, 0), (1, 0), (2, 0), (1, 0), (0, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn play_up_down_with_single_tone() {
+        let mut arpeggiator = Arpeggiator::new();
+        arpeggiator.set_order(Order::UpDown);
+
+        let steps = collect_steps(&mut arpeggiator, [Some(Note::C4), None], 3);
+
+        assert_eq!(steps[..3], [(0, 0), (0, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn play_up_sorted_by_pitch() {
+        let mut arpeggiator = Arpeggiator::new();
+        arpeggiator.set_order(Order::Up);
+        let notes = [Some(Note::C4), Some(Note::G3), Some(Note::E4)];
+
+        let steps = collect_steps(&mut arpeggiator, notes, 3);
+
+        assert_eq!(steps[..3], [(1, 0), (0, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn play_as_listed() {
+        let mut arpeggiator = Arpeggiator::new();
+        arpeggiator.set_order(Order::AsListed);
+        let notes = [Some(Note::C4), Some(Note::G3), Some(Note::E4)];
+
+        let steps = collect_steps(&mut arpeggiator, notes, 4);
+
+        assert_eq!(steps[..4], [(0, 0), (1, 0), (2, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn play_random_within_chord() {
+        let mut arpeggiator = Arpeggiator::new();
+        arpeggiator.set_order(Order::Random);
+        arpeggiator.set_octaves(2);
+
+        let mut visited = [[false; 2]; 3];
+        for _ in 0..100 {
+            let step = arpeggiator.step(TRIAD).unwrap();
+            assert!(step.index < 3);
+            assert!(step.octave < 2);
+            visited[step.index][step.octave as usize] = true;
+            arpeggiator.trigger();
+        }
+
+        assert!(visited.iter().flatten().all(|v| *v));
+    }
+
+    #[test]
+    fn random_order_is_stable_between_triggers() {
+        let mut arpeggiator = Arpeggiator::new();
+        arpeggiator.set_order(Order::Random);
+
+        for _ in 0..10 {
+            assert_eq!(arpeggiator.step(TRIAD), arpeggiator.step(TRIAD));
+            arpeggiator.trigger();
+        }
+    }
+
+    #[test]
+    fn play_up_over_multiple_octaves() {
+        let mut arpeggiator = Arpeggiator::new();
+        arpeggiator.set_order(Order::Up);
+        arpeggiator.set_octaves(2);
+
+        let steps = collect_steps(&mut arpeggiator, TRIAD, 7);
+
+        assert_eq!(
+            steps[..7],
+            [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1), (0, 0)]
+        );
+    }
+
+    #[test]
+    fn play_down_over_multiple_octaves() {
+        let mut arpeggiator = Arpeggiator::new();
+        arpeggiator.set_order(Order::Down);
+        arpeggiator.set_octaves(2);
+
+        let steps = collect_steps(&mut arpeggiator, TRIAD, 4);
+
+        assert_eq!(steps[..4], [(2, 1), (1, 1), (0, 1), (2, 0)]);
+    }
+
+    #[test]
+    fn skip_disabled_tones() {
+        let mut arpeggiator = Arpeggiator::new();
+        arpeggiator.set_order(Order::Up);
+        let notes = [Some(Note::C4), None, Some(Note::G4), None, Some(Note::B4)];
+
+        let steps = collect_steps(&mut arpeggiator, notes, 4);
+
+        assert_eq!(steps[..4], [(0, 0), (2, 0), (4, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn keep_position_when_chord_changes() {
+        let mut arpeggiator = Arpeggiator::new();
+        arpeggiator.set_order(Order::Up);
+
+        arpeggiator.trigger();
+        assert_eq!(arpeggiator.step(TRIAD).unwrap().index, 1);
+
+        let seventh = [
+            Some(Note::C4),
+            Some(Note::E4),
+            Some(Note::G4),
+            Some(Note::B4),
+        ];
+        assert_eq!(arpeggiator.step(seventh).unwrap().index, 1);
+    }
+
+    #[test]
+    fn restart_after_reset() {
+        let mut arpeggiator = Arpeggiator::new();
+        arpeggiator.set_order(Order::Up);
+
+        arpeggiator.trigger();
+        arpeggiator.reset();
+
+        assert_eq!(arpeggiator.step(TRIAD).unwrap().index, 0);
+    }
+
+    #[test]
+    fn no_step_without_tones() {
+        let arpeggiator = Arpeggiator::new();
+        assert!(arpeggiator.step::<3>([None, None, None]).is_none());
+    }
+
+    #[test]
+    fn octaves_are_kept_within_range() {
+        let mut arpeggiator = Arpeggiator::new();
+        arpeggiator.set_order(Order::Down);
+
+        arpeggiator.set_octaves(0);
+        assert_eq!(arpeggiator.step(TRIAD).unwrap().octave, 0);
+
+        arpeggiator.set_octaves(100);
+        assert_eq!(arpeggiator.step(TRIAD).unwrap().octave, MAX_OCTAVES - 1);
+    }
+}
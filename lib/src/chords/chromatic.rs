@@ -0,0 +1,206 @@
+use crate::chords::diatonic;
+use crate::note::Note;
+use crate::scales;
+use crate::scales::diatonic::Mode;
+use crate::scales::scale::Scale;
+use crate::tuning::table::Tuning;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Quality {
+    Major,
+    Minor,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Transformation {
+    // Keeps the root and the fifth, moving the third by a semitone.
+    Parallel,
+    // Moves the root of a major triad a semitone down, or the fifth of a minor
+    // triad a semitone up.
+    Leittonwechsel,
+    // Moves the fifth of a major triad a tone up, or the root of a minor triad
+    // a tone down.
+    Relative,
+}
+
+use Transformation::*;
+
+// Sequences applied one transformation per trigger. Alternating two of the
+// transformations walks through hexatonic (PL), octatonic (PR) and diatonic
+// (LR) cycles.
+pub const SEQUENCES: [&[Transformation]; 8] = [
+    &[Parallel],
+    &[Leittonwechsel],
+    &[Relative],
+    &[Parallel, Leittonwechsel],
+    &[Parallel, Relative],
+    &[Leittonwechsel, Relative],
+    &[Parallel, Leittonwechsel, Relative],
+    &[Relative, Leittonwechsel, Parallel],
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Triad {
+    root: Note,
+    quality: Quality,
+}
+
+impl Triad {
+    pub fn new(root: Note, quality: Quality) -> Self {
+        Self { root, quality }
+    }
+
+    // The quality is given by the third of the scale above the chord root.
+    // Scales without a major third on the given root produce minor triads.
+    pub fn diatonic(scale_root: Note, scale: Scale, root: Note) -> Self {
+        let quality = match scales::scale::lookup_degree(scale_root, scale, root, 3) {
+            Some(third) if third as i32 - root as i32 == 4 => Quality::Major,
+            _ => Quality::Minor,
+        };
+        Self::new(root, quality)
+    }
+
+    pub fn root(&self) -> Note {
+        self.root
+    }
+
+    // The new triad shares two tones with the original one. Triads that would
+    // fall out of the note range are kept.
+    pub fn transform(self, transformation: Transformation) -> Self {
+        let (offset, quality) = match (transformation, self.quality) {
+            (Parallel, Quality::Major) => (0, Quality::Minor),
+            (Parallel, Quality::Minor) => (0, Quality::Major),
+            (Leittonwechsel, Quality::Major) => (4, Quality::Minor),
+            (Leittonwechsel, Quality::Minor) => (-4, Quality::Major),
+            (Relative, Quality::Major) => (-3, Quality::Minor),
+            (Relative, Quality::Minor) => (3, Quality::Major),
+        };
+
+        match Note::try_from_i16(self.root as i16 + offset) {
+            Some(root) => Self::new(root, quality),
+            None => self,
+        }
+    }
+
+    // Cycles of transformations may lead the root away by octaves. This
+    // moves it back within a tritone from the given note.
+    pub fn near(self, note: Note) -> Self {
+        let distance = self.root as i16 - note as i16;
+        let folded = note as i16 + (distance + 6).rem_euclid(12) - 6;
+        match Note::try_from_i16(folded) {
+            Some(root) => Self::new(root, self.quality),
+            None => self,
+        }
+    }
+
+    // Degrees past the triad follow the parallel major or minor mode of the
+    // root.
+    fn scale(&self) -> Scale {
+        match self.quality {
+            Quality::Major => Mode::Ionian.scale(),
+            Quality::Minor => Mode::Aeolian.scale(),
+        }
+    }
+}
+
+pub fn build<const D: usize>(tuning: &Tuning, triad: Triad, degrees: [i8; D]) -> [Option<Note>; D] {
+    diatonic::build(tuning, triad.root, triad.scale(), triad.root, degrees)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    lazy_static! {
+        static ref TUNING: Tuning = Tuning::equal_temperament();
+    }
+
+    fn notes(triad: Triad) -> [Option<Note>; 3] {
+        build(&TUNING, triad, [1, 3, 5])
+    }
+
+    #[test]
+    fn parallel_swaps_major_and_minor() {
+        let c_major = Triad::new(Note::C4, Quality::Major);
+        let c_minor = c_major.transform(Parallel);
+        assert_eq!(
+            notes(c_minor),
+            [Some(Note::C4), Some(Note::Eb4), Some(Note::G4)]
+        );
+        assert_eq!(c_minor.transform(Parallel), c_major);
+    }
+
+    #[test]
+    fn leittonwechsel_moves_major_to_minor_on_third() {
+        let c_major = Triad::new(Note::C4, Quality::Major);
+        let e_minor = c_major.transform(Leittonwechsel);
+        assert_eq!(
+            notes(e_minor),
+            [Some(Note::E4), Some(Note::G4), Some(Note::B4)]
+        );
+        assert_eq!(e_minor.transform(Leittonwechsel), c_major);
+    }
+
+    #[test]
+    fn relative_moves_major_to_minor_on_sixth() {
+        let c_major = Triad::new(Note::C4, Quality::Major);
+        let a_minor = c_major.transform(Relative);
+        assert_eq!(
+            notes(a_minor),
+            [Some(Note::A3), Some(Note::C4), Some(Note::E4)]
+        );
+        assert_eq!(a_minor.transform(Relative), c_major);
+    }
+
+    #[test]
+    fn leave_diatonic_mode_through_cycle() {
+        let mut triad = Triad::new(Note::C4, Quality::Major);
+        let mut roots = [Note::C4; 6];
+        for (i, root) in roots.iter_mut().enumerate() {
+            triad = triad.transform(SEQUENCES[3][i % 2]);
+            *root = triad.root();
+        }
+        assert_eq!(
+            roots,
+            [Note::C4, Note::Ab3, Note::Ab3, Note::E3, Note::E3, Note::C3]
+        );
+        assert_eq!(triad.quality, Quality::Major);
+    }
+
+    #[test]
+    fn fold_root_close_to_note() {
+        let triad = Triad::new(Note::C3, Quality::Minor);
+        assert_eq!(triad.near(Note::C4).root(), Note::C4);
+        assert_eq!(triad.near(Note::A4).root(), Note::C5);
+        assert_eq!(triad.near(Note::Gb4).root(), Note::C4);
+        assert_eq!(triad.near(Note::C4).quality, Quality::Minor);
+    }
+
+    #[test]
+    fn derive_quality_from_scale() {
+        let scale = Mode::Ionian.scale();
+        assert_eq!(
+            Triad::diatonic(Note::C3, scale, Note::G4).quality,
+            Quality::Major
+        );
+        assert_eq!(
+            Triad::diatonic(Note::C3, scale, Note::D4).quality,
+            Quality::Minor
+        );
+    }
+
+    #[test]
+    fn extend_triad_by_parallel_mode() {
+        let a_minor = Triad::new(Note::A3, Quality::Minor);
+        assert_eq!(
+            build(&TUNING, a_minor, [1, 7]),
+            [Some(Note::A3), Some(Note::G4)]
+        );
+    }
+
+    #[test]
+    fn keep_triad_at_the_edge_of_range() {
+        let triad = Triad::new(Note::CMinus1, Quality::Major);
+        assert_eq!(triad.transform(Relative), triad);
+    }
+}
@@ -1,10 +1,14 @@
 use crate::note::Note;
 use crate::scales;
-use crate::scales::diatonic::Mode;
+use crate::scales::scale::Scale;
+use crate::tuning::table::Tuning;
 
+// Notes that are not mapped in the tuning are snapped to their closest
+// mapped neighbour.
 pub fn build<const D: usize>(
+    tuning: &Tuning,
     scale_root: Note,
-    scale_mode: Mode,
+    scale: Scale,
     chord_root: Note,
     degrees: [i8; D],
 ) -> [Option<Note>; D] {
@@ -16,8 +20,8 @@ pub fn build<const D: usize>(
             continue;
         }
 
-        notes[i] =
-            scales::diatonic::lookup_degree(scale_root, scale_mode, chord_root, *degree as i32);
+        notes[i] = scales::scale::lookup_degree(scale_root, scale, chord_root, *degree as i32)
+            .map(|note| tuning.snap(note));
     }
 
     notes
@@ -28,10 +32,17 @@ mod tests {
     use super::*;
 
     use crate::scales::diatonic::Mode::*;
+    use crate::scales::library::MAJOR_PENTATONIC;
+
+    const TWELVE_TET: &str = "12-TET\n12\n100.0\n200.0\n300.0\n400.0\n500.0\n600.0\n700.0\n800.0\n900.0\n1000.0\n1100.0\n2/1\n";
+
+    lazy_static! {
+        static ref TUNING: Tuning = Tuning::equal_temperament();
+    }
 
     #[test]
     fn build_major_triad_on_the_first_degree() {
-        let notes = build(Note::C3, Ionian, Note::C4, [1, 3, 5]);
+        let notes = build(&TUNING, Note::C3, Ionian.scale(), Note::C4, [1, 3, 5]);
 
         assert_eq!(notes[0], Some(Note::C4));
         assert_eq!(notes[1], Some(Note::E4));
@@ -40,7 +51,7 @@ mod tests {
 
     #[test]
     fn build_minor_triad_on_the_second_degree() {
-        let notes = build(Note::C3, Ionian, Note::D4, [1, 3, 5]);
+        let notes = build(&TUNING, Note::C3, Ionian.scale(), Note::D4, [1, 3, 5]);
 
         assert_eq!(notes[0], Some(Note::D4));
         assert_eq!(notes[1], Some(Note::F4));
@@ -49,7 +60,7 @@ mod tests {
 
     #[test]
     fn build_chord_that_overflows_note_range() {
-        let notes = build(Note::C9, Ionian, Note::G9, [1, 3, 5]);
+        let notes = build(&TUNING, Note::C9, Ionian.scale(), Note::G9, [1, 3, 5]);
 
         assert_eq!(notes[0], Some(Note::G9));
         assert_eq!(notes[1], None);
@@ -58,7 +69,7 @@ mod tests {
 
     #[test]
     fn build_chord_with_disabled_degree() {
-        let notes = build(Note::C3, Ionian, Note::C4, [1, 0, 5]);
+        let notes = build(&TUNING, Note::C3, Ionian.scale(), Note::C4, [1, 0, 5]);
 
         assert_eq!(notes[0], Some(Note::C4));
         assert_eq!(notes[1], None);
@@ -67,7 +78,13 @@ mod tests {
 
     #[test]
     fn build_chord_over_multiple_octaves() {
-        let notes = build(Note::C3, Ionian, Note::C4, [1, 7 + 3, 2 * 7 + 5]);
+        let notes = build(
+            &TUNING,
+            Note::C3,
+            Ionian.scale(),
+            Note::C4,
+            [1, 7 + 3, 2 * 7 + 5],
+        );
 
         assert_eq!(notes[0], Some(Note::C4));
         assert_eq!(notes[1], Some(Note::E5));
@@ -76,16 +93,38 @@ mod tests {
 
     #[test]
     fn build_chord_with_negative_degrees() {
-        let notes = build(Note::C3, Ionian, Note::C4, [1, -1, -2]);
+        let notes = build(&TUNING, Note::C3, Ionian.scale(), Note::C4, [1, -1, -2]);
 
         assert_eq!(notes[0], Some(Note::C4));
         assert_eq!(notes[1], Some(Note::C4));
         assert_eq!(notes[2], Some(Note::B3));
 
-        let notes = build(Note::C3, Ionian, Note::C4, [-7, -8, -9]);
+        let notes = build(&TUNING, Note::C3, Ionian.scale(), Note::C4, [-7, -8, -9]);
 
         assert_eq!(notes[0], Some(Note::D3));
         assert_eq!(notes[1], Some(Note::C3));
         assert_eq!(notes[2], Some(Note::B2));
     }
+
+    #[test]
+    fn build_chord_snapping_unmapped_notes() {
+        // Only white keys are mapped
+        let kbm = "12\n0\n127\n60\n60\n261.63\n12\n0\nx\n2\nx\n4\n5\nx\n7\nx\n9\nx\n11\n";
+        let tuning = Tuning::parse(TWELVE_TET, Some(kbm)).unwrap();
+
+        let notes = build(&tuning, Note::D3, Ionian.scale(), Note::D4, [1, 3, 5]);
+
+        assert_eq!(notes[0], Some(Note::D4));
+        assert_eq!(notes[1], Some(Note::F4));
+        assert_eq!(notes[2], Some(Note::A4));
+    }
+
+    #[test]
+    fn build_triad_in_pentatonic() {
+        let notes = build(&TUNING, Note::C3, MAJOR_PENTATONIC, Note::D4, [1, 3, 5]);
+
+        assert_eq!(notes[0], Some(Note::D4));
+        assert_eq!(notes[1], Some(Note::G4));
+        assert_eq!(notes[2], Some(Note::C5));
+    }
 }
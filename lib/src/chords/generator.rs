@@ -0,0 +1,188 @@
+use crate::random::Random;
+use crate::scales::scale::{Scale, MAX_SIZE};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Function {
+    Tonic,
+    Subdominant,
+    Dominant,
+}
+
+// Weights of moving from one harmonic function (rows) to another (columns).
+// Progressions from tonic over subdominant to dominant and back to tonic are
+// favored, retrogressions are rare.
+const FUNCTION_WEIGHTS: [[f32; 3]; 3] = [
+    // Tonic, subdominant, dominant
+    [0.2, 1.0, 0.6],
+    [0.2, 0.3, 1.0],
+    [1.0, 0.1, 0.2],
+];
+
+// Roots on the first, fourth and fifth degree carry their function the
+// strongest, others serve as their substitutes.
+const SUBSTITUTE_WEIGHT: f32 = 0.4;
+
+// Staying on the same chord makes for a dull progression.
+const REPETITION_WEIGHT: f32 = 0.25;
+
+// Picks chords of a random progression. With no adventure, the choice follows
+// functional harmony closely. With full adventure, any degree and chord is
+// equally likely.
+pub struct Generator {
+    random: Random,
+    adventure: f32,
+}
+
+impl Generator {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            random: Random::new(seed),
+            adventure: 0.0,
+        }
+    }
+
+    pub fn set_adventure(&mut self, adventure: f32) {
+        self.adventure = adventure.clamp(0.0, 1.0);
+    }
+
+    // Degrees are given as zero-based indices within the scale.
+    pub fn next_degree(&mut self, scale: Scale, current: usize) -> usize {
+        let semitones = scale.semitones();
+        let current_function = function(semitones[current.min(semitones.len() - 1)]);
+
+        let mut weights = [0.0; MAX_SIZE];
+        for (i, weight) in weights[..semitones.len()].iter_mut().enumerate() {
+            let semitone = semitones[i];
+            let mut functional =
+                FUNCTION_WEIGHTS[current_function as usize][function(semitone) as usize];
+            if !matches!(semitone, 0 | 5 | 7) {
+                functional *= SUBSTITUTE_WEIGHT;
+            }
+            if i == current {
+                functional *= REPETITION_WEIGHT;
+            }
+            *weight = functional * (1.0 - self.adventure) + self.adventure;
+        }
+
+        self.pick(&weights[..semitones.len()])
+    }
+
+    // The chord is kept, unless adventure calls for a random one.
+    pub fn next_chord(&mut self, current: usize, chords: usize) -> usize {
+        if self.random.next_f32() < self.adventure {
+            self.random.next_u32() as usize % chords
+        } else {
+            current
+        }
+    }
+
+    fn pick(&mut self, weights: &[f32]) -> usize {
+        let total: f32 = weights.iter().sum();
+        let mut target = self.random.next_f32() * total;
+        for (i, weight) in weights.iter().enumerate() {
+            if target < *weight {
+                return i;
+            }
+            target -= weight;
+        }
+        weights.len() - 1
+    }
+}
+
+// Function of a chord is given by the distance of its root from the tonic,
+// so it applies to scales of any size.
+fn function(semitone: i8) -> Function {
+    match semitone {
+        0 | 3 | 4 | 8 | 9 => Function::Tonic,
+        1 | 2 | 5 | 6 => Function::Subdominant,
+        _ => Function::Dominant,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::scales::diatonic::Mode;
+
+    const MAJOR: Scale = Scale::new(&[0, 2, 4, 5, 7, 9, 11]);
+    const PENTATONIC: Scale = Scale::new(&[0, 2, 4, 7, 9]);
+
+    fn histogram(generator: &mut Generator, scale: Scale, current: usize) -> [usize; MAX_SIZE] {
+        let mut histogram = [0; MAX_SIZE];
+        for _ in 0..1000 {
+            histogram[generator.next_degree(scale, current)] += 1;
+        }
+        histogram
+    }
+
+    #[test]
+    fn same_seed_produces_same_progression() {
+        let mut generator_a = Generator::new(7);
+        let mut generator_b = Generator::new(7);
+        generator_a.set_adventure(0.5);
+        generator_b.set_adventure(0.5);
+
+        let mut degree_a = 0;
+        let mut degree_b = 0;
+        for _ in 0..100 {
+            degree_a = generator_a.next_degree(MAJOR, degree_a);
+            degree_b = generator_b.next_degree(MAJOR, degree_b);
+            assert_eq!(degree_a, degree_b);
+        }
+    }
+
+    #[test]
+    fn stay_within_the_scale() {
+        let mut generator = Generator::new(1);
+        generator.set_adventure(1.0);
+
+        let mut degree = 0;
+        for _ in 0..1000 {
+            degree = generator.next_degree(PENTATONIC, degree);
+            assert!(degree < PENTATONIC.size());
+        }
+    }
+
+    #[test]
+    fn resolve_dominant_to_tonic() {
+        let mut generator = Generator::new(1);
+        let histogram = histogram(&mut generator, MAJOR, 4);
+        let most_frequent = (0..7).max_by_key(|i| histogram[*i]).unwrap();
+        assert_eq!(most_frequent, 0);
+    }
+
+    #[test]
+    fn move_from_tonic_to_subdominant() {
+        let mut generator = Generator::new(1);
+        let histogram = histogram(&mut generator, MAJOR, 0);
+        let most_frequent = (0..7).max_by_key(|i| histogram[*i]).unwrap();
+        assert_eq!(most_frequent, 3);
+        assert!(histogram[0] < histogram[4]);
+    }
+
+    #[test]
+    fn spread_evenly_with_full_adventure() {
+        let mut generator = Generator::new(1);
+        generator.set_adventure(1.0);
+        let histogram = histogram(&mut generator, Mode::Aeolian.scale(), 4);
+        for count in &histogram[..7] {
+            assert!(*count > 100);
+            assert!(*count < 190);
+        }
+    }
+
+    #[test]
+    fn keep_chord_without_adventure() {
+        let mut generator = Generator::new(1);
+        for _ in 0..100 {
+            assert_eq!(generator.next_chord(3, 10), 3);
+        }
+
+        generator.set_adventure(1.0);
+        let changed = (0..100)
+            .filter(|_| generator.next_chord(3, 10) != 3)
+            .count();
+        assert!(changed > 50);
+    }
+}
@@ -0,0 +1,60 @@
+use crate::note::Note;
+
+// Pure ratios of intervals within an octave, indexed by their size in
+// semitones. Minor seventh is tuned to the harmonic seventh.
+const RATIOS: [f32; 12] = [
+    1.0,
+    16.0 / 15.0,
+    9.0 / 8.0,
+    6.0 / 5.0,
+    5.0 / 4.0,
+    4.0 / 3.0,
+    45.0 / 32.0,
+    3.0 / 2.0,
+    8.0 / 5.0,
+    5.0 / 3.0,
+    7.0 / 4.0,
+    15.0 / 8.0,
+];
+
+// Frequency ratio between the given note and the chord root, derived from the
+// size of their interval. Works for notes below the root too.
+pub fn ratio(chord_root: Note, note: Note) -> f32 {
+    let interval = note as i32 - chord_root as i32;
+    let octave = interval.div_euclid(12);
+    let ratio = RATIOS[interval.rem_euclid(12) as usize];
+    if octave >= 0 {
+        ratio * (1 << octave) as f32
+    } else {
+        ratio / (1 << -octave) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ratio_of_unison() {
+        assert_relative_eq!(ratio(Note::C4, Note::C4), 1.0);
+    }
+
+    #[test]
+    fn ratio_within_octave() {
+        assert_relative_eq!(ratio(Note::C4, Note::E4), 5.0 / 4.0);
+        assert_relative_eq!(ratio(Note::C4, Note::G4), 3.0 / 2.0);
+        assert_relative_eq!(ratio(Note::G4, Note::F5), 7.0 / 4.0);
+    }
+
+    #[test]
+    fn ratio_above_octave() {
+        assert_relative_eq!(ratio(Note::C4, Note::D5), 9.0 / 4.0);
+        assert_relative_eq!(ratio(Note::C4, Note::C6), 4.0);
+    }
+
+    #[test]
+    fn ratio_below_root() {
+        assert_relative_eq!(ratio(Note::C4, Note::G3), 3.0 / 4.0);
+        assert_relative_eq!(ratio(Note::C4, Note::C2), 1.0 / 4.0);
+    }
+}
@@ -1 +1,8 @@
+pub mod chromatic;
 pub mod diatonic;
+pub mod generator;
+pub mod just;
+pub mod progression;
+pub mod quality;
+pub mod voice_leading;
+pub mod voicing;
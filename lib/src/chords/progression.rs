@@ -0,0 +1,239 @@
+use crate::random::Random;
+
+pub const DEGREES: usize = 7;
+
+// Weights of moving from one degree of a heptatonic mode (rows) to another
+// (columns), both starting from the tonic. Only ratios between weights of a
+// row matter.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Table {
+    weights: [[u8; DEGREES]; DEGREES],
+}
+
+impl Table {
+    pub const fn new(weights: [[u8; DEGREES]; DEGREES]) -> Self {
+        Self { weights }
+    }
+}
+
+pub const MAJOR: Table = Table::new([
+    // I  II III IV  V  VI VII
+    [0, 2, 1, 4, 4, 3, 1],
+    [1, 0, 0, 1, 6, 0, 2],
+    [1, 1, 0, 4, 0, 4, 0],
+    [3, 2, 0, 0, 5, 0, 1],
+    [8, 0, 0, 1, 0, 2, 0],
+    [1, 4, 1, 4, 2, 0, 0],
+    [6, 0, 1, 0, 1, 0, 0],
+]);
+
+pub const MINOR: Table = Table::new([
+    // I  II III IV  V  VI VII
+    [0, 1, 2, 4, 3, 4, 3],
+    [1, 0, 0, 1, 5, 0, 2],
+    [1, 0, 0, 3, 1, 4, 2],
+    [3, 1, 1, 0, 4, 2, 3],
+    [6, 0, 0, 2, 0, 3, 1],
+    [2, 2, 2, 3, 2, 0, 4],
+    [4, 0, 4, 0, 1, 2, 0],
+]);
+
+pub const HARMONIC_MINOR: Table = Table::new([
+    // I  II III IV  V  VI VII
+    [0, 2, 1, 4, 4, 3, 1],
+    [1, 0, 0, 1, 6, 0, 2],
+    [1, 0, 0, 3, 2, 4, 0],
+    [3, 2, 0, 0, 5, 1, 2],
+    [8, 0, 0, 1, 0, 3, 0],
+    [1, 3, 1, 4, 3, 0, 1],
+    [7, 0, 1, 0, 1, 0, 0],
+]);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Cadence {
+    // Dominant resolving to tonic.
+    Authentic,
+    // Subdominant resolving to tonic.
+    Plagal,
+    // Dominant moving to the submediant instead of tonic.
+    Deceptive,
+    // Phrase ending on the dominant.
+    Half,
+}
+
+// Walks through degrees of the mode following a transition table. Phrases
+// have a fixed number of bars and always end on the tonic, the bar before it
+// prefers degrees that resolve to the tonic well.
+pub struct Progression {
+    random: Random,
+    phrase_length: usize,
+    bar: usize,
+    degree: usize,
+    previous: Option<usize>,
+}
+
+impl Progression {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            random: Random::new(seed),
+            phrase_length: 4,
+            bar: 0,
+            degree: 0,
+            previous: None,
+        }
+    }
+
+    pub fn set_phrase_length(&mut self, bars: usize) {
+        self.phrase_length = bars.max(2);
+        self.bar %= self.phrase_length;
+    }
+
+    // Degrees are given as zero-based indices within the mode.
+    pub fn advance(&mut self, table: &Table) -> usize {
+        let row = table.weights[self.degree];
+
+        self.bar = (self.bar + 1) % self.phrase_length;
+        let next = if self.bar == self.phrase_length - 1 {
+            Some(0)
+        } else if self.bar == self.phrase_length - 2 {
+            let mut weights = [0; DEGREES];
+            for (i, weight) in weights.iter_mut().enumerate() {
+                *weight = row[i] as u32 * table.weights[i][0] as u32;
+            }
+            self.pick(weights).or_else(|| self.pick(widen(row)))
+        } else {
+            self.pick(widen(row))
+        };
+
+        self.previous = Some(self.degree);
+        self.degree = next.unwrap_or(0);
+        self.degree
+    }
+
+    // Cadence formed by the last move, if any. Half cadence is only
+    // recognized at the end of a phrase.
+    pub fn cadence(&self) -> Option<Cadence> {
+        match (self.previous?, self.degree) {
+            (4 | 6, 0) => Some(Cadence::Authentic),
+            (3, 0) => Some(Cadence::Plagal),
+            (4, 5) => Some(Cadence::Deceptive),
+            (_, 4) if self.bar == self.phrase_length - 1 => Some(Cadence::Half),
+            _ => None,
+        }
+    }
+
+    fn pick(&mut self, weights: [u32; DEGREES]) -> Option<usize> {
+        let total: u32 = weights.iter().sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut target = self.random.next_u32() % total;
+        for (i, weight) in weights.iter().enumerate() {
+            if target < *weight {
+                return Some(i);
+            }
+            target -= weight;
+        }
+        None
+    }
+}
+
+fn widen(row: [u8; DEGREES]) -> [u32; DEGREES] {
+    let mut widened = [0; DEGREES];
+    for (i, weight) in row.iter().enumerate() {
+        widened[i] = *weight as u32;
+    }
+    widened
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_each_phrase_to_tonic() {
+        for phrase_length in [4, 8] {
+            let mut progression = Progression::new(3);
+            progression.set_phrase_length(phrase_length);
+            for bar in 1..=phrase_length * 20 {
+                let degree = progression.advance(&MAJOR);
+                if bar % phrase_length == phrase_length - 1 {
+                    assert_eq!(degree, 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn prepare_cadence_before_the_end_of_phrase() {
+        let mut progression = Progression::new(5);
+        for _ in 0..100 {
+            let degree = progression.advance(&MINOR);
+            if progression.bar == 2 {
+                assert!(MINOR.weights[degree][0] > 0);
+            }
+        }
+    }
+
+    #[test]
+    fn follow_only_allowed_transitions() {
+        let mut progression = Progression::new(7);
+        progression.set_phrase_length(8);
+        let mut previous = progression.degree;
+        for _ in 0..1000 {
+            let degree = progression.advance(&HARMONIC_MINOR);
+            if progression.bar != 7 {
+                assert!(HARMONIC_MINOR.weights[previous][degree] > 0);
+            }
+            previous = degree;
+        }
+    }
+
+    #[test]
+    fn use_custom_table() {
+        let cycle = Table::new([
+            [0, 0, 0, 1, 0, 0, 0],
+            [0, 0, 0, 0, 0, 0, 0],
+            [0, 0, 0, 0, 0, 0, 0],
+            [0, 0, 0, 0, 1, 0, 0],
+            [1, 0, 0, 0, 0, 0, 0],
+            [0, 0, 0, 0, 0, 0, 0],
+            [0, 0, 0, 0, 0, 0, 0],
+        ]);
+        let mut progression = Progression::new(1);
+        progression.set_phrase_length(4);
+        let degrees: [usize; 6] = core::array::from_fn(|_| progression.advance(&cycle));
+        assert_eq!(degrees, [3, 4, 0, 3, 4, 0]);
+    }
+
+    #[test]
+    fn detect_cadences() {
+        let cycle = Table::new([
+            [0, 0, 0, 1, 0, 0, 0],
+            [0, 0, 0, 0, 0, 0, 0],
+            [0, 0, 0, 0, 0, 0, 0],
+            [1, 0, 0, 0, 0, 0, 0],
+            [0, 0, 0, 0, 0, 1, 0],
+            [0, 0, 0, 0, 1, 0, 0],
+            [0, 0, 0, 0, 0, 0, 0],
+        ]);
+        let mut progression = Progression::new(1);
+        progression.set_phrase_length(8);
+        assert_eq!(progression.cadence(), None);
+
+        progression.advance(&cycle);
+        assert_eq!(progression.cadence(), None);
+        progression.advance(&cycle);
+        assert_eq!(progression.cadence(), Some(Cadence::Plagal));
+    }
+
+    #[test]
+    fn keep_same_progression_for_same_seed() {
+        let mut progression_a = Progression::new(9);
+        let mut progression_b = Progression::new(9);
+        for _ in 0..100 {
+            assert_eq!(progression_a.advance(&MAJOR), progression_b.advance(&MAJOR));
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::note::Note;
+use crate::tuning::table::Tuning;
+
+// Degree of the chord each of the semitones above the root stands for.
+// Altered tones are spelled as the degree they alter.
+const DEGREE_OF_SEMITONE: [i8; 12] = [1, 2, 2, 3, 3, 4, 5, 5, 5, 6, 7, 7];
+
+// Chord given by semitones of its tones above the root, independent of any
+// scale.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Quality {
+    semitones: &'static [i8],
+}
+
+impl Quality {
+    pub const fn new(semitones: &'static [i8]) -> Self {
+        Self { semitones }
+    }
+
+    // Pitch classes of the chord, relative to its root.
+    pub fn mask(&self) -> u16 {
+        self.semitones
+            .iter()
+            .fold(0, |mask, semitone| mask | 1 << semitone.rem_euclid(12))
+    }
+
+    pub fn degrees<const D: usize>(&self) -> [i8; D] {
+        let mut degrees = [0; D];
+        for (degree, semitone) in degrees.iter_mut().zip(self.semitones) {
+            *degree = DEGREE_OF_SEMITONE[semitone.rem_euclid(12) as usize] + semitone / 12 * 7;
+        }
+        degrees
+    }
+}
+
+// Notes that are not mapped in the tuning are snapped to their closest
+// mapped neighbour.
+pub fn build<const D: usize>(tuning: &Tuning, root: Note, quality: Quality) -> [Option<Note>; D] {
+    let mut notes = [None; D];
+
+    for (note, semitone) in notes.iter_mut().zip(quality.semitones) {
+        *note = Note::try_from_i16(root as i16 + *semitone as i16).map(|note| tuning.snap(note));
+    }
+
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIMINISHED: Quality = Quality::new(&[0, 3, 6]);
+    const DOMINANT_NINTH: Quality = Quality::new(&[0, 4, 7, 10, 14]);
+
+    lazy_static! {
+        static ref TUNING: Tuning = Tuning::equal_temperament();
+    }
+
+    #[test]
+    fn build_chord_of_given_quality() {
+        let notes = build(&TUNING, Note::B3, DIMINISHED);
+        assert_eq!(notes, [Some(Note::B3), Some(Note::D4), Some(Note::F4)]);
+    }
+
+    #[test]
+    fn leave_remaining_voices_disabled() {
+        let notes: [_; 5] = build(&TUNING, Note::C4, DIMINISHED);
+        assert_eq!(notes[3], None);
+        assert_eq!(notes[4], None);
+    }
+
+    #[test]
+    fn build_chord_that_overflows_note_range() {
+        let notes: [_; 5] = build(&TUNING, Note::G9, DOMINANT_NINTH);
+        assert_eq!(notes[0], Some(Note::G9));
+        assert_eq!(notes[4], None);
+    }
+
+    #[test]
+    fn get_mask_of_pitch_classes() {
+        assert_eq!(DIMINISHED.mask(), 0b0000_0100_1001);
+        assert_eq!(DOMINANT_NINTH.mask(), 0b0100_1001_0101);
+    }
+
+    #[test]
+    fn spell_degrees_of_chord() {
+        assert_eq!(DIMINISHED.degrees(), [1, 3, 5, 0, 0]);
+        assert_eq!(DOMINANT_NINTH.degrees(), [1, 3, 5, 7, 9]);
+    }
+}
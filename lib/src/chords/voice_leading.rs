@@ -0,0 +1,203 @@
+use crate::note::Note;
+
+// Greater than any movement to the closest octave of a tone.
+const SILENCE_COST: u32 = 12;
+
+// Voice the chord so its voices move as little as possible from the previous
+// chord. Tones keep their pitch class, but may be moved by octaves and swapped
+// between voices. All the notes are kept within the window of given number of
+// semitones, centered around the chord root.
+pub fn lead<const D: usize>(
+    previous: [Option<Note>; D],
+    chord: [Option<Note>; D],
+    chord_root: Note,
+    window: u8,
+) -> [Option<Note>; D] {
+    let window = window.max(12) as i32;
+    let lowest = (chord_root as i32 - window / 2).max(0);
+    let highest = (lowest + window).min(Note::HIGHEST_NOTE as i32);
+
+    let mut best_order = identity::<D>();
+    let mut best_cost = u32::MAX;
+    for_each_permutation(|order| {
+        let cost = order.iter().enumerate().fold(0, |cost, (voice, tone)| {
+            match (previous[voice], chord[*tone]) {
+                (Some(previous), Some(tone)) => cost + movement(previous, tone).unsigned_abs(),
+                (None, None) => cost,
+                // Prefer keeping the playing voices over starting new ones
+                _ => cost + SILENCE_COST,
+            }
+        });
+        if cost < best_cost {
+            best_cost = cost;
+            best_order = *order;
+        }
+    });
+
+    let mut voiced = [None; D];
+    for (voice, tone) in best_order.iter().enumerate() {
+        voiced[voice] = chord[*tone].map(|tone| {
+            let target = match previous[voice] {
+                Some(previous) => previous as i32 + movement(previous, tone),
+                None => tone as i32,
+            };
+            Note::from_u8(fit_into_window(target, lowest, highest) as u8)
+        });
+    }
+
+    voiced
+}
+
+// The shortest move from the previous note to any octave of the tone.
+fn movement(previous: Note, tone: Note) -> i32 {
+    let distance = (tone as i32 - previous as i32).rem_euclid(12);
+    if distance > 6 {
+        distance - 12
+    } else {
+        distance
+    }
+}
+
+fn fit_into_window(mut note: i32, lowest: i32, highest: i32) -> i32 {
+    while note < lowest {
+        note += 12;
+    }
+    while note > highest {
+        note -= 12;
+    }
+    note
+}
+
+fn identity<const D: usize>() -> [usize; D] {
+    let mut order = [0; D];
+    for (i, x) in order.iter_mut().enumerate() {
+        *x = i;
+    }
+    order
+}
+
+// Heap's algorithm, visiting all the orders without allocation.
+fn for_each_permutation<const D: usize>(mut visit: impl FnMut(&[usize; D])) {
+    let mut order = identity::<D>();
+    let mut counters = [0; D];
+
+    visit(&order);
+
+    let mut i = 1;
+    while i < D {
+        if counters[i] < i {
+            if i % 2 == 0 {
+                order.swap(0, i);
+            } else {
+                order.swap(counters[i], i);
+            }
+            visit(&order);
+            counters[i] += 1;
+            i = 1;
+        } else {
+            counters[i] = 0;
+            i += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::chords::diatonic;
+    use crate::scales::diatonic::Mode::*;
+    use crate::tuning::table::Tuning;
+
+    const WINDOW: u8 = 24;
+
+    lazy_static! {
+        static ref TUNING: Tuning = Tuning::equal_temperament();
+    }
+
+    fn triad(root: Note) -> [Option<Note>; 3] {
+        diatonic::build(&TUNING, Note::C1, Ionian.scale(), root, [1, 3, 5])
+    }
+
+    fn pitch_classes(chord: [Option<Note>; 3]) -> [u8; 3] {
+        let mut classes = [0; 3];
+        for (class, note) in classes.iter_mut().zip(chord) {
+            *class = note.unwrap().to_midi_id() % 12;
+        }
+        classes.sort_unstable();
+        classes
+    }
+
+    fn assert_progression_moves_at_most(roots: &[Note], max_movement: u8) {
+        let mut previous = triad(roots[0]);
+        for root in &roots[1..] {
+            let chord = triad(*root);
+            let voiced = lead(previous, chord, *root, WINDOW);
+
+            assert_eq!(pitch_classes(voiced), pitch_classes(chord));
+            for (a, b) in previous.iter().zip(voiced) {
+                let (a, b) = (a.unwrap().to_midi_id(), b.unwrap().to_midi_id());
+                assert!(
+                    (a as i32 - b as i32).unsigned_abs() <= max_movement as u32,
+                    "{} -> {}",
+                    a,
+                    b
+                );
+            }
+
+            previous = voiced;
+        }
+    }
+
+    #[test]
+    fn visit_all_permutations() {
+        let mut visited = 0;
+        for_each_permutation::<4>(|_| visited += 1);
+        assert_eq!(visited, 24);
+    }
+
+    #[test]
+    fn keep_the_first_chord_as_it_is() {
+        let chord = triad(Note::C3);
+        assert_eq!(lead([None; 3], chord, Note::C3, WINDOW), chord);
+    }
+
+    #[test]
+    fn lead_i_iv_v_i() {
+        assert_progression_moves_at_most(&[Note::C3, Note::F3, Note::G3, Note::C3], 3);
+    }
+
+    #[test]
+    fn lead_ii_v_i() {
+        assert_progression_moves_at_most(&[Note::D3, Note::G3, Note::C3], 2);
+    }
+
+    #[test]
+    fn lead_i_iv_into_common_tone() {
+        let voiced = lead(triad(Note::C3), triad(Note::F3), Note::F3, WINDOW);
+        assert_eq!(voiced, [Some(Note::C3), Some(Note::F3), Some(Note::A3)]);
+    }
+
+    #[test]
+    fn stay_within_window_during_long_progression() {
+        let roots = [Note::C3, Note::A2, Note::D3, Note::G3];
+        let mut previous = triad(Note::C3);
+        for _ in 0..20 {
+            for root in roots.iter() {
+                previous = lead(previous, triad(*root), *root, WINDOW);
+                for note in previous.iter() {
+                    let distance = note.unwrap().to_midi_id() as i32 - root.to_midi_id() as i32;
+                    assert!(distance.abs() <= WINDOW as i32 / 2);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn keep_silent_voices_silent() {
+        let previous = [Some(Note::C3), Some(Note::E3), Some(Note::G3)];
+        let chord = [Some(Note::F3), Some(Note::A3), None];
+        let voiced = lead(previous, chord, Note::F3, WINDOW);
+        assert_eq!(voiced.iter().filter(|n| n.is_some()).count(), 2);
+    }
+}
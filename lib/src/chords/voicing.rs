@@ -0,0 +1,254 @@
+use crate::note::Note;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Voicing {
+    // Keep the chord as it was built.
+    Close,
+    // Drop the second highest tone by an octave.
+    Drop2,
+    // Drop the third highest tone by an octave.
+    Drop3,
+    // Drop the second and the fourth highest tones by an octave.
+    Drop24,
+    // Raise every other tone, starting from the second lowest, by an octave.
+    Open,
+    // Spread tones evenly over the given number of additional octaves.
+    Spread(u8),
+}
+
+pub const VOICINGS: [Voicing; 7] = [
+    Voicing::Close,
+    Voicing::Drop2,
+    Voicing::Drop3,
+    Voicing::Drop24,
+    Voicing::Open,
+    Voicing::Spread(1),
+    Voicing::Spread(2),
+];
+
+// Move tones of the chord by octaves to get the given voicing. Voices keep
+// their position in the array, silent voices are left silent. Tones that would
+// get out of the range of notes are kept where they were.
+pub fn apply<const D: usize>(voicing: Voicing, chord: [Option<Note>; D]) -> [Option<Note>; D] {
+    let (ascending, count) = sort_voices(chord);
+    let descending = |i: usize| ascending[count - 1 - i];
+
+    let mut voiced = chord;
+    match voicing {
+        Voicing::Close => (),
+        Voicing::Drop2 => {
+            if count >= 2 {
+                shift(&mut voiced, descending(1), -1);
+            }
+        }
+        Voicing::Drop3 => {
+            if count >= 3 {
+                shift(&mut voiced, descending(2), -1);
+            }
+        }
+        Voicing::Drop24 => {
+            if count >= 2 {
+                shift(&mut voiced, descending(1), -1);
+            }
+            if count >= 4 {
+                shift(&mut voiced, descending(3), -1);
+            }
+        }
+        Voicing::Open => {
+            for i in (1..count).step_by(2) {
+                shift(&mut voiced, ascending[i], 1);
+            }
+        }
+        Voicing::Spread(octaves) => {
+            if count >= 2 {
+                for (i, voice) in ascending[..count].iter().enumerate() {
+                    let offset = i * octaves as usize / (count - 1);
+                    shift(&mut voiced, *voice, offset as i16);
+                }
+            }
+        }
+    }
+
+    voiced
+}
+
+// Rotate the chord by moving its lowest tone an octave up, as many times as
+// is the inversion. Negative inversion moves the highest tone down instead.
+pub fn invert<const D: usize>(inversion: i8, chord: [Option<Note>; D]) -> [Option<Note>; D] {
+    let mut inverted = chord;
+
+    for _ in 0..inversion.unsigned_abs() {
+        let (ascending, count) = sort_voices(inverted);
+        if count == 0 {
+            break;
+        }
+        if inversion > 0 {
+            shift(&mut inverted, ascending[0], 1);
+        } else {
+            shift(&mut inverted, ascending[count - 1], -1);
+        }
+    }
+
+    inverted
+}
+
+// Indices of playing voices, ordered from the lowest tone.
+fn sort_voices<const D: usize>(chord: [Option<Note>; D]) -> ([usize; D], usize) {
+    let mut order = [0; D];
+    let mut count = 0;
+
+    for (i, note) in chord.iter().enumerate() {
+        if let Some(note) = note {
+            let mut position = count;
+            while position > 0 && chord[order[position - 1]].unwrap() > *note {
+                order[position] = order[position - 1];
+                position -= 1;
+            }
+            order[position] = i;
+            count += 1;
+        }
+    }
+
+    (order, count)
+}
+
+fn shift<const D: usize>(chord: &mut [Option<Note>; D], voice: usize, octaves: i16) {
+    if let Some(note) = chord[voice] {
+        if let Some(shifted) = Note::try_from_i16(note as i16 + octaves * 12) {
+            chord[voice] = Some(shifted);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CMAJ7: [Option<Note>; 5] = [
+        Some(Note::C3),
+        Some(Note::E3),
+        Some(Note::G3),
+        Some(Note::B3),
+        None,
+    ];
+
+    #[test]
+    fn keep_close_voicing() {
+        assert_eq!(apply(Voicing::Close, CMAJ7), CMAJ7);
+    }
+
+    #[test]
+    fn drop_second_highest_tone() {
+        assert_eq!(
+            apply(Voicing::Drop2, CMAJ7),
+            [
+                Some(Note::C3),
+                Some(Note::E3),
+                Some(Note::G2),
+                Some(Note::B3),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn drop_third_highest_tone() {
+        assert_eq!(
+            apply(Voicing::Drop3, CMAJ7),
+            [
+                Some(Note::C3),
+                Some(Note::E2),
+                Some(Note::G3),
+                Some(Note::B3),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn drop_second_and_fourth_highest_tones() {
+        assert_eq!(
+            apply(Voicing::Drop24, CMAJ7),
+            [
+                Some(Note::C2),
+                Some(Note::E3),
+                Some(Note::G2),
+                Some(Note::B3),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn open_triad() {
+        let triad = [Some(Note::C3), Some(Note::E3), Some(Note::G3)];
+        assert_eq!(
+            apply(Voicing::Open, triad),
+            [Some(Note::C3), Some(Note::E4), Some(Note::G3)]
+        );
+    }
+
+    #[test]
+    fn spread_over_octaves() {
+        let triad = [Some(Note::C3), Some(Note::E3), Some(Note::G3)];
+        assert_eq!(
+            apply(Voicing::Spread(2), triad),
+            [Some(Note::C3), Some(Note::E4), Some(Note::G5)]
+        );
+        assert_eq!(
+            apply(Voicing::Spread(1), triad),
+            [Some(Note::C3), Some(Note::E3), Some(Note::G4)]
+        );
+    }
+
+    #[test]
+    fn apply_to_unordered_chord_with_silent_voices() {
+        let chord = [None, Some(Note::G3), Some(Note::C3), None, Some(Note::E3)];
+        assert_eq!(
+            apply(Voicing::Drop2, chord),
+            [None, Some(Note::G3), Some(Note::C3), None, Some(Note::E2)]
+        );
+    }
+
+    #[test]
+    fn invert_triad_up() {
+        let triad = [Some(Note::C3), Some(Note::E3), Some(Note::G3)];
+        assert_eq!(
+            invert(1, triad),
+            [Some(Note::C4), Some(Note::E3), Some(Note::G3)]
+        );
+        assert_eq!(
+            invert(2, triad),
+            [Some(Note::C4), Some(Note::E4), Some(Note::G3)]
+        );
+        assert_eq!(
+            invert(3, triad),
+            [Some(Note::C4), Some(Note::E4), Some(Note::G4)]
+        );
+    }
+
+    #[test]
+    fn invert_seventh_chord_down() {
+        assert_eq!(
+            invert(-2, CMAJ7),
+            [
+                Some(Note::C3),
+                Some(Note::E3),
+                Some(Note::G2),
+                Some(Note::B2),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn keep_root_position_without_inversion() {
+        assert_eq!(invert(0, CMAJ7), CMAJ7);
+    }
+
+    #[test]
+    fn keep_tones_that_would_leave_the_range() {
+        let chord = [Some(Note::CMinus1), Some(Note::E0), Some(Note::G0)];
+        assert_eq!(apply(Voicing::Drop3, chord), chord);
+    }
+}
@@ -1,6 +1,12 @@
+// Options are split into pages of 8, so each page fits into the display.
+pub const PAGES: usize = 7;
+pub const OPTIONS_PER_PAGE: [u32; PAGES] = [8, 8, 8, 0, 7, 3, 3];
+
+const MASK: u64 = 0b111_0000_0111_0111_1111_0000_0000_1111_1111_1111_1111_1111_1111;
+
 #[derive(Clone, Copy, Default, PartialEq, Eq)]
 pub struct Config {
-    config: u8,
+    config: u64,
 }
 
 impl Config {
@@ -19,31 +25,277 @@ impl Config {
     pub fn tonic_controlled_by_solo_cv(&self) -> bool {
         self.config & (1 << 3) != 0
     }
+
+    pub fn trigger_controlled_by_solo_cv(&self) -> bool {
+        self.config & (1 << 4) != 0
+    }
+
+    pub fn just_intonation(&self) -> bool {
+        self.config & (1 << 5) != 0
+    }
+
+    pub fn white_key_quantization(&self) -> bool {
+        self.config & (1 << 6) != 0
+    }
+
+    pub fn hysteresis_quantization(&self) -> bool {
+        self.config & (1 << 7) != 0
+    }
+
+    pub fn balanced_amplitude(&self) -> bool {
+        self.config & (1 << 8) != 0
+    }
+
+    pub fn rms_amplitude(&self) -> bool {
+        self.config & (1 << 9) != 0
+    }
+
+    pub fn constant_rate_glide(&self) -> bool {
+        self.config & (1 << 10) != 0
+    }
+
+    pub fn voice_matching(&self) -> bool {
+        self.config & (1 << 11) != 0
+    }
+
+    pub fn voice_leading(&self) -> bool {
+        self.config & (1 << 12) != 0
+    }
+
+    pub fn voicing_controlled_by_detune_cv(&self) -> bool {
+        self.config & (1 << 13) != 0
+    }
+
+    pub fn inversion_controlled_by_wavetable_cv(&self) -> bool {
+        self.config & (1 << 14) != 0
+    }
+
+    pub fn inversion_controlled_by_solo_cv(&self) -> bool {
+        self.config & (1 << 15) != 0
+    }
+
+    pub fn strum_down(&self) -> bool {
+        self.config & (1 << 16) != 0
+    }
+
+    pub fn random_strum_order(&self) -> bool {
+        self.config & (1 << 17) != 0
+    }
+
+    pub fn gate_controlled_by_solo_cv(&self) -> bool {
+        self.config & (1 << 18) != 0
+    }
+
+    pub fn chord_sequencer(&self) -> bool {
+        self.config & (1 << 19) != 0
+    }
+
+    pub fn sequence_scale_mode(&self) -> bool {
+        self.config & (1 << 20) != 0
+    }
+
+    pub fn sample_and_hold_chord_root(&self) -> bool {
+        self.config & (1 << 21) != 0
+    }
+
+    pub fn track_and_hold_chord_root(&self) -> bool {
+        self.config & (1 << 22) != 0
+    }
+
+    pub fn chord_generator(&self) -> bool {
+        self.config & (1 << 23) != 0
+    }
+
+    pub fn chord_progression(&self) -> bool {
+        self.config & (1 << 32) != 0
+    }
+
+    pub fn eight_bar_phrases(&self) -> bool {
+        self.config & (1 << 33) != 0
+    }
+
+    pub fn neo_riemannian_transformations(&self) -> bool {
+        self.config & (1 << 34) != 0
+    }
+
+    pub fn wavetable_bank_controlled_by_wavetable_cv(&self) -> bool {
+        self.config & (1 << 35) != 0
+    }
+
+    pub fn sync_warp(&self) -> bool {
+        self.config & (1 << 36) != 0
+    }
+
+    pub fn window_warp(&self) -> bool {
+        self.config & (1 << 37) != 0
+    }
+
+    pub fn hard_sync(&self) -> bool {
+        self.config & (1 << 38) != 0
+    }
+
+    pub fn frequency_modulation(&self) -> bool {
+        self.config & (1 << 40) != 0
+    }
+
+    pub fn ring_modulation(&self) -> bool {
+        self.config & (1 << 41) != 0
+    }
+
+    pub fn amplitude_modulation(&self) -> bool {
+        self.config & (1 << 42) != 0
+    }
+
+    pub fn pythagorean_temperament(&self) -> bool {
+        self.config & (1 << 48) != 0
+    }
+
+    pub fn meantone_temperament(&self) -> bool {
+        self.config & (1 << 49) != 0
+    }
+
+    pub fn werckmeister_temperament(&self) -> bool {
+        self.config & (1 << 50) != 0
+    }
+
+    // TONE' CV plays the solo voice only while none of its other uses is
+    // enabled. Chord sequencer, generator, progression and transformations are
+    // advanced by the trigger on TONE' CV, and inversion on TONE' CV gives way
+    // to WAVETABLE CV.
+    pub fn solo_controlled_by_solo_cv(&self) -> bool {
+        let inversion =
+            self.inversion_controlled_by_solo_cv() && !self.inversion_controlled_by_wavetable_cv();
+        !(inversion
+            || self.tonic_controlled_by_solo_cv()
+            || self.trigger_controlled_by_solo_cv()
+            || self.gate_controlled_by_solo_cv()
+            || self.sample_and_hold_chord_root()
+            || self.track_and_hold_chord_root()
+            || self.chord_sequencer()
+            || self.chord_generator()
+            || self.chord_progression()
+            || self.neo_riemannian_transformations())
+    }
+
+    pub fn page(&self, page: usize) -> u8 {
+        (self.config >> (page * 8)) as u8
+    }
+
+    pub fn with_page(self, page: usize, options: u8) -> Self {
+        let shift = page * 8;
+        let cleared = self.config & !(0xFF << shift);
+        Self::from(cleared | (options as u64) << shift)
+    }
+
+    pub fn with_option_toggled(self, page: usize, option: usize) -> Self {
+        self.with_page(page, self.page(page) ^ (1 << option))
+    }
+
+    pub fn page_leds(&self, page: usize) -> [bool; 8] {
+        let options = self.page(page);
+
+        let mut value = [false; 8];
+        for (i, led) in value.iter_mut().enumerate() {
+            *led = options & (1 << i) != 0;
+        }
+
+        value
+    }
 }
 
-impl From<u8> for Config {
-    fn from(other: u8) -> Self {
+impl From<u64> for Config {
+    fn from(other: u64) -> Self {
         Self {
-            config: other & 0b1111,
+            config: other & MASK,
         }
     }
 }
 
-impl From<Config> for u8 {
+impl From<Config> for u64 {
     fn from(other: Config) -> Self {
         other.config
     }
 }
 
-impl From<Config> for [bool; 8] {
-    fn from(other: Config) -> Self {
-        let mut value = [false; 8];
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        value[0] = other.overdrive();
-        value[1] = other.modes_ordered_by_brightness();
-        value[2] = other.mode_controlled_by_detune_cv();
-        value[3] = other.tonic_controlled_by_solo_cv();
+    #[test]
+    fn set_options_on_the_second_page() {
+        let config = Config::from(0b1000_0001).with_page(1, 0b10);
 
-        value
+        assert!(config.overdrive());
+        assert!(config.hysteresis_quantization());
+        assert!(!config.balanced_amplitude());
+        assert!(config.rms_amplitude());
+        assert_eq!(config.page(0), 0b1000_0001);
+        assert_eq!(config.page(1), 0b10);
+    }
+
+    #[test]
+    fn toggle_single_option_of_a_page() {
+        let config = Config::from(0b1000_0001 << 8).with_option_toggled(1, 3);
+        assert!(config.balanced_amplitude());
+        assert!(config.voice_matching());
+        assert!(config.inversion_controlled_by_solo_cv());
+
+        let config = config.with_option_toggled(1, 7);
+        assert_eq!(config.page(1), 0b0000_1001);
+    }
+
+    #[test]
+    fn ignore_options_past_the_last_one() {
+        let config = Config::default()
+            .with_page(2, 0xFF)
+            .with_page(3, 0xFF)
+            .with_page(4, 0xFF)
+            .with_page(5, 0xFF)
+            .with_page(6, 0xFF);
+        assert_eq!(config.page(1), 0);
+        assert_eq!(config.page(2), 0b1111_1111);
+        assert_eq!(config.page(3), 0);
+        assert_eq!(config.page(4), 0b111_1111);
+        assert_eq!(config.page(5), 0b111);
+        assert_eq!(config.page(6), 0b111);
+        assert!(config.chord_generator());
+        assert!(config.neo_riemannian_transformations());
+        assert!(config.werckmeister_temperament());
+    }
+
+    #[test]
+    fn disable_solo_when_tone_cv_is_assigned_elsewhere() {
+        assert!(Config::default().solo_controlled_by_solo_cv());
+
+        let sample_and_hold = Config::from(1 << 21);
+        assert!(sample_and_hold.sample_and_hold_chord_root());
+        assert!(!sample_and_hold.solo_controlled_by_solo_cv());
+
+        let track_and_hold = Config::from(1 << 22);
+        assert!(track_and_hold.track_and_hold_chord_root());
+        assert!(!track_and_hold.solo_controlled_by_solo_cv());
+
+        for bit in [3, 4, 15, 18, 19, 23, 32, 34] {
+            assert!(!Config::from(1 << bit).solo_controlled_by_solo_cv());
+        }
+    }
+
+    #[test]
+    fn keep_solo_when_inversion_moves_to_wavetable_cv() {
+        let config = Config::from(1 << 14 | 1 << 15);
+        assert!(config.solo_controlled_by_solo_cv());
+    }
+
+    #[test]
+    fn display_options_of_a_page() {
+        let config = Config::from(0b01_0000_0010);
+        assert_eq!(
+            config.page_leds(0),
+            [false, true, false, false, false, false, false, false]
+        );
+        assert_eq!(
+            config.page_leds(1),
+            [true, false, false, false, false, false, false, false]
+        );
     }
 }
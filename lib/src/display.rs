@@ -1,19 +1,28 @@
+use crate::envelope::Stage;
+use crate::instrument::ScaleMode;
 use crate::note::Note;
-use crate::scales::diatonic::Mode;
 
 #[derive(Clone, Copy)]
 pub enum Action {
     SetChord([i8; 5]),
+    SetFrozenChord([i8; 5]),
+    SetChordQuality(u16),
     SetScaleRoot(Note),
-    SetScaleMode(Mode),
+    SetScaleMode(ScaleMode),
+    SetUserScaleNote(u8, bool),
     SetChordRootDegree(u8),
     SetSolo(u8),
     SetWavetableBank(usize),
     SetWavetable(f32),
     SetStyle(usize),
+    SetVoicing(usize),
+    SetInversion(i8),
+    SetSequencerStep(usize),
     SetDetune(usize, f32),
     SetCalibration(CalibrationPhase),
     SetConfiguration([bool; 8]),
+    SetConfigurationOption(usize, bool),
+    SetEnvelopeStage(Stage),
 }
 
 #[derive(Clone, Copy)]
@@ -69,16 +78,28 @@ impl From<State> for [bool; 8] {
 pub fn reduce(action: Action) -> State {
     match action {
         Action::SetChord(chord) => reduce_set_chord(chord),
+        Action::SetFrozenChord(chord) => reduce_set_frozen_chord(chord),
+        Action::SetChordQuality(mask) => reduce_set_chord_quality(mask),
         Action::SetScaleRoot(root) => reduce_set_scale_root(root),
         Action::SetScaleMode(mode) => reduce_set_scale_mode(mode),
+        Action::SetUserScaleNote(pitch_class, enabled) => {
+            reduce_set_user_scale_note(pitch_class, enabled)
+        }
         Action::SetChordRootDegree(degree) => reduce_set_degree(degree),
         Action::SetSolo(degree) => reduce_set_degree(degree),
         Action::SetWavetableBank(bank_index) => reduce_set_wavetable_bank(bank_index),
         Action::SetWavetable(wavetable_phase) => reduce_set_wavetable(wavetable_phase),
         Action::SetStyle(style_index) => reduce_set_style(style_index),
+        Action::SetVoicing(voicing_index) => reduce_set_voicing(voicing_index),
+        Action::SetInversion(inversion) => reduce_set_inversion(inversion),
+        Action::SetSequencerStep(position) => reduce_set_sequencer_step(position),
         Action::SetDetune(index, phase) => reduce_set_detune(index, phase),
         Action::SetCalibration(phase) => reduce_set_calibration(phase),
         Action::SetConfiguration(options) => reduce_set_configuration(options),
+        Action::SetConfigurationOption(index, enabled) => {
+            reduce_set_configuration_option(index, enabled)
+        }
+        Action::SetEnvelopeStage(stage) => reduce_set_envelope_stage(stage),
     }
 }
 
@@ -100,11 +121,39 @@ fn reduce_set_chord(chord: [i8; 5]) -> State {
     state_array.into()
 }
 
+fn reduce_set_frozen_chord(chord: [i8; 5]) -> State {
+    let mut state = reduce_set_chord(chord);
+    state.led_sharp = true;
+    state
+}
+
+// Each pitch class of the quality lights the degree it stands for. The sharp
+// LED signals that some of the degrees are altered, such as the minor third or
+// the diminished fifth.
+fn reduce_set_chord_quality(mask: u16) -> State {
+    const LED_OF_SEMITONE: [usize; 12] = [0, 1, 1, 2, 2, 3, 4, 4, 4, 5, 6, 6];
+    const ALTERED: u16 = 0b0101_0100_1010;
+
+    let mut state_array = [false; 8];
+
+    for (semitone, led) in LED_OF_SEMITONE.iter().enumerate() {
+        if mask & (1 << semitone) != 0 {
+            state_array[*led] = true;
+        }
+    }
+    state_array[7] = mask & ALTERED != 0;
+
+    state_array.into()
+}
+
 fn reduce_set_scale_root(root: Note) -> State {
+    pitch_class_to_state_array(root.to_midi_id() % 12).into()
+}
+
+fn pitch_class_to_state_array(pitch_class: u8) -> [bool; 8] {
     let mut state_array = [false; 8];
 
-    let relative_root = root.to_midi_id() % 12;
-    match relative_root {
+    match pitch_class {
         0 => {
             state_array[0] = true;
         }
@@ -149,18 +198,47 @@ fn reduce_set_scale_root(root: Note) -> State {
         _ => unreachable!(),
     }
 
-    state_array.into()
+    state_array
 }
 
-fn reduce_set_scale_mode(mode: Mode) -> State {
+// User scales are displayed on the first LEDs with the sharp LED on.
+fn reduce_set_scale_mode(mode: ScaleMode) -> State {
     let mut state_array = [false; 8];
-    state_array[mode as usize] = true;
+    match mode {
+        ScaleMode::Diatonic(mode) => {
+            state_array[mode as usize] = true;
+        }
+        ScaleMode::User(index) => {
+            debug_assert!(index < 7);
+            state_array[index] = true;
+            state_array[7] = true;
+        }
+    }
+    state_array.into()
+}
+
+// Pitch class is displayed the same way as the scale root. If it is not part of
+// the scale, the display is inverted.
+fn reduce_set_user_scale_note(pitch_class: u8, enabled: bool) -> State {
+    let mut state_array = pitch_class_to_state_array(pitch_class);
+    if !enabled {
+        state_array.iter_mut().for_each(|led| *led = !*led);
+    }
     state_array.into()
 }
 
+// Scales may have up to 12 degrees. Degrees above the seventh are displayed as
+// the degree seven steps below with the sharp LED on.
 fn reduce_set_degree(degree: u8) -> State {
+    debug_assert!((1..=12).contains(&degree));
+
     let mut state_array = [false; 8];
-    state_array[(degree - 1) as usize] = true;
+    if degree <= 7 {
+        state_array[(degree - 1) as usize] = true;
+    } else {
+        state_array[(degree - 8) as usize] = true;
+        state_array[7] = true;
+    }
     state_array.into()
 }
 
@@ -189,7 +267,7 @@ fn reduce_set_wavetable(phase: f32) -> State {
 }
 
 fn reduce_set_style(style_index: usize) -> State {
-    debug_assert!(style_index < 3);
+    debug_assert!(style_index < 5);
 
     let mut state_array = [false; 8];
 
@@ -200,15 +278,55 @@ fn reduce_set_style(style_index: usize) -> State {
     } else if style_index == 1 {
         state_array[3] = true;
         state_array[4] = true;
-    } else {
+    } else if style_index == 2 {
         state_array[5] = true;
         state_array[6] = true;
         state_array[7] = true;
+    } else if style_index == 3 {
+        state_array[0] = true;
+        state_array[2] = true;
+        state_array[4] = true;
+        state_array[6] = true;
+    } else {
+        state_array[1] = true;
+        state_array[3] = true;
+        state_array[5] = true;
+        state_array[7] = true;
     }
 
     state_array.into()
 }
 
+fn reduce_set_voicing(voicing_index: usize) -> State {
+    debug_assert!(voicing_index < 7);
+
+    let mut state_array = [false; 8];
+    state_array[voicing_index] = true;
+
+    state_array.into()
+}
+
+// Root position is displayed in the middle, inverting up moves to the right.
+fn reduce_set_inversion(inversion: i8) -> State {
+    debug_assert!((-3..=3).contains(&inversion));
+
+    let mut state_array = [false; 8];
+    state_array[(inversion + 3) as usize] = true;
+
+    state_array.into()
+}
+
+// The first eight steps light a single LED, the following eight light all but
+// one, so all sixteen positions can be told apart.
+fn reduce_set_sequencer_step(position: usize) -> State {
+    debug_assert!(position < 16);
+
+    let mut state_array = [position >= 8; 8];
+    state_array[position % 8] = position < 8;
+
+    state_array.into()
+}
+
 fn reduce_set_detune(index: usize, phase: f32) -> State {
     debug_assert!(index < 4);
     debug_assert!((0.0..=1.0).contains(&phase));
@@ -236,10 +354,34 @@ fn reduce_set_configuration(options: [bool; 8]) -> State {
     options.into()
 }
 
+// The option under the cursor is displayed by its LED alone. If it is
+// disabled, the display is inverted.
+fn reduce_set_configuration_option(index: usize, enabled: bool) -> State {
+    let mut state_array = [!enabled; 8];
+    state_array[index] = enabled;
+    state_array.into()
+}
+
+// Stages are shown on the first four LEDs, in the order of the pots setting
+// them. Nothing is lit while the envelope is idle.
+fn reduce_set_envelope_stage(stage: Stage) -> State {
+    let mut state_array = [false; 8];
+    match stage {
+        Stage::Idle => (),
+        Stage::Attack => state_array[0] = true,
+        Stage::Decay => state_array[1] = true,
+        Stage::Sustain => state_array[2] = true,
+        Stage::Release => state_array[3] = true,
+    }
+    state_array.into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::scales::diatonic::Mode;
+
     #[test]
     fn initialize_state_from_array() {
         let state: State = [true, false, true, false, true, false, true, false].into();
@@ -292,6 +434,42 @@ mod tests {
         )
     }
 
+    #[test]
+    fn reduce_major_chord_quality() {
+        let state = reduce(Action::SetChordQuality(0b0000_1001_0001));
+        assert_eq!(
+            state,
+            State {
+                led1: true,
+                led2: false,
+                led3: true,
+                led4: false,
+                led5: true,
+                led6: false,
+                led7: false,
+                led_sharp: false,
+            }
+        )
+    }
+
+    #[test]
+    fn reduce_half_diminished_chord_quality() {
+        let state = reduce(Action::SetChordQuality(0b0100_0100_1001));
+        assert_eq!(
+            state,
+            State {
+                led1: true,
+                led2: false,
+                led3: true,
+                led4: false,
+                led5: true,
+                led6: false,
+                led7: true,
+                led_sharp: true,
+            }
+        )
+    }
+
     #[test]
     fn reduce_simple_fifth_chord() {
         let state = reduce(Action::SetChord([1, 3, 5, 0, 0]));
@@ -436,6 +614,24 @@ mod tests {
         )
     }
 
+    #[test]
+    fn reduce_frozen_chord() {
+        let state = reduce(Action::SetFrozenChord([1, 3, 5, 0, 0]));
+        assert_eq!(
+            state,
+            State {
+                led1: true,
+                led2: false,
+                led3: true,
+                led4: false,
+                led5: true,
+                led6: false,
+                led7: false,
+                led_sharp: true,
+            }
+        )
+    }
+
     #[test]
     fn reduce_scale_root_c() {
         let state = reduce(Action::SetScaleRoot(Note::C3));
@@ -654,7 +850,7 @@ mod tests {
 
     #[test]
     fn reduce_scale_mode_ionian() {
-        let state = reduce(Action::SetScaleMode(Mode::Ionian));
+        let state = reduce(Action::SetScaleMode(ScaleMode::Diatonic(Mode::Ionian)));
         assert_eq!(
             state,
             State {
@@ -672,7 +868,7 @@ mod tests {
 
     #[test]
     fn reduce_scale_mode_dorian() {
-        let state = reduce(Action::SetScaleMode(Mode::Dorian));
+        let state = reduce(Action::SetScaleMode(ScaleMode::Diatonic(Mode::Dorian)));
         assert_eq!(
             state,
             State {
@@ -690,7 +886,7 @@ mod tests {
 
     #[test]
     fn reduce_scale_mode_phrygian() {
-        let state = reduce(Action::SetScaleMode(Mode::Phrygian));
+        let state = reduce(Action::SetScaleMode(ScaleMode::Diatonic(Mode::Phrygian)));
         assert_eq!(
             state,
             State {
@@ -708,7 +904,7 @@ mod tests {
 
     #[test]
     fn reduce_scale_mode_lydian() {
-        let state = reduce(Action::SetScaleMode(Mode::Lydian));
+        let state = reduce(Action::SetScaleMode(ScaleMode::Diatonic(Mode::Lydian)));
         assert_eq!(
             state,
             State {
@@ -726,7 +922,7 @@ mod tests {
 
     #[test]
     fn reduce_scale_mode_mixolydian() {
-        let state = reduce(Action::SetScaleMode(Mode::Mixolydian));
+        let state = reduce(Action::SetScaleMode(ScaleMode::Diatonic(Mode::Mixolydian)));
         assert_eq!(
             state,
             State {
@@ -744,7 +940,7 @@ mod tests {
 
     #[test]
     fn reduce_scale_mode_aeolian() {
-        let state = reduce(Action::SetScaleMode(Mode::Aeolian));
+        let state = reduce(Action::SetScaleMode(ScaleMode::Diatonic(Mode::Aeolian)));
         assert_eq!(
             state,
             State {
@@ -762,7 +958,7 @@ mod tests {
 
     #[test]
     fn reduce_scale_mode_locrian() {
-        let state = reduce(Action::SetScaleMode(Mode::Locrian));
+        let state = reduce(Action::SetScaleMode(ScaleMode::Diatonic(Mode::Locrian)));
         assert_eq!(
             state,
             State {
@@ -778,6 +974,60 @@ mod tests {
         )
     }
 
+    #[test]
+    fn reduce_scale_mode_user_scale() {
+        let state = reduce(Action::SetScaleMode(ScaleMode::User(1)));
+        assert_eq!(
+            state,
+            State {
+                led1: false,
+                led2: true,
+                led3: false,
+                led4: false,
+                led5: false,
+                led6: false,
+                led7: false,
+                led_sharp: true,
+            }
+        )
+    }
+
+    #[test]
+    fn reduce_user_scale_enabled_note() {
+        let state = reduce(Action::SetUserScaleNote(6, true));
+        assert_eq!(
+            state,
+            State {
+                led1: false,
+                led2: false,
+                led3: false,
+                led4: true,
+                led5: false,
+                led6: false,
+                led7: false,
+                led_sharp: true,
+            }
+        )
+    }
+
+    #[test]
+    fn reduce_user_scale_disabled_note() {
+        let state = reduce(Action::SetUserScaleNote(4, false));
+        assert_eq!(
+            state,
+            State {
+                led1: true,
+                led2: true,
+                led3: false,
+                led4: true,
+                led5: true,
+                led6: true,
+                led7: true,
+                led_sharp: true,
+            }
+        )
+    }
+
     #[test]
     fn reduce_chord_root_degree_1() {
         let state = reduce(Action::SetChordRootDegree(1));
@@ -904,6 +1154,42 @@ mod tests {
         )
     }
 
+    #[test]
+    fn reduce_chord_root_degree_8() {
+        let state = reduce(Action::SetChordRootDegree(8));
+        assert_eq!(
+            state,
+            State {
+                led1: true,
+                led2: false,
+                led3: false,
+                led4: false,
+                led5: false,
+                led6: false,
+                led7: false,
+                led_sharp: true,
+            }
+        )
+    }
+
+    #[test]
+    fn reduce_chord_root_degree_12() {
+        let state = reduce(Action::SetChordRootDegree(12));
+        assert_eq!(
+            state,
+            State {
+                led1: false,
+                led2: false,
+                led3: false,
+                led4: false,
+                led5: true,
+                led6: false,
+                led7: false,
+                led_sharp: true,
+            }
+        )
+    }
+
     #[test]
     fn reduce_chord_root_solo_1() {
         let state = reduce(Action::SetSolo(1));
@@ -1156,6 +1442,168 @@ mod tests {
         )
     }
 
+    #[test]
+    fn reduce_style_3() {
+        let state = reduce(Action::SetStyle(3));
+        assert_eq!(
+            state,
+            State {
+                led1: true,
+                led2: false,
+                led3: true,
+                led4: false,
+                led5: true,
+                led6: false,
+                led7: true,
+                led_sharp: false,
+            }
+        )
+    }
+
+    #[test]
+    fn reduce_style_4() {
+        let state = reduce(Action::SetStyle(4));
+        assert_eq!(
+            state,
+            State {
+                led1: false,
+                led2: true,
+                led3: false,
+                led4: true,
+                led5: false,
+                led6: true,
+                led7: false,
+                led_sharp: true,
+            }
+        )
+    }
+
+    #[test]
+    fn reduce_voicing_close() {
+        let state = reduce(Action::SetVoicing(0));
+        assert_eq!(
+            state,
+            State {
+                led1: true,
+                led2: false,
+                led3: false,
+                led4: false,
+                led5: false,
+                led6: false,
+                led7: false,
+                led_sharp: false,
+            }
+        )
+    }
+
+    #[test]
+    fn reduce_voicing_widest_spread() {
+        let state = reduce(Action::SetVoicing(6));
+        assert_eq!(
+            state,
+            State {
+                led1: false,
+                led2: false,
+                led3: false,
+                led4: false,
+                led5: false,
+                led6: false,
+                led7: true,
+                led_sharp: false,
+            }
+        )
+    }
+
+    #[test]
+    fn reduce_inversion_root_position() {
+        let state = reduce(Action::SetInversion(0));
+        assert_eq!(
+            state,
+            State {
+                led1: false,
+                led2: false,
+                led3: false,
+                led4: true,
+                led5: false,
+                led6: false,
+                led7: false,
+                led_sharp: false,
+            }
+        )
+    }
+
+    #[test]
+    fn reduce_inversion_down() {
+        let state = reduce(Action::SetInversion(-3));
+        assert_eq!(
+            state,
+            State {
+                led1: true,
+                led2: false,
+                led3: false,
+                led4: false,
+                led5: false,
+                led6: false,
+                led7: false,
+                led_sharp: false,
+            }
+        )
+    }
+
+    #[test]
+    fn reduce_sequencer_first_step() {
+        let state = reduce(Action::SetSequencerStep(0));
+        assert_eq!(
+            state,
+            State {
+                led1: true,
+                led2: false,
+                led3: false,
+                led4: false,
+                led5: false,
+                led6: false,
+                led7: false,
+                led_sharp: false,
+            }
+        )
+    }
+
+    #[test]
+    fn reduce_sequencer_eighth_step() {
+        let state = reduce(Action::SetSequencerStep(7));
+        assert_eq!(
+            state,
+            State {
+                led1: false,
+                led2: false,
+                led3: false,
+                led4: false,
+                led5: false,
+                led6: false,
+                led7: false,
+                led_sharp: true,
+            }
+        )
+    }
+
+    #[test]
+    fn reduce_sequencer_tenth_step() {
+        let state = reduce(Action::SetSequencerStep(9));
+        assert_eq!(
+            state,
+            State {
+                led1: true,
+                led2: false,
+                led3: true,
+                led4: true,
+                led5: true,
+                led6: true,
+                led7: true,
+                led_sharp: true,
+            }
+        )
+    }
+
     #[test]
     fn reduce_wavetable_low() {
         let state = reduce(Action::SetWavetable(0.0));
@@ -1427,4 +1875,58 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn reduce_set_configuration_option() {
+        let state = reduce(Action::SetConfigurationOption(2, true));
+        assert_eq!(
+            state,
+            State {
+                led1: false,
+                led2: false,
+                led3: true,
+                led4: false,
+                led5: false,
+                led6: false,
+                led7: false,
+                led_sharp: false,
+            }
+        );
+
+        let state = reduce(Action::SetConfigurationOption(7, false));
+        assert_eq!(
+            state,
+            State {
+                led1: true,
+                led2: true,
+                led3: true,
+                led4: true,
+                led5: true,
+                led6: true,
+                led7: true,
+                led_sharp: false,
+            }
+        );
+    }
+
+    #[test]
+    fn reduce_set_envelope_stage() {
+        let state = reduce(Action::SetEnvelopeStage(Stage::Sustain));
+        assert_eq!(
+            state,
+            State {
+                led1: false,
+                led2: false,
+                led3: true,
+                led4: false,
+                led5: false,
+                led6: false,
+                led7: false,
+                led_sharp: false,
+            }
+        );
+
+        let state = reduce(Action::SetEnvelopeStage(Stage::Idle));
+        assert_eq!(state, State::default());
+    }
 }
@@ -0,0 +1,282 @@
+#[allow(unused_imports)]
+use micromath::F32Ext;
+
+// Segments approach targets placed past their end, so they finish in finite
+// time. Attack aims high above the peak and gets a nearly linear, punchy rise,
+// decay and release aim close to their end and get a steep exponential fall.
+const ATTACK_TARGET_RATIO: f32 = 0.3;
+const DECAY_TARGET_RATIO: f32 = 0.0001;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Stage {
+    Idle,
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Segment {
+    coefficient: f32,
+    base: f32,
+}
+
+impl Segment {
+    fn new(seconds: f32, sample_rate: f32, target: f32, target_ratio: f32) -> Self {
+        let samples = seconds * sample_rate;
+        let coefficient = if samples < 1.0 {
+            0.0
+        } else {
+            (-((1.0 + target_ratio) / target_ratio).ln() / samples).exp()
+        };
+        Self {
+            coefficient,
+            base: target * (1.0 - coefficient),
+        }
+    }
+
+    fn step(&self, value: f32) -> f32 {
+        self.base + value * self.coefficient
+    }
+}
+
+// ADSR envelope with exponential segments, opened by a gate. Retriggering
+// continues from the current level, so it does not click.
+pub struct Envelope {
+    sample_rate: f32,
+    attack_time: f32,
+    decay_time: f32,
+    release_time: f32,
+    sustain: f32,
+    attack: Segment,
+    decay: Segment,
+    release: Segment,
+    stage: Stage,
+    gate: bool,
+    value: f32,
+}
+
+impl Envelope {
+    pub fn new(sample_rate: u32) -> Self {
+        let mut envelope = Self {
+            sample_rate: sample_rate as f32,
+            attack_time: 0.0,
+            decay_time: 0.0,
+            release_time: 0.0,
+            sustain: 1.0,
+            attack: Segment::default(),
+            decay: Segment::default(),
+            release: Segment::default(),
+            stage: Stage::Idle,
+            gate: false,
+            value: 0.0,
+        };
+        envelope.apply_settings();
+        envelope
+    }
+
+    pub fn set_attack(&mut self, seconds: f32) {
+        self.attack_time = seconds;
+        self.apply_settings();
+    }
+
+    pub fn set_decay(&mut self, seconds: f32) {
+        self.decay_time = seconds;
+        self.apply_settings();
+    }
+
+    pub fn set_sustain(&mut self, level: f32) {
+        self.sustain = level.clamp(0.0, 1.0);
+        self.apply_settings();
+    }
+
+    pub fn set_release(&mut self, seconds: f32) {
+        self.release_time = seconds;
+        self.apply_settings();
+    }
+
+    pub fn set_gate(&mut self, gate: bool) {
+        if gate && !self.gate {
+            self.stage = Stage::Attack;
+        } else if !gate && self.gate {
+            self.stage = Stage::Release;
+        }
+        self.gate = gate;
+    }
+
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    pub fn tick(&mut self) -> f32 {
+        match self.stage {
+            Stage::Idle => (),
+            Stage::Attack => {
+                self.value = self.attack.step(self.value);
+                if self.value >= 1.0 {
+                    self.value = 1.0;
+                    self.stage = Stage::Decay;
+                }
+            }
+            Stage::Decay => {
+                self.value = self.decay.step(self.value);
+                if self.value <= self.sustain {
+                    self.value = self.sustain;
+                    self.stage = Stage::Sustain;
+                }
+            }
+            Stage::Sustain => {
+                self.value = self.sustain;
+            }
+            Stage::Release => {
+                self.value = self.release.step(self.value);
+                if self.value <= 0.0 {
+                    self.value = 0.0;
+                    self.stage = Stage::Idle;
+                }
+            }
+        }
+        self.value
+    }
+
+    fn apply_settings(&mut self) {
+        self.attack = Segment::new(
+            self.attack_time,
+            self.sample_rate,
+            1.0 + ATTACK_TARGET_RATIO,
+            ATTACK_TARGET_RATIO,
+        );
+        self.decay = Segment::new(
+            self.decay_time,
+            self.sample_rate,
+            self.sustain - DECAY_TARGET_RATIO,
+            DECAY_TARGET_RATIO,
+        );
+        self.release = Segment::new(
+            self.release_time,
+            self.sample_rate,
+            -DECAY_TARGET_RATIO,
+            DECAY_TARGET_RATIO,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 1000;
+
+    fn create_envelope() -> Envelope {
+        let mut envelope = Envelope::new(SAMPLE_RATE);
+        envelope.set_attack(0.1);
+        envelope.set_decay(0.2);
+        envelope.set_sustain(0.5);
+        envelope.set_release(0.3);
+        envelope
+    }
+
+    fn tick_for(envelope: &mut Envelope, samples: usize) -> f32 {
+        for _ in 1..samples {
+            envelope.tick();
+        }
+        envelope.tick()
+    }
+
+    #[test]
+    fn stay_silent_without_gate() {
+        let mut envelope = create_envelope();
+        assert_eq!(tick_for(&mut envelope, 100), 0.0);
+    }
+
+    #[test]
+    fn reach_peak_at_the_end_of_attack() {
+        let mut envelope = create_envelope();
+        envelope.set_gate(true);
+
+        assert!(tick_for(&mut envelope, 99) < 1.0);
+        assert_relative_eq!(envelope.tick(), 1.0);
+    }
+
+    #[test]
+    fn rise_faster_than_linear_during_attack() {
+        let mut envelope = create_envelope();
+        envelope.set_gate(true);
+
+        let half = tick_for(&mut envelope, 50);
+        assert!(half > 0.55);
+        assert!(half < 0.75);
+    }
+
+    #[test]
+    fn fall_exponentially_to_sustain() {
+        let mut envelope = create_envelope();
+        envelope.set_gate(true);
+        tick_for(&mut envelope, 100);
+
+        let quarter = tick_for(&mut envelope, 50);
+        assert!(quarter < 0.6);
+        assert!(quarter > 0.5);
+
+        assert_relative_eq!(tick_for(&mut envelope, 150), 0.5);
+        assert_relative_eq!(tick_for(&mut envelope, 1000), 0.5);
+    }
+
+    #[test]
+    fn release_to_silence_after_gate_ends() {
+        let mut envelope = create_envelope();
+        envelope.set_sustain(1.0);
+        envelope.set_gate(true);
+        tick_for(&mut envelope, 1000);
+
+        envelope.set_gate(false);
+        let tenth = tick_for(&mut envelope, 30);
+        assert!(tenth < 0.5);
+        assert!(tick_for(&mut envelope, 269) > 0.0);
+        assert_eq!(envelope.tick(), 0.0);
+    }
+
+    #[test]
+    fn retrigger_from_current_level() {
+        let mut envelope = create_envelope();
+        envelope.set_gate(true);
+        tick_for(&mut envelope, 1000);
+        envelope.set_gate(false);
+        let released = tick_for(&mut envelope, 30);
+
+        envelope.set_gate(true);
+        let retriggered = envelope.tick();
+        assert!(retriggered > released);
+        assert!(retriggered - released < 0.05);
+    }
+
+    #[test]
+    fn pass_through_stages_of_the_envelope() {
+        let mut envelope = create_envelope();
+        assert_eq!(envelope.stage(), Stage::Idle);
+
+        envelope.set_gate(true);
+        assert_eq!(envelope.stage(), Stage::Attack);
+        tick_for(&mut envelope, 100);
+        assert_eq!(envelope.stage(), Stage::Decay);
+        tick_for(&mut envelope, 1000);
+        assert_eq!(envelope.stage(), Stage::Sustain);
+
+        envelope.set_gate(false);
+        assert_eq!(envelope.stage(), Stage::Release);
+        tick_for(&mut envelope, 1000);
+        assert_eq!(envelope.stage(), Stage::Idle);
+    }
+
+    #[test]
+    fn jump_without_segment_times() {
+        let mut envelope = Envelope::new(SAMPLE_RATE);
+        envelope.set_sustain(0.7);
+        envelope.set_gate(true);
+        assert_relative_eq!(envelope.tick(), 1.0);
+        assert_relative_eq!(envelope.tick(), 0.7);
+        envelope.set_gate(false);
+        assert_eq!(envelope.tick(), 0.0);
+    }
+}
@@ -0,0 +1,213 @@
+#[allow(unused_imports)]
+use micromath::F32Ext;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Glide {
+    // Takes the given number of seconds, no matter how far the target is.
+    ConstantTime(f32),
+    // Takes the given number of seconds per octave.
+    ConstantRate(f32),
+}
+
+impl Default for Glide {
+    fn default() -> Self {
+        Glide::ConstantTime(0.0)
+    }
+}
+
+// Pitch moves linearly on the logarithmic scale, so the frequency changes
+// exponentially. The target frequency is kept aside so the slide lands on it
+// precisely.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Slide {
+    Stable,
+    Traveling {
+        step: f32,
+        current: f32,
+        target: f32,
+        target_frequency: f32,
+    },
+}
+
+impl Slide {
+    pub fn new(glide: Glide, frequency: f32, target_frequency: f32, sample_rate: f32) -> Self {
+        let current = frequency.log2();
+        let target = target_frequency.log2();
+        let distance = target - current;
+
+        let samples = match glide {
+            Glide::ConstantTime(time) => time * sample_rate,
+            Glide::ConstantRate(time) => time * sample_rate * distance.abs(),
+        };
+
+        if samples < 1.0 {
+            Slide::Stable
+        } else {
+            Slide::Traveling {
+                step: distance / samples,
+                current,
+                target,
+                target_frequency,
+            }
+        }
+    }
+
+    pub fn target_frequency(&self) -> Option<f32> {
+        match self {
+            Slide::Stable => None,
+            Slide::Traveling {
+                target_frequency, ..
+            } => Some(*target_frequency),
+        }
+    }
+
+    // Returns the new frequency if the slide is still in progress or just
+    // finished.
+    pub fn advance(&mut self, samples: usize) -> Option<f32> {
+        match *self {
+            Slide::Stable => None,
+            Slide::Traveling {
+                step,
+                current,
+                target,
+                target_frequency,
+            } => {
+                let new_value = current + step * samples as f32;
+                if (step < 0.0 && new_value <= target) || (step > 0.0 && new_value >= target) {
+                    *self = Slide::Stable;
+                    Some(target_frequency)
+                } else {
+                    *self = Slide::Traveling {
+                        step,
+                        current: new_value,
+                        target,
+                        target_frequency,
+                    };
+                    Some(f32::powf(2.0, new_value))
+                }
+            }
+        }
+    }
+}
+
+// Assign new frequencies to voices playing the closest pitch, so each voice
+// travels the shortest distance. Frequencies left over once all playing voices
+// are taken are assigned to the silent ones in order.
+pub fn match_voices<const N: usize>(
+    playing: [Option<f32>; N],
+    frequencies: [Option<f32>; N],
+) -> [Option<f32>; N] {
+    let mut matched = [None; N];
+    let mut assigned_voices = [false; N];
+    let mut assigned_frequencies = [false; N];
+
+    loop {
+        let mut closest = None;
+        let mut closest_distance = f32::MAX;
+        for (i, playing) in playing.iter().enumerate() {
+            for (j, frequency) in frequencies.iter().enumerate() {
+                if let (Some(playing), Some(frequency), false, false) = (
+                    playing,
+                    frequency,
+                    assigned_voices[i],
+                    assigned_frequencies[j],
+                ) {
+                    let distance = (playing / frequency).max(frequency / playing);
+                    if distance < closest_distance {
+                        closest = Some((i, j));
+                        closest_distance = distance;
+                    }
+                }
+            }
+        }
+
+        if let Some((i, j)) = closest {
+            matched[i] = frequencies[j];
+            assigned_voices[i] = true;
+            assigned_frequencies[j] = true;
+        } else {
+            break;
+        }
+    }
+
+    for (j, frequency) in frequencies.iter().enumerate() {
+        if frequency.is_none() || assigned_frequencies[j] {
+            continue;
+        }
+        if let Some(i) = (0..N).find(|i| !assigned_voices[*i]) {
+            matched[i] = *frequency;
+            assigned_voices[i] = true;
+        }
+    }
+
+    matched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 1000.0;
+
+    #[test]
+    fn slide_with_constant_time() {
+        let mut slide = Slide::new(Glide::ConstantTime(0.1), 100.0, 400.0, SAMPLE_RATE);
+
+        assert_relative_eq!(slide.advance(50).unwrap(), 200.0, max_relative = 0.01);
+        assert_relative_eq!(slide.advance(49).unwrap(), 400.0, max_relative = 0.02);
+        assert_relative_eq!(slide.advance(1).unwrap(), 400.0);
+        assert_eq!(slide, Slide::Stable);
+        assert!(slide.advance(1).is_none());
+    }
+
+    #[test]
+    fn slide_with_constant_rate() {
+        let mut slide = Slide::new(Glide::ConstantRate(0.1), 400.0, 100.0, SAMPLE_RATE);
+
+        assert_relative_eq!(slide.advance(100).unwrap(), 200.0, max_relative = 0.01);
+        assert_relative_eq!(slide.advance(100).unwrap(), 100.0);
+        assert_eq!(slide, Slide::Stable);
+    }
+
+    #[test]
+    fn skip_slide_without_glide_time() {
+        let slide = Slide::new(Glide::ConstantTime(0.0), 100.0, 400.0, SAMPLE_RATE);
+        assert_eq!(slide, Slide::Stable);
+    }
+
+    #[test]
+    fn remember_target_frequency() {
+        let slide = Slide::new(Glide::ConstantTime(0.1), 100.0, 400.0, SAMPLE_RATE);
+        assert_eq!(slide.target_frequency(), Some(400.0));
+    }
+
+    #[test]
+    fn match_voices_to_closest_frequencies() {
+        let playing = [Some(130.8), Some(164.8), Some(196.0)];
+        let frequencies = [Some(174.6), Some(220.0), Some(261.6)];
+
+        assert_eq!(
+            match_voices(playing, frequencies),
+            [Some(261.6), Some(174.6), Some(220.0)]
+        );
+    }
+
+    #[test]
+    fn match_voices_with_new_and_silenced_ones() {
+        let playing = [Some(100.0), None, Some(300.0), Some(400.0)];
+        let frequencies = [Some(290.0), Some(90.0), None, Some(600.0)];
+
+        assert_eq!(
+            match_voices(playing, frequencies),
+            [Some(90.0), None, Some(290.0), Some(600.0)]
+        );
+
+        let playing = [Some(100.0), None, Some(300.0)];
+        let frequencies = [Some(290.0), Some(500.0), Some(600.0)];
+
+        assert_eq!(
+            match_voices(playing, frequencies),
+            [Some(500.0), Some(600.0), Some(290.0)]
+        );
+    }
+}
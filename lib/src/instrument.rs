@@ -5,20 +5,44 @@ use core::ptr;
 #[allow(unused_imports)]
 use micromath::F32Ext;
 
+use crate::arpeggiator::{Arpeggiator, Order};
 use crate::chords;
+use crate::chords::chromatic::{self, Triad};
+use crate::chords::generator::Generator;
+use crate::chords::progression::{self, Progression, Table};
+use crate::chords::quality::Quality;
+use crate::chords::voicing::{self, VOICINGS};
 use crate::detune::DetuneConfig;
+use crate::envelope::{self, Envelope};
+use crate::glide::{self, Glide};
+use crate::modulation::{self, CrossModulation, FrequencyModulation};
 use crate::note::Note;
-use crate::oscillator::Oscillator;
+use crate::oscillator::{Oscillator, Role};
 use crate::overdrive::Overdrive;
 use crate::quantizer;
+use crate::quantizer::diatonic::Algorithm;
 use crate::scales;
+use crate::scales::diatonic::Mode;
+use crate::scales::scale::Scale;
+use crate::sequencer::Step;
+use crate::strum::{self, Strum};
+use crate::sync::Events;
 use crate::taper;
+use crate::tuning::table::Tuning;
+use crate::tuning::temperament::Temperament;
+use crate::warp::{self, Warp};
 use crate::wavetable::Wavetable;
 
+pub use crate::chords::progression::Cadence;
+
 const SOLO_DEGREE: usize = 1;
 const CHORD_DEGREES: usize = 5;
 const DEGREES: usize = CHORD_DEGREES + SOLO_DEGREE;
 
+// After the gate gets disconnected, the envelope is crossfaded into its bypass
+// over this many seconds, so the level does not jump.
+const ENVELOPE_BYPASS_FADE: f32 = 0.005;
+
 const CHORDS_A: [[i8; CHORD_DEGREES]; 19] = [
     [1, 0, 0, 0, 0],
     [1, 3, 5, 0, 0],
@@ -107,7 +131,64 @@ const CHORDS_C: [[i8; CHORD_DEGREES]; 28] = [
     [1, 4, 5, 6, 9],
 ];
 
-const STYLES: [&[[i8; CHORD_DEGREES]]; 3] = [&CHORDS_A, &CHORDS_B, &CHORDS_C];
+const ARPEGGIOS: [Arpeggio; 16] = [
+    // Triads
+    Arpeggio::new([1, 3, 5, 0, 0], Order::Up, 1),
+    Arpeggio::new([1, 3, 5, 0, 0], Order::Down, 1),
+    Arpeggio::new([1, 3, 5, 0, 0], Order::UpDown, 2),
+    Arpeggio::new([1, 3, 5, 0, 0], Order::Up, 3),
+    // Suspended chords
+    Arpeggio::new([1, 2, 5, 0, 0], Order::UpDown, 1),
+    Arpeggio::new([1, 4, 5, 0, 0], Order::UpDown, 2),
+    // Seventh chords
+    Arpeggio::new([1, 3, 5, 7, 0], Order::Up, 1),
+    Arpeggio::new([1, 3, 5, 7, 0], Order::Down, 2),
+    Arpeggio::new([1, 3, 5, 7, 0], Order::UpDown, 2),
+    // Ninth chords
+    Arpeggio::new([1, 3, 5, 7, 9], Order::Up, 1),
+    Arpeggio::new([1, 3, 5, 7, 9], Order::UpDown, 2),
+    Arpeggio::new([1, 3, 5, 7, 9], Order::Random, 2),
+    // Broken chords, played in the order they are written
+    Arpeggio::new([1, 5, 3, 5, 0], Order::AsListed, 1),
+    Arpeggio::new([1, 5, 8, 10, 0], Order::AsListed, 1),
+    Arpeggio::new([1, 5, 10, 12, 7], Order::AsListed, 2),
+    // Chaos
+    Arpeggio::new([1, 3, 5, 7, 9], Order::Random, 4),
+];
+
+// Qualities are built on a chromatically quantized root, independent of the
+// scale.
+const QUALITIES: [Quality; 16] = [
+    // Triads: major, minor, diminished, augmented
+    Quality::new(&[0, 4, 7]),
+    Quality::new(&[0, 3, 7]),
+    Quality::new(&[0, 3, 6]),
+    Quality::new(&[0, 4, 8]),
+    // Suspended chords
+    Quality::new(&[0, 2, 7]),
+    Quality::new(&[0, 5, 7]),
+    // Sixth chords: major, minor
+    Quality::new(&[0, 4, 7, 9]),
+    Quality::new(&[0, 3, 7, 9]),
+    // Seventh chords: major, minor, dominant, half-diminished, diminished
+    Quality::new(&[0, 4, 7, 11]),
+    Quality::new(&[0, 3, 7, 10]),
+    Quality::new(&[0, 4, 7, 10]),
+    Quality::new(&[0, 3, 6, 10]),
+    Quality::new(&[0, 3, 6, 9]),
+    // Ninth chords: added, major, dominant
+    Quality::new(&[0, 4, 7, 14]),
+    Quality::new(&[0, 4, 7, 11, 14]),
+    Quality::new(&[0, 4, 7, 10, 14]),
+];
+
+const STYLES: [Style; 5] = [
+    Style::Chords(&CHORDS_A),
+    Style::Chords(&CHORDS_B),
+    Style::Chords(&CHORDS_C),
+    Style::Arpeggios(&ARPEGGIOS),
+    Style::Qualities(&QUALITIES),
+];
 
 const DETUNES: [[DetuneConfig; DEGREES]; 4] = [
     [
@@ -144,9 +225,61 @@ const DETUNES: [[DetuneConfig; DEGREES]; 4] = [
     ],
 ];
 
+// Number of tones that can be turned up or down an octave.
+pub const MAX_INVERSION: i8 = 3;
+
+pub const USER_SCALES: usize = 4;
+
+// Initial user scales, before they get overwritten by the user
+pub const USER_SCALES_DEFAULT: [Scale; USER_SCALES] = [
+    scales::library::MAJOR_PENTATONIC,
+    scales::library::MINOR_PENTATONIC,
+    scales::library::BLUES,
+    scales::library::WHOLE_TONE,
+];
+
+const MODES: [Mode; 8] = [
+    Mode::Ionian,
+    Mode::Dorian,
+    Mode::Phrygian,
+    Mode::Lydian,
+    Mode::Mixolydian,
+    Mode::Aeolian,
+    Mode::Locrian,
+    Mode::HarmonicMinor,
+];
+
+const MODES_BY_BRIGHTNESS: [Mode; 8] = [
+    Mode::Lydian,
+    Mode::Ionian,
+    Mode::Mixolydian,
+    Mode::Dorian,
+    Mode::Aeolian,
+    Mode::Phrygian,
+    Mode::Locrian,
+    Mode::HarmonicMinor,
+];
+
+// Strategies of splitting the available headroom between playing oscillators.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AmplitudeMode {
+    Stable,
+    Balanced,
+    Rms,
+}
+
+// Scale is selected either from the built-in diatonic modes, or from scales
+// defined by the user.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub enum ScaleMode {
+    Diatonic(Mode),
+    User(usize),
+}
+
 pub struct Instrument<'a> {
     scale_root: DiscreteParameter<Note>,
-    scale_mode: DiscreteParameter<scales::diatonic::Mode>,
+    scale_mode: DiscreteParameter<ScaleMode>,
+    user_scales: [Scale; USER_SCALES],
     solo: Solo,
     solo_quantization: bool,
     solo_raw: Option<f32>,
@@ -158,8 +291,39 @@ pub struct Instrument<'a> {
     chord_quantization: bool,
     selected_detune_index: DiscreteParameter<usize>,
     style_index: DiscreteParameter<usize>,
+    voicing_index: DiscreteParameter<usize>,
+    inversion: DiscreteParameter<i8>,
     amplitude: f32,
+    amplitude_mode: AmplitudeMode,
     overdrive: bool,
+    hard_sync: bool,
+    sync_events: Events,
+    modulated: bool,
+    frequency_modulation: FrequencyModulation,
+    cross_modulation: Option<CrossModulation>,
+    just_intonation: bool,
+    voice_matching: bool,
+    voice_leading: Option<u8>,
+    voiced_notes: [Option<Note>; CHORD_DEGREES],
+    chord_frequencies: [Option<f32>; CHORD_DEGREES],
+    root_voice: usize,
+    strum: Strum<CHORD_DEGREES>,
+    envelope: Envelope,
+    gated: bool,
+    bypass: f32,
+    bypass_step: f32,
+    seed: u32,
+    generator: Option<Generator>,
+    progression: Option<Progression>,
+    transformations: bool,
+    transformations_index: DiscreteParameter<usize>,
+    transformation_step: usize,
+    triad: Option<(Note, Triad)>,
+    generated_degree: Option<u8>,
+    quantization: Algorithm,
+    arpeggiator: Arpeggiator,
+    tuning: Tuning,
+    temperament: Temperament,
     degrees: [Degree<'a>; DEGREES],
 }
 
@@ -167,7 +331,11 @@ impl<'a> Instrument<'a> {
     pub fn new(wavetable_banks: &'a [&'a [Wavetable]], sample_rate: u32) -> Self {
         Self {
             scale_root: DiscreteParameter::new(Note::C1, 0.01),
-            scale_mode: DiscreteParameter::new(scales::diatonic::Ionian, 0.001),
+            scale_mode: DiscreteParameter::new(
+                ScaleMode::Diatonic(scales::diatonic::Ionian),
+                0.001,
+            ),
+            user_scales: USER_SCALES_DEFAULT,
             solo: Solo::Disabled,
             solo_quantization: true,
             solo_raw: None,
@@ -179,8 +347,39 @@ impl<'a> Instrument<'a> {
             chord_quantization: false,
             selected_detune_index: DiscreteParameter::new(0, 0.001),
             style_index: DiscreteParameter::new(0, 0.001),
+            voicing_index: DiscreteParameter::new(0, 0.001),
+            inversion: DiscreteParameter::new(0, 0.001),
             amplitude: 1.0,
+            amplitude_mode: AmplitudeMode::Stable,
             overdrive: false,
+            hard_sync: false,
+            sync_events: Events::new(),
+            modulated: false,
+            frequency_modulation: FrequencyModulation::new(sample_rate),
+            cross_modulation: None,
+            just_intonation: false,
+            voice_matching: false,
+            voice_leading: None,
+            voiced_notes: [None; CHORD_DEGREES],
+            chord_frequencies: [None; CHORD_DEGREES],
+            root_voice: 0,
+            strum: Strum::new(sample_rate),
+            envelope: Envelope::new(sample_rate),
+            gated: false,
+            bypass: 1.0,
+            bypass_step: 1.0 / (ENVELOPE_BYPASS_FADE * sample_rate as f32),
+            seed: 0,
+            generator: None,
+            progression: None,
+            transformations: false,
+            transformations_index: DiscreteParameter::new(0, 0.001),
+            transformation_step: 0,
+            triad: None,
+            generated_degree: None,
+            quantization: Algorithm::Center,
+            arpeggiator: Arpeggiator::new(),
+            tuning: Tuning::equal_temperament(),
+            temperament: Temperament::Equal,
             degrees: [
                 Degree::new(wavetable_banks, sample_rate),
                 Degree::new(wavetable_banks, sample_rate),
@@ -197,50 +396,20 @@ impl<'a> Instrument<'a> {
         &mut self,
         scale_mode: f32,
         modes_ordered_by_brightness: bool,
-    ) -> Option<scales::diatonic::Mode> {
+    ) -> Option<ScaleMode> {
         let original = self.scale_mode();
 
+        let positions = MODES.len() + USER_SCALES;
         let scale_mode = self.scale_mode.offset_raw(scale_mode);
-        self.scale_mode.set(if scale_mode < 1.0 / 8.0 {
-            if modes_ordered_by_brightness {
-                scales::diatonic::Lydian
-            } else {
-                scales::diatonic::Ionian
-            }
-        } else if scale_mode < 2.0 / 8.0 {
-            if modes_ordered_by_brightness {
-                scales::diatonic::Ionian
-            } else {
-                scales::diatonic::Dorian
-            }
-        } else if scale_mode < 3.0 / 8.0 {
-            if modes_ordered_by_brightness {
-                scales::diatonic::Mixolydian
-            } else {
-                scales::diatonic::Phrygian
-            }
-        } else if scale_mode < 4.0 / 8.0 {
-            if modes_ordered_by_brightness {
-                scales::diatonic::Dorian
-            } else {
-                scales::diatonic::Lydian
-            }
-        } else if scale_mode < 5.0 / 8.0 {
-            if modes_ordered_by_brightness {
-                scales::diatonic::Aeolian
-            } else {
-                scales::diatonic::Mixolydian
-            }
-        } else if scale_mode < 6.0 / 8.0 {
+        let position = (scale_mode * positions as f32).max(0.0) as usize;
+        self.scale_mode.set(if position < MODES.len() {
             if modes_ordered_by_brightness {
-                scales::diatonic::Phrygian
+                ScaleMode::Diatonic(MODES_BY_BRIGHTNESS[position])
             } else {
-                scales::diatonic::Aeolian
+                ScaleMode::Diatonic(MODES[position])
             }
-        } else if scale_mode < 7.0 / 8.0 {
-            scales::diatonic::Locrian
         } else {
-            scales::diatonic::HarmonicMinor
+            ScaleMode::User((position - MODES.len()).min(USER_SCALES - 1))
         });
         self.apply_settings();
 
@@ -251,14 +420,36 @@ impl<'a> Instrument<'a> {
         }
     }
 
-    pub fn scale_mode(&self) -> scales::diatonic::Mode {
+    pub fn scale_mode(&self) -> ScaleMode {
         *self.scale_mode
     }
 
+    // Masks that do not form a valid scale are ignored.
+    pub fn set_user_scale(&mut self, index: usize, mask: u16) {
+        if let Some(scale) = Scale::from_mask(mask) {
+            if self.user_scales[index] != scale {
+                self.user_scales[index] = scale;
+                self.apply_settings();
+            }
+        }
+    }
+
+    pub fn user_scale(&self, index: usize) -> u16 {
+        self.user_scales[index].mask()
+    }
+
+    fn scale(&self) -> Scale {
+        match self.scale_mode() {
+            ScaleMode::Diatonic(mode) => mode.scale(),
+            ScaleMode::User(index) => self.user_scales[index],
+        }
+    }
+
     pub fn set_scale_root_voct(&mut self, scale_root: f32) -> Option<Note> {
         let original = self.scale_root();
 
         self.scale_root.set(quantizer::chromatic::quantize(
+            &self.tuning,
             self.scale_root.offset_raw(scale_root),
         ));
         self.apply_settings();
@@ -335,10 +526,146 @@ impl<'a> Instrument<'a> {
         self.overdrive = overdrive;
     }
 
+    // Tune chord tones to pure ratios relative to the chord root, so sustained
+    // chords do not beat.
+    pub fn set_just_intonation(&mut self, just_intonation: bool) {
+        if self.just_intonation != just_intonation {
+            self.just_intonation = just_intonation;
+            self.apply_settings();
+        }
+    }
+
+    pub fn set_glide(&mut self, glide: Glide) {
+        self.degrees.iter_mut().for_each(|d| d.set_glide(glide));
+    }
+
+    // Move each voice to the closest tone of the new chord, instead of keeping
+    // them ordered by degrees. Combined with glide, this makes for smooth
+    // legato transitions.
+    pub fn set_voice_matching(&mut self, voice_matching: bool) {
+        self.voice_matching = voice_matching;
+    }
+
+    // Pick octaves and inversions of chords so their voices move as little as
+    // possible. Notes are kept within the window of given number of
+    // semitones, centered around the chord root.
+    pub fn set_voice_leading(&mut self, window: Option<u8>) {
+        if self.voice_leading != window {
+            self.voice_leading = window;
+            self.apply_settings();
+        }
+    }
+
+    // Delay between onsets of chord voices, zero disables strumming.
+    pub fn set_strum(&mut self, milliseconds: f32) {
+        self.strum.set_spacing(milliseconds);
+    }
+
+    pub fn set_strum_order(&mut self, order: strum::Order) {
+        self.strum.set_order(order);
+    }
+
+    // Without a gate, the instrument drones and the envelope is bypassed.
+    pub fn set_gate(&mut self, gate: Option<bool>) {
+        self.gated = gate.is_some();
+        if self.gated {
+            self.bypass = 0.0;
+        }
+        self.envelope.set_gate(gate.unwrap_or(false));
+    }
+
+    pub fn set_attack(&mut self, seconds: f32) {
+        self.envelope.set_attack(seconds);
+    }
+
+    pub fn set_decay(&mut self, seconds: f32) {
+        self.envelope.set_decay(seconds);
+    }
+
+    pub fn set_sustain(&mut self, level: f32) {
+        self.envelope.set_sustain(level);
+    }
+
+    pub fn set_release(&mut self, seconds: f32) {
+        self.envelope.set_release(seconds);
+    }
+
+    pub fn envelope_stage(&self) -> envelope::Stage {
+        self.envelope.stage()
+    }
+
+    pub fn set_quantization(&mut self, quantization: Algorithm) {
+        if self.quantization != quantization {
+            self.quantization = quantization;
+            self.apply_settings();
+        }
+    }
+
+    pub fn set_tuning(&mut self, tuning: Tuning) {
+        self.tuning = tuning;
+        self.apply_settings();
+    }
+
+    // Built-in tables are parsed only when the selection changes.
+    pub fn set_temperament(&mut self, temperament: Temperament) {
+        if self.temperament != temperament {
+            self.temperament = temperament;
+            self.set_tuning(temperament.tuning());
+        }
+    }
+
     fn solo_enabled(&self) -> bool {
         !matches!(self.solo, Solo::Disabled)
     }
 
+    // Capture the current chord, so it can be recalled later as a step of a
+    // sequence. The chord root is stored relative to the scale root.
+    pub fn step(&self, with_scale_mode: bool) -> Step {
+        let distance = *self.chord_root_note as i32 - self.scale_root() as i32;
+        let chord_root = distance.div_euclid(12) * self.scale().size() as i32
+            + self.chord_root_degree.saturating_sub(1) as i32;
+        let scale_mode = if with_scale_mode {
+            Some(match self.scale_mode() {
+                ScaleMode::Diatonic(mode) => MODES.iter().position(|m| *m == mode).unwrap(),
+                ScaleMode::User(index) => MODES.len() + index,
+            } as u8)
+        } else {
+            None
+        };
+        Step::new(
+            chord_root.clamp(i8::MIN as i32, i8::MAX as i32) as i8,
+            *self.chord_degrees_index as u8,
+            *self.style_index as u8,
+            scale_mode,
+        )
+    }
+
+    // Recall a chord captured by `step`. The scale mode is only changed if the
+    // step holds one.
+    pub fn set_step(&mut self, step: Step) {
+        if let Some(scale_mode) = step.scale_mode() {
+            let position = scale_mode as usize;
+            self.scale_mode.set(if position < MODES.len() {
+                ScaleMode::Diatonic(MODES[position])
+            } else {
+                ScaleMode::User((position - MODES.len()).min(USER_SCALES - 1))
+            });
+        }
+
+        let style = (step.style() as usize).min(STYLES.len() - 1);
+        if style != self.style() {
+            self.arpeggiator.reset();
+        }
+        self.style_index.set(style);
+        self.chord_degrees_index
+            .set((step.chord() as usize).min(STYLES[style].len() - 1));
+
+        self.chord_root_raw = ChordRoot::Degree(step.chord_root());
+        self.apply_settings();
+    }
+
+    // Chord roots below the lowest tonic of the scale are reported as degree
+    // zero, though they play the tonic.
     pub fn chord_root_degree(&self) -> u8 {
         self.chord_root_degree
     }
@@ -354,6 +681,10 @@ impl<'a> Instrument<'a> {
 
         let updated = self.style();
         if original != updated {
+            // Start the pattern from its beginning when switching to arpeggios
+            self.arpeggiator.reset();
+            self.apply_settings();
+
             Some(updated)
         } else {
             None
@@ -364,6 +695,51 @@ impl<'a> Instrument<'a> {
         *self.style_index
     }
 
+    pub fn set_voicing(&mut self, voicing: f32) -> Option<usize> {
+        let original = self.voicing();
+
+        self.voicing_index.set(
+            ((self.voicing_index.offset_raw(voicing) * VOICINGS.len() as f32) as usize)
+                .min(VOICINGS.len() - 1),
+        );
+
+        let updated = self.voicing();
+        if original != updated {
+            self.apply_settings();
+            Some(updated)
+        } else {
+            None
+        }
+    }
+
+    pub fn voicing(&self) -> usize {
+        *self.voicing_index
+    }
+
+    // Inversion is centered around the middle of the range, turning tones of
+    // the chord up above it and down below it.
+    pub fn set_inversion(&mut self, inversion: f32) -> Option<i8> {
+        let original = self.inversion();
+
+        let steps = MAX_INVERSION * 2 + 1;
+        let index = ((self.inversion.offset_raw(inversion) * steps as f32) as i8)
+            .min(steps - 1)
+            .max(0);
+        self.inversion.set(index - MAX_INVERSION);
+
+        let updated = self.inversion();
+        if original != updated {
+            self.apply_settings();
+            Some(updated)
+        } else {
+            None
+        }
+    }
+
+    pub fn inversion(&self) -> i8 {
+        *self.inversion
+    }
+
     pub fn set_chord_quantization(&mut self, chord_quantization: bool) {
         self.chord_quantization = chord_quantization;
     }
@@ -392,9 +768,181 @@ impl<'a> Instrument<'a> {
         }
     }
 
+    // Pitch classes of the chord relative to its root, when the chord is
+    // selected by its quality.
+    pub fn chord_quality(&self) -> Option<u16> {
+        self.quality().map(|quality| quality.mask())
+    }
+
     pub fn chord_degrees(&self) -> [i8; CHORD_DEGREES] {
         let chords = STYLES[*self.style_index];
-        chords[self.chord_degrees_index.min(chords.len() - 1)]
+        chords.degrees(*self.chord_degrees_index)
+    }
+
+    // Advance the arpeggio. Chords of other styles are strummed again, if
+    // strumming is enabled. With the chord generator or progression enabled, a
+    // new chord is picked first, with transformations enabled, the triad is
+    // transformed.
+    pub fn trigger(&mut self) {
+        self.generate_chord();
+        self.transform_chord();
+
+        if self.arpeggio().is_some() {
+            self.arpeggiator.trigger();
+            self.apply_settings();
+        } else if self.strum.enabled() {
+            self.strum.start(self.chord_frequencies);
+            self.apply_chord_frequencies();
+            self.apply_amplitude();
+        }
+    }
+
+    // Generated chords and progressions follow a random sequence given by the
+    // seed. It is scrambled first, so seeds differing only in their lowest
+    // bits lead to unrelated sequences. Only generators started afterwards
+    // use it.
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed.wrapping_mul(0x9E37_79B9);
+    }
+
+    // Generated chords replace the chord root degree and the chord, while the
+    // selected chord root still sets their octave. The adventure ranges from
+    // functional progressions to random ones.
+    pub fn set_chord_generator(&mut self, adventure: Option<f32>) {
+        if let Some(adventure) = adventure {
+            let seed = self.seed;
+            self.generator
+                .get_or_insert_with(|| Generator::new(seed))
+                .set_adventure(adventure);
+        } else if self.generator.is_some() {
+            self.generator = None;
+            if self.progression.is_none() {
+                self.generated_degree = None;
+            }
+            self.set_chord_degrees(self.chord_degrees_raw);
+        }
+    }
+
+    // Progressions replace the chord root degree, walking through phrases of
+    // the given number of bars. The selected chord root still sets their
+    // octave. Progression takes precedence over the chord generator.
+    pub fn set_chord_progression(&mut self, phrase_length: Option<usize>) {
+        if let Some(phrase_length) = phrase_length {
+            let seed = self.seed;
+            self.progression
+                .get_or_insert_with(|| Progression::new(seed))
+                .set_phrase_length(phrase_length);
+        } else if self.progression.is_some() {
+            self.progression = None;
+            if self.generator.is_none() {
+                self.generated_degree = None;
+            }
+            self.apply_settings();
+        }
+    }
+
+    // Neo-Riemannian transformations from the selected sequence are applied
+    // to the triad on each trigger, leaving the selected scale. The triad
+    // starts from the one of the scale on the selected chord root and returns
+    // to it once another chord root or sequence is selected.
+    pub fn set_transformations(&mut self, sequence: Option<f32>) {
+        let original = (self.transformations, *self.transformations_index);
+
+        if let Some(sequence) = sequence {
+            self.transformations = true;
+            self.transformations_index.set(
+                ((self.transformations_index.offset_raw(sequence)
+                    * chromatic::SEQUENCES.len() as f32) as usize)
+                    .min(chromatic::SEQUENCES.len() - 1),
+            );
+        } else {
+            self.transformations = false;
+        }
+
+        if original != (self.transformations, *self.transformations_index) {
+            self.transformation_step = 0;
+            self.triad = None;
+            self.apply_settings();
+        }
+    }
+
+    fn transform_chord(&mut self) {
+        if !self.transformations {
+            return;
+        }
+
+        let sequence = chromatic::SEQUENCES[*self.transformations_index];
+        if let Some((origin, triad)) = self.triad {
+            let transformation = sequence[self.transformation_step % sequence.len()];
+            self.transformation_step = (self.transformation_step + 1) % sequence.len();
+            self.triad = Some((origin, triad.transform(transformation).near(origin)));
+            self.apply_settings();
+        }
+    }
+
+    // Cadence formed by the last chord of the progression, if any.
+    pub fn cadence(&self) -> Option<Cadence> {
+        self.progression
+            .as_ref()
+            .and_then(|progression| progression.cadence())
+    }
+
+    // User scales are told apart by their third.
+    fn progression_table(&self) -> &'static Table {
+        match self.scale_mode() {
+            ScaleMode::Diatonic(Mode::Ionian | Mode::Lydian | Mode::Mixolydian) => {
+                &progression::MAJOR
+            }
+            ScaleMode::Diatonic(Mode::HarmonicMinor) => &progression::HARMONIC_MINOR,
+            ScaleMode::Diatonic(_) => &progression::MINOR,
+            ScaleMode::User(_) if self.scale().semitones().contains(&4) => &progression::MAJOR,
+            ScaleMode::User(_) => &progression::MINOR,
+        }
+    }
+
+    fn generate_chord(&mut self) {
+        if self.progression.is_some() {
+            self.progress_chord();
+            return;
+        }
+
+        let scale = self.scale();
+        let current_degree = self.chord_root_degree.saturating_sub(1) as usize;
+        let current_chord = *self.chord_degrees_index;
+        let chords = STYLES[*self.style_index].len();
+
+        let generator = if let Some(generator) = self.generator.as_mut() {
+            generator
+        } else {
+            return;
+        };
+        let degree = generator.next_degree(scale, current_degree);
+        let chord = generator.next_chord(current_chord, chords);
+
+        self.generated_degree = Some(degree as u8);
+        self.chord_degrees_index.set(chord);
+        self.apply_settings();
+    }
+
+    // Degrees of the transition tables are spread over scales of other sizes
+    // than seven.
+    fn progress_chord(&mut self) {
+        let table = self.progression_table();
+        let size = self.scale().size();
+        let progression = self.progression.as_mut().unwrap();
+
+        let degree = progression.advance(table) * size / progression::DEGREES;
+
+        self.generated_degree = Some(degree as u8);
+        self.apply_settings();
+    }
+
+    fn quality(&self) -> Option<Quality> {
+        STYLES[*self.style_index].quality(*self.chord_degrees_index)
+    }
+
+    fn arpeggio(&self) -> Option<Arpeggio> {
+        STYLES[*self.style_index].arpeggio(*self.chord_degrees_index)
     }
 
     pub fn set_wavetable_bank(&mut self, wavetable_bank: f32) -> Option<usize> {
@@ -405,6 +953,52 @@ impl<'a> Instrument<'a> {
         update
     }
 
+    // Phase of all oscillators is warped before the wavetable lookup, adding
+    // harmonics to the selected wavetable.
+    pub fn set_warp(&mut self, mode: warp::Mode, amount: f32) {
+        let warp = Warp::new(mode, amount);
+        self.degrees.iter_mut().for_each(|d| d.set_warp(warp));
+    }
+
+    // Reset the phase of all chord voices whenever the oscillator of the chord
+    // root wraps. Voices run the given number of times faster than their
+    // frequency, so sweeping the ratio brings up the classic sync sound.
+    pub fn set_hard_sync(&mut self, ratio: Option<f32>) {
+        self.hard_sync = ratio.is_some();
+        let ratio = ratio.unwrap_or(1.0);
+        self.degrees
+            .iter_mut()
+            .for_each(|d| d.set_sync_ratio(ratio));
+    }
+
+    // Chord voices are modulated by the solo voice while it is enabled, or by
+    // a dedicated sine modulator running at the given ratio of the chord root
+    // otherwise. Takes the index and the ratio.
+    pub fn set_frequency_modulation(&mut self, modulation: Option<(f32, f32)>) {
+        self.modulated = modulation.is_some();
+        if let Some((index, ratio)) = modulation {
+            self.frequency_modulation.set_index(index);
+            self.frequency_modulation.set_ratio(ratio);
+        }
+    }
+
+    // Ring or amplitude modulate the chord by the solo voice, mixed with the
+    // dry chord by the given amount. Without the solo voice, the chord is
+    // kept dry.
+    pub fn set_cross_modulation(&mut self, mode: Option<modulation::Mode>, amount: f32) {
+        self.cross_modulation = mode.map(|mode| CrossModulation::new(mode, amount));
+    }
+
+    // Banks form rows of a grid, so the bank can be swept continuously, with
+    // neighbouring banks crossfaded.
+    pub fn set_wavetable_bank_continuous(&mut self, wavetable_bank: f32) -> Option<usize> {
+        let update = self.degrees[0].set_wavetable_bank_continuous(wavetable_bank);
+        self.degrees[1..].iter_mut().for_each(|d| {
+            d.set_wavetable_bank_continuous(wavetable_bank);
+        });
+        update
+    }
+
     pub fn wavetable_bank(&self) -> usize {
         self.degrees[0].wavetable_bank()
     }
@@ -470,24 +1064,51 @@ impl<'a> Instrument<'a> {
         self.amplitude = amplitude;
     }
 
+    pub fn set_amplitude_mode(&mut self, amplitude_mode: AmplitudeMode) {
+        if self.amplitude_mode != amplitude_mode {
+            self.amplitude_mode = amplitude_mode;
+            self.apply_settings();
+        }
+    }
+
     pub fn populate(&mut self, buffer_chord: &mut [f32], buffer_solo: &mut [f32]) {
         zero_slice(buffer_chord);
         zero_slice(buffer_solo);
 
-        if self.solo_enabled() {
-            let solo_degree = self.degrees.len() - 1;
-            self.degrees[solo_degree].populate_add(buffer_solo);
+        if self.strum.advance(buffer_chord.len()) {
+            self.apply_chord_frequencies();
+            self.apply_amplitude();
+        }
 
-            self.degrees[..solo_degree]
-                .iter_mut()
-                .for_each(|d| d.populate_add(buffer_chord));
+        if self.modulated {
+            for (chord, solo) in buffer_chord
+                .chunks_mut(modulation::CHUNK)
+                .zip(buffer_solo.chunks_mut(modulation::CHUNK))
+            {
+                self.populate_degrees(chord, solo);
+            }
         } else {
-            self.degrees[0].populate_add(buffer_chord);
+            self.populate_degrees(buffer_chord, buffer_solo);
+        }
 
-            self.degrees[1..]
-                .iter_mut()
-                .for_each(|d| d.populate_add(buffer_solo));
-        };
+        if let Some(cross_modulation) = self.cross_modulation {
+            if self.solo_enabled() {
+                let level = self.degrees[self.degrees.len() - 1].level();
+                cross_modulation.process(buffer_chord, buffer_solo, level);
+            }
+        }
+
+        if self.gated || self.bypass < 1.0 {
+            for (chord, solo) in buffer_chord.iter_mut().zip(buffer_solo.iter_mut()) {
+                if !self.gated {
+                    self.bypass = (self.bypass + self.bypass_step).min(1.0);
+                }
+                let envelope = self.envelope.tick();
+                let level = envelope + (1.0 - envelope) * self.bypass;
+                *chord *= level;
+                *solo *= level;
+            }
+        }
 
         if self.overdrive {
             let overdrive = Overdrive::new(3.0, 0.8);
@@ -500,83 +1121,297 @@ impl<'a> Instrument<'a> {
         }
     }
 
+    fn populate_degrees(&mut self, buffer_chord: &mut [f32], buffer_solo: &mut [f32]) {
+        let solo_enabled = self.solo_enabled();
+        let hard_sync = self.hard_sync;
+        let solo_degree = self.degrees.len() - 1;
+        let leader = self.leading_voice();
+
+        // The solo voice is rendered first, so it can modulate the chord.
+        if solo_enabled {
+            self.degrees[solo_degree].populate_add(buffer_solo);
+        }
+
+        let mut deviation = [0.0; modulation::CHUNK];
+        let deviation = if self.modulated {
+            let deviation = &mut deviation[..buffer_chord.len()];
+            if solo_enabled {
+                let solo = &self.degrees[solo_degree];
+                self.frequency_modulation.populate_external(
+                    deviation,
+                    buffer_solo,
+                    solo.frequency,
+                    solo.level(),
+                );
+            } else {
+                self.frequency_modulation
+                    .populate_internal(deviation, self.degrees[leader].frequency);
+            }
+            Some(&*deviation)
+        } else {
+            None
+        };
+
+        // The first voice has the chord output for itself unless the solo
+        // takes the other one.
+        let voices = if solo_enabled {
+            solo_degree
+        } else {
+            self.degrees.len()
+        };
+        let separate = |i: usize| i != 0 && !solo_enabled;
+
+        // The leading voice is always rendered first, so wraps of its phase
+        // are known before the other voices follow them.
+        if hard_sync {
+            let buffer = if separate(leader) {
+                &mut *buffer_solo
+            } else {
+                &mut *buffer_chord
+            };
+            self.degrees[leader].populate_add_leading(buffer, &mut self.sync_events, deviation);
+        }
+
+        let events = self.sync_events.as_slice();
+        for (i, degree) in self.degrees[..voices].iter_mut().enumerate() {
+            let buffer = if separate(i) {
+                &mut *buffer_solo
+            } else {
+                &mut *buffer_chord
+            };
+            if !hard_sync {
+                degree.populate_add_modulated(buffer, deviation);
+            } else if i != leader {
+                degree.populate_add_following(buffer, events, deviation);
+            }
+        }
+    }
+
+    // The chord root leads hard sync and sets the frequency of the modulator.
+    // While it is silent, waiting for its strum or left out by the arpeggio,
+    // the first playing voice of the chord takes over.
+    fn leading_voice(&self) -> usize {
+        let chord = &self.degrees[..self.degrees.len() - 1];
+        if chord[self.root_voice].enabled {
+            self.root_voice
+        } else {
+            chord
+                .iter()
+                .position(|d| d.enabled)
+                .unwrap_or(self.root_voice)
+        }
+    }
+
+    // Find the note and the degree of the given number of scale steps from the
+    // scale root.
+    fn resolve_degree(&self, offset: i32) -> (Note, u8) {
+        let interval = if offset >= 0 { offset + 1 } else { offset - 1 };
+        let degree = offset.rem_euclid(self.scale().size() as i32) as u8 + 1;
+        scales::scale::lookup_degree(self.scale_root(), self.scale(), self.scale_root(), interval)
+            .map_or((self.scale_root(), 1), |note| (note, degree))
+    }
+
+    // Chromatic chord roots are displayed by their distance from the scale
+    // root in semitones.
+    fn quantize_chromatic(&self, chord_root_raw: f32) -> (Note, u8) {
+        let note = quantizer::chromatic::quantize(
+            &self.tuning,
+            self.chord_root_note.offset_raw(chord_root_raw),
+        );
+        let degree = (note as i32 - self.scale_root() as i32).rem_euclid(12) as u8 + 1;
+        (note, degree)
+    }
+
     fn apply_settings(&mut self) {
         let last = self.degrees.len() - 1;
 
         let chord_notes = if matches!(self.chord_root_raw, ChordRoot::None) {
-            for degree in self.degrees[..last].iter_mut() {
-                degree.disable();
-            }
+            self.chord_frequencies = [None; CHORD_DEGREES];
+            self.apply_chord_frequencies();
 
             [None; 5]
         } else {
+            let quality = self.quality();
+
             let (chord_root_note, chord_root_degree) = match self.chord_root_raw {
+                ChordRoot::Linear(chord_root_raw) | ChordRoot::Voct(chord_root_raw)
+                    if quality.is_some() =>
+                {
+                    self.quantize_chromatic(chord_root_raw)
+                }
                 ChordRoot::Linear(chord_root_raw) => quantizer::diatonic::quantize_linear(
-                    self.scale_mode(),
+                    self.scale(),
                     self.scale_root(),
                     self.chord_root_note.offset_raw(chord_root_raw),
                 ),
                 ChordRoot::Voct(chord_root_raw) => quantizer::diatonic::quantize_voct(
-                    self.scale_mode(),
+                    &self.tuning,
+                    self.quantization,
+                    self.scale(),
                     self.scale_root(),
                     self.chord_root_note.offset_raw(chord_root_raw),
+                    Some(*self.chord_root_note),
                 ),
+                ChordRoot::Degree(offset) => self.resolve_degree(offset as i32),
                 ChordRoot::None => unreachable!(),
             };
 
             self.chord_root_note.set(chord_root_note);
-            self.chord_root_degree = chord_root_degree;
 
-            let chord_notes = chords::diatonic::build(
-                self.scale_root(),
-                self.scale_mode(),
-                chord_root_note,
-                self.chord_degrees(),
-            );
+            // Generated chords keep the octave of the selected chord root.
+            let (chord_root_note, chord_root_degree) = if let Some(degree) = self.generated_degree {
+                let distance = chord_root_note as i32 - self.scale_root() as i32;
+                self.resolve_degree(
+                    distance.div_euclid(12) * self.scale().size() as i32 + degree as i32,
+                )
+            } else {
+                (chord_root_note, chord_root_degree)
+            };
+            self.chord_root_degree = chord_root_degree;
 
-            for (i, degree) in self.degrees[..last].iter_mut().enumerate() {
-                if let Some(note) = chord_notes[i] {
-                    let frequency = if is_already_used_in_chord(chord_notes, i) {
-                        note.to_freq_f32() * 1.01
-                    } else {
-                        note.to_freq_f32()
-                    };
-                    degree.set_frequency(frequency);
-                    degree.enable();
-                } else {
-                    degree.disable();
-                }
+            if self.transformations {
+                let triad = match self.triad {
+                    Some((origin, triad)) if origin == chord_root_note => triad,
+                    _ => Triad::diatonic(self.scale_root(), self.scale(), chord_root_note),
+                };
+                self.triad = Some((chord_root_note, triad));
             }
 
-            chord_notes
-        };
+            let (chord_root_note, mut chord_notes) = if let Some(quality) = quality {
+                (
+                    chord_root_note,
+                    chords::quality::build(&self.tuning, chord_root_note, quality),
+                )
+            } else if let Some((_, triad)) = self.triad {
+                (
+                    triad.root(),
+                    chords::chromatic::build(&self.tuning, triad, self.chord_degrees()),
+                )
+            } else {
+                (
+                    chord_root_note,
+                    chords::diatonic::build(
+                        &self.tuning,
+                        self.scale_root(),
+                        self.scale(),
+                        chord_root_note,
+                        self.chord_degrees(),
+                    ),
+                )
+            };
+            chord_notes = voicing::invert(*self.inversion, chord_notes);
+            chord_notes = voicing::apply(VOICINGS[*self.voicing_index], chord_notes);
+
+            if let (Some(window), None) = (self.voice_leading, self.arpeggio()) {
+                chord_notes = chords::voice_leading::lead(
+                    self.voiced_notes,
+                    chord_notes,
+                    chord_root_note,
+                    window,
+                );
+            }
+            self.voiced_notes = chord_notes;
 
-        self.solo = if let Some(mut voct) = self.solo_raw {
-            if voct < 0.5 / 12.0 {
-                self.degrees[last].disable();
-                Solo::Silent
+            let arpeggio_step = if let Some(arpeggio) = self.arpeggio() {
+                self.arpeggiator.set_order(arpeggio.order);
+                self.arpeggiator.set_octaves(arpeggio.octaves);
+                Some(self.arpeggiator.step(chord_notes))
+            } else {
+                None
+            };
+
+            let tuning = &self.tuning;
+            let just_intonation = self.just_intonation;
+            let chord_tone_frequency = |note: Note| {
+                if just_intonation {
+                    tuning.frequency(chord_root_note) * chords::just::ratio(chord_root_note, note)
+                } else {
+                    tuning.frequency(note)
+                }
+            };
+
+            let mut frequencies = [None; CHORD_DEGREES];
+            for (i, frequency) in frequencies.iter_mut().enumerate() {
+                *frequency = match (chord_notes[i], arpeggio_step) {
+                    (Some(note), None) => {
+                        if is_already_used_in_chord(chord_notes, i) {
+                            Some(chord_tone_frequency(note) * 1.01)
+                        } else {
+                            Some(chord_tone_frequency(note))
+                        }
+                    }
+                    (Some(note), Some(Some(step))) if step.index == i => {
+                        Some(chord_tone_frequency(note) * (1 << step.octave) as f32)
+                    }
+                    _ => None,
+                };
+            }
+
+            // Voicing and voice matching may move the chord root to another
+            // voice, so it is found by its pitch class, and then followed by
+            // its frequency.
+            let root_frequency = chord_notes
+                .iter()
+                .position(|note| {
+                    matches!(note, Some(note) if note.to_midi_id() % 12 == chord_root_note.to_midi_id() % 12)
+                })
+                .and_then(|i| frequencies[i]);
+
+            if self.voice_matching {
+                let mut playing = [None; CHORD_DEGREES];
+                for (frequency, degree) in playing.iter_mut().zip(self.degrees[..last].iter()) {
+                    if degree.enabled {
+                        *frequency = Some(degree.frequency);
+                    }
+                }
+                frequencies = glide::match_voices(playing, frequencies);
+            }
+
+            if root_frequency.is_some() {
+                self.root_voice = frequencies
+                    .iter()
+                    .position(|frequency| *frequency == root_frequency)
+                    .unwrap_or(0);
+            }
+
+            if self.strum.enabled() && frequencies != self.chord_frequencies {
+                self.strum.start(frequencies);
+            }
+            self.chord_frequencies = frequencies;
+            self.apply_chord_frequencies();
+
+            chord_notes
+        };
+
+        self.solo = if let Some(mut voct) = self.solo_raw {
+            if voct < 0.5 / 12.0 {
+                self.degrees[last].disable();
+                Solo::Silent
             } else {
                 voct = voct.min(10.0);
 
                 self.degrees[last].enable();
 
-                let mut note = match self.solo {
-                    Solo::Enabled { note, .. } => note,
-                    _ => DiscreteParameter::new(Note::C1, 0.01),
+                let (mut note, previous) = match self.solo {
+                    Solo::Enabled { note, .. } => (note, Some(*note)),
+                    _ => (DiscreteParameter::new(Note::C1, 0.01), None),
                 };
 
                 let (new_note, degree) = quantizer::diatonic::quantize_voct(
-                    self.scale_mode(),
+                    &self.tuning,
+                    self.quantization,
+                    self.scale(),
                     self.scale_root(),
                     note.offset_raw(voct),
+                    previous,
                 );
                 note.set(new_note);
 
                 let frequency = if self.solo_quantization {
                     if is_already_used_by_chord(chord_notes, *note) {
-                        note.to_freq_f32() * 1.01
+                        self.tuning.frequency(*note) * 1.01
                     } else {
-                        note.to_freq_f32()
+                        self.tuning.frequency(*note)
                     }
                 } else {
                     Note::C0.to_freq_f32() * 2.0.powf(voct)
@@ -590,7 +1425,30 @@ impl<'a> Instrument<'a> {
             Solo::Disabled
         };
 
-        let target_amplitude = calculate_target_amplitude(&self.degrees);
+        self.apply_amplitude();
+    }
+
+    // Voices waiting for their strum stay silent, fading out of the previous
+    // chord.
+    fn apply_chord_frequencies(&mut self) {
+        let last = self.degrees.len() - 1;
+        for (i, (degree, frequency)) in self.degrees[..last]
+            .iter_mut()
+            .zip(self.chord_frequencies)
+            .enumerate()
+        {
+            match frequency {
+                Some(frequency) if !self.strum.pending(i) => {
+                    degree.set_frequency(frequency);
+                    degree.enable();
+                }
+                _ => degree.disable(),
+            }
+        }
+    }
+
+    fn apply_amplitude(&mut self) {
+        let target_amplitude = calculate_target_amplitude(self.amplitude_mode, &self.degrees);
         let instrument_amplitude = self.amplitude();
         self.degrees
             .iter_mut()
@@ -598,33 +1456,91 @@ impl<'a> Instrument<'a> {
     }
 }
 
-#[cfg(all(feature = "balanced_amplitude", feature = "stable_amplitude"))]
-compile_error!("feature \"balanced_amplitude\" and feature \"stable_amplitude\" cannot be enabled at the same time");
-
-// Amplitude of N mixed voices is not N times higher than the one of a single
-// one. Express perceived amplitude by increasing lower values.  This should
-// make changes between different numbers of oscillators less noticable. The
-// problem with this approach is that changes in size of chord affects loudness
-// of the solo/root output.
-#[cfg(feature = "balanced_amplitude")]
-fn calculate_target_amplitude(degrees: &[Degree]) -> f32 {
-    const COMPENSATION: f32 = 2.0;
+fn calculate_target_amplitude(mode: AmplitudeMode, degrees: &[Degree]) -> f32 {
     let max_oscillators = (DEGREES * OSCILLATORS_IN_DEGREE) as f32;
-    let enabled_oscillators = self
-        .degrees
+    let enabled_oscillators = degrees
         .iter()
-        .fold(0, |a, d| a + d.enabled_oscillators()) as f32;
-    let total_amplitude = (enabled_oscillators + COMPENSATION) / (max_oscillators + COMPENSATION);
-    total_amplitude / enabled_oscillators
+        .fold(0, |a, d| a + d.enabled_oscillators())
+        .max(1) as f32;
+
+    match mode {
+        // The total amplitude of all oscillators combined must be 1. This
+        // produces stable loudness, but since it requires huge amount of
+        // headroom, it suffers from weak signal.
+        AmplitudeMode::Stable => 1.0 / max_oscillators,
+        // Amplitude of N mixed voices is not N times higher than the one of a
+        // single one. Express perceived amplitude by increasing lower values.
+        // This should make changes between different numbers of oscillators
+        // less noticable. The problem with this approach is that changes in
+        // size of chord affects loudness of the solo/root output.
+        AmplitudeMode::Balanced => {
+            const COMPENSATION: f32 = 2.0;
+            let total_amplitude =
+                (enabled_oscillators + COMPENSATION) / (max_oscillators + COMPENSATION);
+            total_amplitude / enabled_oscillators
+        }
+        // RMS of N mixed uncorrelated voices grows with the square root of N.
+        // Compensate for it to keep the loudness constant, while still
+        // leaving enough headroom for all the oscillators.
+        AmplitudeMode::Rms => 1.0 / (max_oscillators * enabled_oscillators).sqrt(),
+    }
 }
 
-// The total amplitude of all oscillators combined must be 1. This produces
-// stable loudness, but since it requires huge amount of headroom, it suffers
-// from weak signal.
-#[cfg(feature = "stable_amplitude")]
-fn calculate_target_amplitude(_: &[Degree]) -> f32 {
-    let max_oscillators = (DEGREES * OSCILLATORS_IN_DEGREE) as f32;
-    1.0 / max_oscillators
+#[derive(Clone, Copy)]
+enum Style {
+    Chords(&'static [[i8; CHORD_DEGREES]]),
+    Arpeggios(&'static [Arpeggio]),
+    Qualities(&'static [Quality]),
+}
+
+impl Style {
+    fn len(&self) -> usize {
+        match self {
+            Style::Chords(chords) => chords.len(),
+            Style::Arpeggios(arpeggios) => arpeggios.len(),
+            Style::Qualities(qualities) => qualities.len(),
+        }
+    }
+
+    fn degrees(&self, index: usize) -> [i8; CHORD_DEGREES] {
+        let index = index.min(self.len() - 1);
+        match self {
+            Style::Chords(chords) => chords[index],
+            Style::Arpeggios(arpeggios) => arpeggios[index].degrees,
+            Style::Qualities(qualities) => qualities[index].degrees(),
+        }
+    }
+
+    fn arpeggio(&self, index: usize) -> Option<Arpeggio> {
+        match self {
+            Style::Chords(_) | Style::Qualities(_) => None,
+            Style::Arpeggios(arpeggios) => Some(arpeggios[index.min(arpeggios.len() - 1)]),
+        }
+    }
+
+    fn quality(&self, index: usize) -> Option<Quality> {
+        match self {
+            Style::Qualities(qualities) => Some(qualities[index.min(qualities.len() - 1)]),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Arpeggio {
+    degrees: [i8; CHORD_DEGREES],
+    order: Order,
+    octaves: u8,
+}
+
+impl Arpeggio {
+    const fn new(degrees: [i8; CHORD_DEGREES], order: Order, octaves: u8) -> Self {
+        Self {
+            degrees,
+            order,
+            octaves,
+        }
+    }
 }
 
 fn is_already_used_in_chord(chord_notes: [Option<Note>; CHORD_DEGREES], index: usize) -> bool {
@@ -648,6 +1564,7 @@ fn is_already_used_by_chord(chord_notes: [Option<Note>; CHORD_DEGREES], note: No
 enum ChordRoot {
     Linear(f32),
     Voct(f32),
+    Degree(i8),
     None,
 }
 
@@ -683,9 +1600,9 @@ impl<'a> Degree<'a> {
             wavetable_banks,
             selected_wavetable_bank: DiscreteParameter::new(0, 0.001),
             oscillators: [
-                Oscillator::new(wavetable_banks[0], sample_rate),
-                Oscillator::new(wavetable_banks[0], sample_rate),
-                Oscillator::new(wavetable_banks[0], sample_rate),
+                Oscillator::new(wavetable_banks, sample_rate),
+                Oscillator::new(wavetable_banks, sample_rate),
+                Oscillator::new(wavetable_banks, sample_rate),
             ],
             enabled: false,
             target_amplitude: 0.0,
@@ -697,6 +1614,20 @@ impl<'a> Degree<'a> {
         self.apply_settings();
     }
 
+    pub fn set_glide(&mut self, glide: Glide) {
+        self.oscillators.iter_mut().for_each(|o| o.set_glide(glide));
+    }
+
+    pub fn set_warp(&mut self, warp: Warp) {
+        self.oscillators.iter_mut().for_each(|o| o.set_warp(warp));
+    }
+
+    pub fn set_sync_ratio(&mut self, sync_ratio: f32) {
+        self.oscillators
+            .iter_mut()
+            .for_each(|o| o.set_sync_ratio(sync_ratio));
+    }
+
     pub fn set_detune(&mut self, detune_config: DetuneConfig, detune_phase: f32) {
         self.detune_config = detune_config;
         self.detune_phase = detune_phase;
@@ -713,7 +1644,6 @@ impl<'a> Degree<'a> {
         self.apply_settings();
     }
 
-    #[cfg(feature = "balanced_amplitude")]
     pub fn enabled_oscillators(&self) -> usize {
         if !self.enabled {
             return 0;
@@ -744,14 +1674,14 @@ impl<'a> Degree<'a> {
 
         match self.detune_config {
             DetuneConfig::Disabled => {
-                self.oscillators[0].frequency = self.frequency;
+                self.oscillators[0].set_frequency(self.frequency);
                 self.oscillators[0].set_amplitude(target_amplitude);
                 self.oscillators[1..]
                     .iter_mut()
                     .for_each(|o| o.set_amplitude(0.0));
             }
             DetuneConfig::SingleSide(min, max, voices) => {
-                self.oscillators[0].frequency = self.frequency;
+                self.oscillators[0].set_frequency(self.frequency);
 
                 for (i, oscillator) in self.oscillators[1..voices].iter_mut().enumerate() {
                     let detune_delta = max - min;
@@ -761,7 +1691,7 @@ impl<'a> Degree<'a> {
                     } else {
                         (min + detune_delta * taper::log(self.detune_phase)) * stage
                     };
-                    oscillator.frequency = self.frequency * detune;
+                    oscillator.set_frequency(self.frequency * detune);
                 }
 
                 self.oscillators[..voices]
@@ -775,7 +1705,7 @@ impl<'a> Degree<'a> {
                 let start = if voices % 2 == 0 { 0 } else { 1 };
 
                 if start > 0 {
-                    self.oscillators[0].frequency = self.frequency;
+                    self.oscillators[0].set_frequency(self.frequency);
                 }
 
                 for (i, pair) in self.oscillators[start..voices]
@@ -785,8 +1715,8 @@ impl<'a> Degree<'a> {
                     let detune_delta = max - min;
                     let stage = (i + 1) as f32;
                     let detune = (min + detune_delta * taper::log(self.detune_phase)) * stage;
-                    pair[0].frequency = self.frequency * (1.0 / detune);
-                    pair[1].frequency = self.frequency * detune;
+                    pair[0].set_frequency(self.frequency * (1.0 / detune));
+                    pair[1].set_frequency(self.frequency * detune);
                 }
 
                 self.oscillators[..voices]
@@ -805,7 +1735,7 @@ impl<'a> Degree<'a> {
                 };
                 let detune_delta = max - min;
                 let detune = min + detune_delta * taper::log(detune_phase);
-                self.oscillators[0].frequency = self.frequency * detune;
+                self.oscillators[0].set_frequency(self.frequency * detune);
 
                 self.oscillators[0].set_amplitude(target_amplitude);
                 self.oscillators[1..]
@@ -824,10 +1754,27 @@ impl<'a> Degree<'a> {
                 .min(self.wavetable_banks.len() - 1),
         );
 
-        let wavetable_bank = self.wavetable_banks[self.wavetable_bank()];
+        let wavetable_bank = self.wavetable_bank() as f32;
         self.oscillators
             .iter_mut()
-            .for_each(|o| o.wavetable_bank = wavetable_bank);
+            .for_each(|o| o.set_wavetable_bank(wavetable_bank));
+
+        if original != self.wavetable_bank() {
+            Some(self.wavetable_bank())
+        } else {
+            None
+        }
+    }
+
+    // The selected bank is the one closest to the position.
+    pub fn set_wavetable_bank_continuous(&mut self, wavetable_bank: f32) -> Option<usize> {
+        let original = self.wavetable_bank();
+
+        let position = wavetable_bank.clamp(0.0, 1.0) * (self.wavetable_banks.len() - 1) as f32;
+        self.selected_wavetable_bank.set(position.round() as usize);
+        self.oscillators
+            .iter_mut()
+            .for_each(|o| o.set_wavetable_bank(position));
 
         if original != self.wavetable_bank() {
             Some(self.wavetable_bank())
@@ -855,6 +1802,43 @@ impl<'a> Degree<'a> {
             .iter_mut()
             .for_each(|o| o.populate_add(buffer));
     }
+
+    pub fn populate_add_modulated(&mut self, buffer: &mut [f32], deviation: Option<&[f32]>) {
+        self.oscillators
+            .iter_mut()
+            .for_each(|o| o.populate_add_synced(buffer, Role::Free, deviation));
+    }
+
+    // The first oscillator leads all the others, including those of other
+    // degrees.
+    pub fn populate_add_leading(
+        &mut self,
+        buffer: &mut [f32],
+        events: &mut Events,
+        deviation: Option<&[f32]>,
+    ) {
+        let (leading, following) = self.oscillators.split_at_mut(1);
+        leading[0].populate_add_synced(buffer, Role::Leading(events), deviation);
+        following.iter_mut().for_each(|o| {
+            o.populate_add_synced(buffer, Role::Following(events.as_slice()), deviation)
+        });
+    }
+
+    pub fn populate_add_following(
+        &mut self,
+        buffer: &mut [f32],
+        events: &[(usize, f32)],
+        deviation: Option<&[f32]>,
+    ) {
+        self.oscillators
+            .iter_mut()
+            .for_each(|o| o.populate_add_synced(buffer, Role::Following(events), deviation));
+    }
+
+    // Nominal level of the output, with all enabled oscillators in phase.
+    pub fn level(&self) -> f32 {
+        self.target_amplitude * self.enabled_oscillators() as f32
+    }
 }
 
 fn zero_slice(slice: &mut [f32]) {
@@ -1249,6 +2233,68 @@ mod tests {
         assert!(old_mode != new_mode);
     }
 
+    #[test]
+    fn select_user_scale_at_the_end_of_the_range() {
+        let mut instrument = create_valid_instrument();
+
+        let new_mode = instrument.set_scale_mode(1.0, false);
+        assert_eq!(new_mode, Some(ScaleMode::User(USER_SCALES - 1)));
+
+        let new_mode = instrument.set_scale_mode(0.0, false);
+        assert_eq!(new_mode, Some(ScaleMode::Diatonic(Mode::Ionian)));
+    }
+
+    #[test]
+    fn build_chords_in_edited_user_scale() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_scale_root_voct(Note::C1.to_voct());
+        instrument.set_scale_mode(8.5 / 12.0, false);
+        assert_eq!(instrument.scale_mode(), ScaleMode::User(0));
+        instrument.set_chord_root_voct(Some(Note::C3.to_voct()));
+        instrument.set_chord_degrees(1.5 / CHORDS_A.len() as f32);
+        assert_relative_eq!(
+            instrument.degrees[2].frequency / instrument.degrees[0].frequency,
+            2.0.powf(9.0 / 12.0),
+            max_relative = 0.001
+        );
+
+        instrument.set_user_scale(0, 0b0100_1001_0101);
+        assert_eq!(instrument.user_scale(0), 0b0100_1001_0101);
+        assert_relative_eq!(
+            instrument.degrees[2].frequency / instrument.degrees[0].frequency,
+            2.0.powf(10.0 / 12.0),
+            max_relative = 0.001
+        );
+    }
+
+    #[test]
+    fn ignore_invalid_user_scale() {
+        let mut instrument = create_valid_instrument();
+        let original = instrument.user_scale(1);
+
+        instrument.set_user_scale(1, 0b1111_1111_1110);
+        assert_eq!(instrument.user_scale(1), original);
+
+        instrument.set_user_scale(1, 0b0000_1001_0001);
+        assert_eq!(instrument.user_scale(1), original);
+    }
+
+    #[test]
+    fn chord_root_quantization_with_hysteresis() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_quantization(Algorithm::Hysteresis(0.5));
+
+        instrument.set_chord_root_voct(Some(Note::C3.to_voct()));
+        let root = instrument.chord_root_degree();
+
+        let new_root = instrument.set_chord_root_voct(Some(Note::C3.to_voct() + 1.2 / 12.0));
+        assert!(new_root.is_none());
+        assert_eq!(instrument.chord_root_degree(), root);
+
+        instrument.set_quantization(Algorithm::Center);
+        assert_ne!(instrument.chord_root_degree(), root);
+    }
+
     #[test]
     fn change_chord_root() {
         let mut instrument = create_valid_instrument();
@@ -1299,6 +2345,191 @@ mod tests {
         assert!(old_bank != new_bank);
     }
 
+    #[test]
+    fn populate_with_warped_phase() {
+        for mode in [warp::Mode::Distortion, warp::Mode::Sync, warp::Mode::Window] {
+            let mut instrument = create_valid_instrument();
+            instrument.set_warp(mode, 0.5);
+            assert_populate(&mut instrument);
+            instrument.set_warp(mode, 10.0);
+            assert_populate(&mut instrument);
+        }
+    }
+
+    #[test]
+    fn populate_with_hard_sync() {
+        let mut instrument = create_valid_instrument();
+        for ratio in [1.0, 2.5, 8.0] {
+            instrument.set_hard_sync(Some(ratio));
+            let mut solo_buffer = [0.0; 32];
+            let mut chord_buffer = [0.0; 32];
+            instrument.populate(&mut solo_buffer, &mut chord_buffer);
+            assert!(chord_buffer.iter().all(|x| x.abs() <= 1.0));
+        }
+        instrument.set_solo_voct(Some(0.5));
+        assert_populate(&mut instrument);
+        instrument.set_hard_sync(None);
+        assert_populate(&mut instrument);
+    }
+
+    #[test]
+    fn populate_with_hard_sync_at_maximum_ratio_on_high_root() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_chord_root_voct(Some(7.5));
+        instrument.set_hard_sync(Some(8.0));
+        let mut solo_buffer = [0.0; 128];
+        let mut chord_buffer = [0.0; 128];
+        for _ in 0..10 {
+            instrument.populate(&mut solo_buffer, &mut chord_buffer);
+            assert!(solo_buffer.iter().all(|x| x.abs() <= 1.0));
+            assert!(chord_buffer.iter().all(|x| x.abs() <= 1.0));
+        }
+    }
+
+    #[test]
+    fn lead_hard_sync_by_chord_root_with_voice_matching() {
+        let mut matched = create_valid_instrument();
+        let mut reference = create_valid_instrument();
+        matched.set_voice_matching(true);
+        matched.set_hard_sync(Some(2.0));
+
+        let mut moved = false;
+        for semitones in [0.0, 5.0, 7.0, 3.0, 0.0] {
+            let chord_root = Some(2.5 + semitones / 12.0);
+            matched.set_chord_root_voct(chord_root);
+            reference.set_chord_root_voct(chord_root);
+            assert_populate(&mut matched);
+
+            let root = reference.degrees[0].frequency;
+            let leader = matched.leading_voice();
+            assert_relative_eq!(matched.degrees[leader].frequency, root);
+            moved |= leader != 0;
+        }
+        assert!(moved);
+    }
+
+    #[test]
+    fn modulate_chord_at_maximum_index_and_ratio_on_high_root() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_solo_voct(None);
+        instrument.set_chord_root_voct(Some(7.5));
+        instrument.set_frequency_modulation(Some((5.0, 7.0)));
+        let mut solo_buffer = [0.0; 128];
+        let mut chord_buffer = [0.0; 128];
+        for _ in 0..10 {
+            instrument.populate(&mut solo_buffer, &mut chord_buffer);
+            assert!(solo_buffer.iter().all(|x| x.abs() <= 1.0));
+            assert!(chord_buffer.iter().all(|x| x.abs() <= 1.0));
+        }
+    }
+
+    #[test]
+    fn modulate_chord_with_internal_modulator() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_frequency_modulation(Some((2.0, 1.5)));
+        assert_populate(&mut instrument);
+        instrument.set_hard_sync(Some(2.0));
+        assert_populate(&mut instrument);
+    }
+
+    #[test]
+    fn modulate_chord_with_solo_voice() {
+        let populate_with = |modulation: Option<(f32, f32)>| {
+            let mut instrument = create_valid_instrument();
+            instrument.set_solo_voct(Some(0.5));
+            instrument.set_frequency_modulation(modulation);
+            let mut chord_buffer = [0.0; 128];
+            let mut solo_buffer = [0.0; 128];
+            instrument.populate(&mut chord_buffer, &mut solo_buffer);
+            (chord_buffer, solo_buffer)
+        };
+
+        let (free_chord, free_solo) = populate_with(None);
+        let (modulated_chord, modulated_solo) = populate_with(Some((1.0, 1.0)));
+
+        assert!(free_solo.iter().any(|x| x.abs() > 0.001));
+
+        for (a, b) in free_solo.iter().zip(modulated_solo.iter()) {
+            assert_relative_eq!(a, b, epsilon = 0.0001);
+        }
+        assert!(free_chord
+            .iter()
+            .zip(modulated_chord.iter())
+            .any(|(a, b)| (a - b).abs() > 0.001));
+    }
+
+    #[test]
+    fn keep_cross_modulated_chord_within_range() {
+        for mode in [modulation::Mode::Ring, modulation::Mode::Amplitude] {
+            for overdrive in [false, true] {
+                let mut instrument = create_valid_instrument();
+                instrument.set_solo_voct(Some(0.5));
+                instrument.set_overdrive(overdrive);
+                instrument.set_cross_modulation(Some(mode), 1.0);
+                for _ in 0..10 {
+                    let mut chord_buffer = [0.0; 128];
+                    let mut solo_buffer = [0.0; 128];
+                    instrument.populate(&mut chord_buffer, &mut solo_buffer);
+                    assert!(chord_buffer.iter().all(|x| x.abs() <= 1.0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ring_modulate_chord_by_solo_voice() {
+        let populate_with = |mode: Option<modulation::Mode>| {
+            let mut instrument = create_valid_instrument();
+            instrument.set_solo_voct(Some(0.5));
+            instrument.set_cross_modulation(mode, 1.0);
+            let mut chord_buffer = [0.0; 128];
+            let mut solo_buffer = [0.0; 128];
+            instrument.populate(&mut chord_buffer, &mut solo_buffer);
+            chord_buffer
+        };
+
+        let dry = populate_with(None);
+        let wet = populate_with(Some(modulation::Mode::Ring));
+        for (d, w) in dry.iter().zip(wet.iter()) {
+            assert!(w.abs() <= d.abs() + 0.0001);
+        }
+        assert!(dry
+            .iter()
+            .zip(wet.iter())
+            .any(|(d, w)| (d - w).abs() > 0.001));
+    }
+
+    #[test]
+    fn sweep_wavetable_bank_continuously() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_wavetable_bank(0.0);
+
+        assert_eq!(instrument.set_wavetable_bank_continuous(0.4), None);
+        assert_populate(&mut instrument);
+        assert_eq!(instrument.set_wavetable_bank_continuous(0.6), Some(1));
+        assert_populate(&mut instrument);
+        assert_eq!(instrument.set_wavetable_bank_continuous(10.0), None);
+        assert_populate(&mut instrument);
+    }
+
+    #[test]
+    fn keep_output_continuous_across_wavetable_banks() {
+        let populate_at = |wavetable_bank: f32| {
+            let mut instrument = create_valid_instrument();
+            instrument.set_wavetable_bank_continuous(wavetable_bank);
+            let mut solo_buffer = [0.0; 64];
+            let mut chord_buffer = [0.0; 64];
+            instrument.populate(&mut solo_buffer, &mut chord_buffer);
+            chord_buffer
+        };
+
+        let below = populate_at(0.999);
+        let above = populate_at(1.0);
+        for (a, b) in below.iter().zip(above.iter()) {
+            assert_relative_eq!(a, b, epsilon = 0.01);
+        }
+    }
+
     #[test]
     fn change_wavetable() {
         let mut instrument = create_valid_instrument();
@@ -1377,4 +2608,671 @@ mod tests {
 
         assert!(old_style != new_style);
     }
+
+    fn create_arpeggio_instrument() -> Instrument<'static> {
+        let mut instrument = create_valid_instrument();
+        instrument.set_style(3.5 / STYLES.len() as f32);
+        instrument.set_chord_degrees(0.0);
+        instrument
+    }
+
+    fn sounding_degrees(instrument: &Instrument) -> [bool; CHORD_DEGREES] {
+        let mut sounding = [false; CHORD_DEGREES];
+        for (i, degree) in instrument.degrees[..CHORD_DEGREES].iter().enumerate() {
+            sounding[i] = degree.enabled;
+        }
+        sounding
+    }
+
+    #[test]
+    fn arpeggio_plays_single_degree_at_a_time() {
+        let mut instrument = create_arpeggio_instrument();
+
+        assert_eq!(
+            sounding_degrees(&instrument),
+            [true, false, false, false, false]
+        );
+
+        instrument.trigger();
+        assert_eq!(
+            sounding_degrees(&instrument),
+            [false, true, false, false, false]
+        );
+
+        instrument.trigger();
+        assert_eq!(
+            sounding_degrees(&instrument),
+            [false, false, true, false, false]
+        );
+
+        instrument.trigger();
+        assert_eq!(
+            sounding_degrees(&instrument),
+            [true, false, false, false, false]
+        );
+    }
+
+    #[test]
+    fn arpeggio_shifts_octaves() {
+        let mut instrument = create_arpeggio_instrument();
+        // Triad going up over three octaves
+        instrument.set_chord_degrees(3.5 / ARPEGGIOS.len() as f32);
+        let root_frequency = instrument.degrees[0].frequency;
+
+        for _ in 0..3 {
+            instrument.trigger();
+        }
+
+        assert!(instrument.degrees[0].enabled);
+        assert_relative_eq!(instrument.degrees[0].frequency, root_frequency * 2.0);
+
+        for _ in 0..3 {
+            instrument.trigger();
+        }
+
+        assert!(instrument.degrees[0].enabled);
+        assert_relative_eq!(instrument.degrees[0].frequency, root_frequency * 4.0);
+    }
+
+    #[test]
+    fn arpeggio_survives_chord_root_change() {
+        let mut instrument = create_arpeggio_instrument();
+        instrument.trigger();
+
+        instrument.set_chord_root_voct(Some(3.0));
+
+        assert_eq!(
+            sounding_degrees(&instrument),
+            [false, true, false, false, false]
+        );
+    }
+
+    #[test]
+    fn trigger_is_ignored_outside_of_arpeggio_style() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_style(0.0);
+        instrument.set_chord_degrees(0.5);
+        let sounding = sounding_degrees(&instrument);
+
+        instrument.trigger();
+
+        assert_eq!(sounding_degrees(&instrument), sounding);
+    }
+
+    #[test]
+    fn populate_arpeggio() {
+        let mut instrument = create_arpeggio_instrument();
+        instrument.trigger();
+        assert_populate(&mut instrument);
+    }
+
+    fn assert_chord_ratios(instrument: &Instrument, ratios: &[f32]) {
+        for (i, ratio) in ratios.iter().enumerate() {
+            assert_relative_eq!(
+                instrument.degrees[i + 1].frequency / instrument.degrees[0].frequency,
+                *ratio,
+                max_relative = 0.0001
+            );
+        }
+    }
+
+    #[test]
+    fn just_intonation_of_major_chord() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_just_intonation(true);
+        instrument.set_chord_root_voct(Some(Note::C3.to_voct()));
+        instrument.set_chord_degrees(1.5 / CHORDS_A.len() as f32);
+
+        assert_chord_ratios(&instrument, &[5.0 / 4.0, 3.0 / 2.0]);
+        assert_relative_eq!(
+            instrument.degrees[0].frequency,
+            Note::C3.to_freq_f32(),
+            max_relative = 0.0001
+        );
+    }
+
+    #[test]
+    fn just_intonation_of_minor_chord() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_just_intonation(true);
+        instrument.set_chord_root_voct(Some(Note::A2.to_voct()));
+        instrument.set_chord_degrees(1.5 / CHORDS_A.len() as f32);
+
+        assert_chord_ratios(&instrument, &[6.0 / 5.0, 3.0 / 2.0]);
+    }
+
+    #[test]
+    fn just_intonation_of_seventh_chords() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_just_intonation(true);
+        instrument.set_style(2.5 / STYLES.len() as f32);
+        instrument.set_chord_degrees(4.5 / CHORDS_C.len() as f32);
+
+        instrument.set_chord_root_voct(Some(Note::C3.to_voct()));
+        assert_chord_ratios(&instrument, &[5.0 / 4.0, 3.0 / 2.0, 15.0 / 8.0]);
+
+        instrument.set_chord_root_voct(Some(Note::G3.to_voct()));
+        assert_chord_ratios(&instrument, &[5.0 / 4.0, 3.0 / 2.0, 7.0 / 4.0]);
+
+        instrument.set_chord_root_voct(Some(Note::D3.to_voct()));
+        assert_chord_ratios(&instrument, &[6.0 / 5.0, 3.0 / 2.0, 7.0 / 4.0]);
+    }
+
+    #[test]
+    fn disabling_just_intonation_restores_equal_temperament() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_chord_root_voct(Some(Note::C3.to_voct()));
+        instrument.set_chord_degrees(1.5 / CHORDS_A.len() as f32);
+
+        instrument.set_just_intonation(true);
+        instrument.set_just_intonation(false);
+        assert_chord_ratios(&instrument, &[2.0.powf(4.0 / 12.0), 2.0.powf(7.0 / 12.0)]);
+    }
+
+    fn set_active_degrees(instrument: &mut Instrument, degrees: usize) {
+        // Chords of the third style grow from one to five tones
+        const CHORD_SIZES: [usize; CHORD_DEGREES] = [0, 2, 3, 4, 5];
+        instrument.set_detune(0.0);
+        instrument.set_style(2.5 / STYLES.len() as f32);
+        let chord = CHORD_SIZES[degrees.min(CHORD_DEGREES) - 1];
+        instrument.set_chord_degrees((chord as f32 + 0.5) / CHORDS_C.len() as f32);
+        instrument.set_solo_voct(if degrees > CHORD_DEGREES {
+            Some(3.5)
+        } else {
+            None
+        });
+
+        let enabled_oscillators = instrument
+            .degrees
+            .iter()
+            .fold(0, |a, d| a + d.enabled_oscillators());
+        assert_eq!(enabled_oscillators, degrees);
+    }
+
+    // Peak is the sum of amplitudes of all oscillators, RMS expects them to
+    // be uncorrelated.
+    fn output_level(instrument: &Instrument) -> (f32, f32) {
+        let (peak, power) = instrument
+            .degrees
+            .iter()
+            .fold((0.0, 0.0), |(peak, power), d| {
+                let oscillators = d.enabled_oscillators() as f32;
+                (
+                    peak + d.target_amplitude * oscillators,
+                    power + d.target_amplitude.powi(2) * oscillators,
+                )
+            });
+        (peak, power.sqrt())
+    }
+
+    #[test]
+    fn stable_amplitude_keeps_oscillator_level() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_amplitude_mode(AmplitudeMode::Stable);
+
+        for degrees in 1..=DEGREES {
+            set_active_degrees(&mut instrument, degrees);
+            let (peak, _) = output_level(&instrument);
+            assert_relative_eq!(peak, degrees as f32 / 18.0, max_relative = 0.0001);
+        }
+    }
+
+    #[test]
+    fn balanced_amplitude_compensates_chord_size() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_amplitude_mode(AmplitudeMode::Balanced);
+
+        for degrees in 1..=DEGREES {
+            set_active_degrees(&mut instrument, degrees);
+            let (peak, _) = output_level(&instrument);
+            assert_relative_eq!(peak, (degrees as f32 + 2.0) / 20.0, max_relative = 0.0001);
+        }
+    }
+
+    #[test]
+    fn rms_amplitude_keeps_loudness() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_amplitude_mode(AmplitudeMode::Rms);
+
+        for degrees in 1..=DEGREES {
+            set_active_degrees(&mut instrument, degrees);
+            let (peak, rms) = output_level(&instrument);
+            // 1 / sqrt(18)
+            assert_relative_eq!(rms, 0.235_702_26, max_relative = 0.001);
+            assert!(peak <= 1.0);
+        }
+    }
+
+    #[test]
+    fn assign_chord_tones_by_index_without_voice_matching() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_chord_root_voct(Some(Note::C3.to_voct()));
+        instrument.set_chord_degrees(1.5 / CHORDS_A.len() as f32);
+
+        instrument.set_chord_root_voct(Some(Note::F3.to_voct()));
+        assert_relative_eq!(instrument.degrees[0].frequency, Note::F3.to_freq_f32());
+        assert_relative_eq!(instrument.degrees[1].frequency, Note::A3.to_freq_f32());
+        assert_relative_eq!(instrument.degrees[2].frequency, Note::C4.to_freq_f32());
+    }
+
+    #[test]
+    fn assign_closest_chord_tones_with_voice_matching() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_chord_root_voct(Some(Note::C3.to_voct()));
+        instrument.set_chord_degrees(1.5 / CHORDS_A.len() as f32);
+
+        instrument.set_voice_matching(true);
+        instrument.set_chord_root_voct(Some(Note::F3.to_voct()));
+        assert_relative_eq!(instrument.degrees[0].frequency, Note::C4.to_freq_f32());
+        assert_relative_eq!(instrument.degrees[1].frequency, Note::F3.to_freq_f32());
+        assert_relative_eq!(instrument.degrees[2].frequency, Note::A3.to_freq_f32());
+    }
+
+    #[test]
+    fn glide_chord_change() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_detune(0.0);
+        instrument.set_chord_root_voct(Some(Note::C3.to_voct()));
+        let mut buffer_chord = [0.0; 64];
+        let mut buffer_solo = [0.0; 64];
+        instrument.populate(&mut buffer_chord, &mut buffer_solo);
+
+        instrument.set_glide(Glide::ConstantTime(1.0));
+        instrument.set_chord_root_voct(Some(Note::F3.to_voct()));
+        instrument.populate(&mut buffer_chord, &mut buffer_solo);
+        let frequency = instrument.degrees[0].oscillators[0].frequency;
+        assert!(frequency > Note::C3.to_freq_f32());
+        assert!(frequency < Note::F3.to_freq_f32());
+    }
+
+    #[test]
+    fn gate_opens_and_closes_the_envelope() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_release(0.01);
+        let mut buffer_chord = [0.0; 64];
+        let mut buffer_solo = [0.0; 64];
+        let audible = |buffer: &[f32]| buffer.iter().any(|x| x.abs() > 0.001);
+
+        instrument.set_gate(Some(false));
+        instrument.populate(&mut buffer_chord, &mut buffer_solo);
+        assert!(!audible(&buffer_chord) && !audible(&buffer_solo));
+
+        instrument.set_gate(Some(true));
+        for _ in 0..10 {
+            instrument.populate(&mut buffer_chord, &mut buffer_solo);
+        }
+        assert!(audible(&buffer_chord) && audible(&buffer_solo));
+
+        instrument.set_gate(Some(false));
+        for _ in 0..10 {
+            instrument.populate(&mut buffer_chord, &mut buffer_solo);
+        }
+        assert!(!audible(&buffer_chord) && !audible(&buffer_solo));
+
+        instrument.set_gate(None);
+        instrument.populate(&mut buffer_chord, &mut buffer_solo);
+        assert!(audible(&buffer_chord) && audible(&buffer_solo));
+    }
+
+    #[test]
+    fn fade_into_bypass_when_gate_is_disconnected() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_release(0.01);
+        let mut buffer_chord = [0.0; 64];
+        let mut buffer_solo = [0.0; 64];
+        let peak = |buffer: &[f32]| buffer.iter().fold(0.0_f32, |a, x| a.max(x.abs()));
+
+        instrument.set_gate(Some(false));
+        for _ in 0..10 {
+            instrument.populate(&mut buffer_chord, &mut buffer_solo);
+        }
+
+        instrument.set_gate(None);
+        instrument.populate(&mut buffer_chord, &mut buffer_solo);
+        let faded = peak(&buffer_chord[..8]);
+        for _ in 0..10 {
+            instrument.populate(&mut buffer_chord, &mut buffer_solo);
+        }
+        assert!(faded < peak(&buffer_chord) * 0.1);
+    }
+
+    // Render blocks from silence, returning indices of the first audible block
+    // of the chord root and the rest of the chord.
+    fn first_audible_blocks(instrument: &mut Instrument) -> (Option<usize>, Option<usize>) {
+        let mut root = None;
+        let mut rest = None;
+        for block in 0..50 {
+            let mut buffer_root = [0.0; 32];
+            let mut buffer_rest = [0.0; 32];
+            instrument.populate(&mut buffer_root, &mut buffer_rest);
+            if root.is_none() && buffer_root.iter().any(|x| x.abs() > 0.001) {
+                root = Some(block);
+            }
+            if rest.is_none() && buffer_rest.iter().any(|x| x.abs() > 0.001) {
+                rest = Some(block);
+            }
+        }
+        (root, rest)
+    }
+
+    fn create_strummed_instrument(order: strum::Order) -> Instrument<'static> {
+        let mut instrument = create_valid_instrument();
+        instrument.set_detune(0.0);
+        instrument.set_solo_voct(None);
+        instrument.set_chord_degrees(1.5 / CHORDS_A.len() as f32);
+        instrument.set_chord_root_voct(None);
+        instrument.set_strum(10.0);
+        instrument.set_strum_order(order);
+        instrument
+    }
+
+    #[test]
+    fn strum_chord_up() {
+        let mut instrument = create_strummed_instrument(strum::Order::Up);
+        instrument.set_chord_root_voct(Some(Note::C3.to_voct()));
+
+        let (root, rest) = first_audible_blocks(&mut instrument);
+        assert_eq!(root, Some(0));
+        // 441 samples of the spacing take 14 blocks
+        assert_eq!(rest, Some(13));
+    }
+
+    #[test]
+    fn strum_chord_down() {
+        let mut instrument = create_strummed_instrument(strum::Order::Down);
+        instrument.set_chord_root_voct(Some(Note::C3.to_voct()));
+
+        let (root, rest) = first_audible_blocks(&mut instrument);
+        assert_eq!(rest, Some(0));
+        assert_eq!(root, Some(27));
+    }
+
+    #[test]
+    fn strum_again_on_trigger() {
+        let mut instrument = create_strummed_instrument(strum::Order::Down);
+        instrument.set_chord_root_voct(Some(Note::C3.to_voct()));
+        first_audible_blocks(&mut instrument);
+
+        instrument.trigger();
+        let mut buffer_root = [0.0; 32];
+        let mut buffer_rest = [0.0; 32];
+        for _ in 0..10 {
+            instrument.populate(&mut buffer_root, &mut buffer_rest);
+        }
+        assert!(buffer_root.iter().all(|x| x.abs() < 0.001));
+        assert!(buffer_rest.iter().any(|x| x.abs() > 0.001));
+    }
+
+    #[test]
+    fn voice_leading_through_i_iv_v_i() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_chord_degrees(1.5 / CHORDS_A.len() as f32);
+        instrument.set_chord_root_voct(Some(Note::C3.to_voct()));
+        instrument.set_voice_leading(Some(24));
+
+        for root in [Note::F3, Note::G3, Note::C3] {
+            let previous: [f32; 3] = [
+                instrument.degrees[0].frequency,
+                instrument.degrees[1].frequency,
+                instrument.degrees[2].frequency,
+            ];
+            instrument.set_chord_root_voct(Some(root.to_voct()));
+            for (i, previous) in previous.iter().enumerate() {
+                let ratio = instrument.degrees[i].frequency / previous;
+                assert!(ratio.max(1.0 / ratio) <= 2.0.powf(3.01 / 12.0));
+            }
+        }
+    }
+
+    #[test]
+    fn inversion_raises_the_root() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_chord_degrees(1.5 / CHORDS_A.len() as f32);
+        instrument.set_chord_root_voct(Some(Note::C3.to_voct()));
+        let root = instrument.degrees[0].frequency;
+
+        assert!(instrument.set_inversion(0.5).is_none());
+        assert_eq!(instrument.set_inversion(4.5 / 7.0), Some(1));
+        assert_relative_eq!(
+            instrument.degrees[0].frequency,
+            root * 2.0,
+            max_relative = 0.001
+        );
+        assert_eq!(instrument.set_inversion(0.0), Some(-MAX_INVERSION));
+    }
+
+    #[test]
+    fn recall_captured_step() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_chord_degrees(1.5 / CHORDS_A.len() as f32);
+        instrument.set_chord_root_voct(Some(Note::E3.to_voct()));
+        let degree = instrument.chord_root_degree();
+        let chord = instrument.chord_degrees();
+        let root = instrument.degrees[0].frequency;
+        let step = instrument.step(true);
+
+        instrument.set_scale_mode(0.2, false);
+        instrument.set_chord_degrees(0.0);
+        instrument.set_chord_root_voct(Some(Note::A1.to_voct()));
+        instrument.set_step(step);
+
+        assert_eq!(instrument.scale_mode(), ScaleMode::Diatonic(Mode::Ionian));
+        assert_eq!(instrument.chord_root_degree(), degree);
+        assert_eq!(instrument.chord_degrees(), chord);
+        assert_relative_eq!(instrument.degrees[0].frequency, root, max_relative = 0.001);
+    }
+
+    #[test]
+    fn recall_step_below_scale_root() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_chord_root_voct(Some(1.0));
+        let degree = instrument.chord_root_degree();
+        let root = instrument.degrees[0].frequency;
+        let step = instrument.step(false);
+
+        instrument.set_scale_mode(0.2, false);
+        instrument.set_chord_root_voct(Some(3.0));
+        instrument.set_step(step);
+
+        assert_ne!(instrument.scale_mode(), ScaleMode::Diatonic(Mode::Ionian));
+        instrument.set_scale_mode(0.0, false);
+        assert_eq!(instrument.chord_root_degree(), degree);
+        assert_relative_eq!(instrument.degrees[0].frequency, root, max_relative = 0.001);
+    }
+
+    #[test]
+    fn treat_chord_root_below_lowest_tonic_as_tonic() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_chord_root_voct(Some(-1.0));
+        assert_eq!(instrument.chord_root_degree(), 0);
+
+        let mut recalled = create_valid_instrument();
+        recalled.set_step(instrument.step(false));
+        assert_eq!(recalled.chord_root_degree(), 1);
+        assert_eq!(*recalled.chord_root_note, *instrument.chord_root_note);
+
+        instrument.set_chord_generator(Some(0.5));
+        instrument.trigger();
+        assert!((1..=7).contains(&instrument.chord_root_degree()));
+    }
+
+    #[test]
+    fn generate_chords_on_trigger() {
+        let mut instrument_a = create_valid_instrument();
+        let mut instrument_b = create_valid_instrument();
+        let degree = instrument_a.chord_root_degree();
+        instrument_a.set_chord_generator(Some(0.3));
+        instrument_b.set_chord_generator(Some(0.3));
+
+        let mut changes = 0;
+        for _ in 0..20 {
+            let previous = instrument_a.chord_root_degree();
+            instrument_a.trigger();
+            instrument_b.trigger();
+            assert!((1..=7).contains(&instrument_a.chord_root_degree()));
+            assert_eq!(
+                instrument_a.chord_root_degree(),
+                instrument_b.chord_root_degree()
+            );
+            assert_eq!(instrument_a.chord_degrees(), instrument_b.chord_degrees());
+            if instrument_a.chord_root_degree() != previous {
+                changes += 1;
+            }
+        }
+        assert!(changes > 10);
+
+        instrument_a.set_chord_generator(None);
+        assert_eq!(instrument_a.chord_root_degree(), degree);
+    }
+
+    #[test]
+    fn generate_different_chords_with_different_seeds() {
+        let mut instrument_a = create_valid_instrument();
+        let mut instrument_b = create_valid_instrument();
+        instrument_a.set_seed(1);
+        instrument_b.set_seed(2);
+        instrument_a.set_chord_generator(Some(0.3));
+        instrument_b.set_chord_generator(Some(0.3));
+
+        let mut differences = 0;
+        for _ in 0..20 {
+            instrument_a.trigger();
+            instrument_b.trigger();
+            if instrument_a.chord_root_degree() != instrument_b.chord_root_degree() {
+                differences += 1;
+            }
+        }
+        assert!(differences > 5);
+    }
+
+    #[test]
+    fn progress_through_phrases_on_trigger() {
+        let mut instrument = create_valid_instrument();
+        let degree = instrument.chord_root_degree();
+        instrument.set_chord_progression(Some(4));
+
+        for bar in 1..=12 {
+            instrument.trigger();
+            assert!((1..=7).contains(&instrument.chord_root_degree()));
+            if bar % 4 == 3 {
+                assert_eq!(instrument.chord_root_degree(), 1);
+            }
+        }
+
+        instrument.set_chord_progression(None);
+        assert_eq!(instrument.chord_root_degree(), degree);
+    }
+
+    #[test]
+    fn transform_triad_on_trigger() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_chord_degrees(0.08);
+        assert_eq!(instrument.chord_degrees(), [1, 3, 5, 0, 0]);
+        instrument.set_transformations(Some(0.8));
+        let original = instrument.chord_frequencies;
+
+        instrument.trigger();
+        let parallel = instrument.chord_frequencies;
+        assert_ne!(parallel, original);
+        assert_relative_eq!(parallel[0].unwrap(), original[0].unwrap());
+
+        instrument.trigger();
+        instrument.trigger();
+        assert_ne!(instrument.chord_frequencies, parallel);
+
+        instrument.set_transformations(None);
+        assert_eq!(instrument.chord_frequencies, original);
+    }
+
+    #[test]
+    fn build_chord_quality_on_chromatic_root() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_style(4.5 / STYLES.len() as f32);
+        instrument.set_chord_degrees(0.0);
+        assert_eq!(instrument.chord_quality(), Some(0b1001_0001));
+        assert_eq!(instrument.chord_root_degree(), 7);
+
+        let frequencies = instrument.chord_frequencies;
+        assert_relative_eq!(
+            frequencies[1].unwrap() / frequencies[0].unwrap(),
+            f32::powf(2.0, 4.0 / 12.0),
+            epsilon = 0.001
+        );
+        assert_relative_eq!(
+            frequencies[2].unwrap() / frequencies[0].unwrap(),
+            f32::powf(2.0, 7.0 / 12.0),
+            epsilon = 0.001
+        );
+        assert_eq!(frequencies[3], None);
+
+        instrument.set_style(0.0);
+        assert_eq!(instrument.chord_quality(), None);
+    }
+
+    #[test]
+    fn open_voicing_raises_the_third() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_chord_degrees(1.5 / CHORDS_A.len() as f32);
+        instrument.set_chord_root_voct(Some(Note::C3.to_voct()));
+        let third = instrument.degrees[1].frequency;
+
+        assert_eq!(instrument.set_voicing(4.5 / VOICINGS.len() as f32), Some(4));
+        assert_relative_eq!(
+            instrument.degrees[1].frequency,
+            third * 2.0,
+            max_relative = 0.001
+        );
+        assert!(instrument
+            .set_voicing(4.5 / VOICINGS.len() as f32)
+            .is_none());
+    }
+
+    #[test]
+    fn tuning_is_consulted_for_chord_frequencies() {
+        const JUST_MAJOR: &str = "Just major\n7\n9/8\n5/4\n4/3\n3/2\n5/3\n15/8\n2/1\n";
+        const WHITE_KEYS: &str =
+            "12\n0\n127\n60\n60\n261.63\n7\n0\nx\n1\nx\n2\n3\nx\n4\nx\n5\nx\n6\n";
+
+        let mut instrument = create_valid_instrument();
+        instrument.set_chord_root_voct(Some(Note::C3.to_voct()));
+        instrument.set_chord_degrees(1.5 / CHORDS_A.len() as f32);
+        assert_relative_eq!(
+            instrument.degrees[1].frequency / instrument.degrees[0].frequency,
+            2.0.powf(4.0 / 12.0),
+            max_relative = 0.001
+        );
+
+        instrument.set_tuning(Tuning::parse(JUST_MAJOR, Some(WHITE_KEYS)).unwrap());
+        assert_relative_eq!(
+            instrument.degrees[1].frequency / instrument.degrees[0].frequency,
+            5.0 / 4.0,
+            max_relative = 0.001
+        );
+        assert_relative_eq!(
+            instrument.degrees[2].frequency / instrument.degrees[0].frequency,
+            3.0 / 2.0,
+            max_relative = 0.001
+        );
+    }
+
+    #[test]
+    fn temperament_is_consulted_for_chord_frequencies() {
+        let mut instrument = create_valid_instrument();
+        instrument.set_chord_root_voct(Some(Note::C3.to_voct()));
+        instrument.set_chord_degrees(1.5 / CHORDS_A.len() as f32);
+
+        instrument.set_temperament(Temperament::Pythagorean);
+        assert_relative_eq!(
+            instrument.degrees[1].frequency / instrument.degrees[0].frequency,
+            81.0 / 64.0,
+            max_relative = 0.001
+        );
+
+        instrument.set_temperament(Temperament::Equal);
+        assert_relative_eq!(
+            instrument.degrees[1].frequency / instrument.degrees[0].frequency,
+            2.0.powf(4.0 / 12.0),
+            max_relative = 0.001
+        );
+    }
 }
@@ -13,16 +13,28 @@ extern crate approx;
 
 pub mod config;
 pub mod display;
+pub mod envelope;
+pub mod glide;
 pub mod instrument;
+pub mod modulation;
 pub mod probe;
+pub mod quantizer;
+pub mod scales;
+pub mod sequencer;
 pub mod store;
+pub mod strum;
+pub mod tuning;
+pub mod warp;
 pub mod wavetable;
 
+mod arpeggiator;
 mod chords;
 mod detune;
+mod minblep;
 mod note;
 mod oscillator;
 mod overdrive;
-mod quantizer;
-mod scales;
+mod random;
+mod sync;
+
 mod taper;
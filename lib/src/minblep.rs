@@ -0,0 +1,74 @@
+// Generated by hack/generate_minblep.py, do not edit.
+
+pub const LENGTH: usize = 16;
+pub const OVERSAMPLING: usize = 32;
+
+// Difference between a band-limited step and an ideal one.
+#[rustfmt::skip]
+pub const RESIDUAL: [f32; LENGTH * OVERSAMPLING + 1] = [
+    -1.000000, -1.000000, -1.000000, -1.000000, -0.999999, -0.999998, -0.999996, -0.999993,
+    -0.999989, -0.999982, -0.999972, -0.999958, -0.999939, -0.999914, -0.999879, -0.999834,
+    -0.999776, -0.999702, -0.999608, -0.999490, -0.999345, -0.999166, -0.998948, -0.998685,
+    -0.998369, -0.997992, -0.997546, -0.997020, -0.996403, -0.995684, -0.994850, -0.993887,
+    -0.992781, -0.991516, -0.990074, -0.988437, -0.986587, -0.984504, -0.982166, -0.979551,
+    -0.976636, -0.973398, -0.969813, -0.965854, -0.961496, -0.956714, -0.951480, -0.945767,
+    -0.939550, -0.932802, -0.925495, -0.917606, -0.909107, -0.899976, -0.890188, -0.879722,
+    -0.868558, -0.856677, -0.844062, -0.830698, -0.816573, -0.801678, -0.786006, -0.769553,
+    -0.752317, -0.734303, -0.715515, -0.695965, -0.675664, -0.654632, -0.632889, -0.610460,
+    -0.587377, -0.563672, -0.539383, -0.514552, -0.489225, -0.463453, -0.437288, -0.410787,
+    -0.384012, -0.357026, -0.329896, -0.302691, -0.275483, -0.248347, -0.221357, -0.194590,
+    -0.168125, -0.142039, -0.116411, -0.091319, -0.066841, -0.043051, -0.020024, 0.002168,
+    0.023456, 0.043773, 0.063057, 0.081250, 0.098298, 0.114151, 0.128765, 0.142102,
+    0.154128, 0.164818, 0.174150, 0.182111, 0.188694, 0.193899, 0.197732, 0.200206,
+    0.201342, 0.201168, 0.199716, 0.197029, 0.193151, 0.188136, 0.182043, 0.174936,
+    0.166882, 0.157958, 0.148238, 0.137807, 0.126747, 0.115146, 0.103094, 0.090680,
+    0.077996, 0.065135, 0.052188, 0.039244, 0.026393, 0.013722, 0.001315, -0.010748,
+    -0.022389, -0.033535, -0.044119, -0.054078, -0.063354, -0.071896, -0.079658, -0.086603,
+    -0.092698, -0.097918, -0.102244, -0.105665, -0.108177, -0.109782, -0.110490, -0.110316,
+    -0.109282, -0.107418, -0.104757, -0.101339, -0.097208, -0.092415, -0.087013, -0.081059,
+    -0.074614, -0.067741, -0.060505, -0.052974, -0.045214, -0.037295, -0.029285, -0.021250,
+    -0.013258, -0.005372, 0.002344, 0.009833, 0.017036, 0.023903, 0.030383, 0.036432,
+    0.042010, 0.047083, 0.051620, 0.055596, 0.058992, 0.061793, 0.063992, 0.065583,
+    0.066570, 0.066959, 0.066762, 0.065995, 0.064681, 0.062844, 0.060515, 0.057725,
+    0.054512, 0.050914, 0.046973, 0.042733, 0.038238, 0.033536, 0.028672, 0.023695,
+    0.018651, 0.013587, 0.008548, 0.003579, -0.001278, -0.005983, -0.010498, -0.014788,
+    -0.018819, -0.022564, -0.025997, -0.029094, -0.031839, -0.034215, -0.036212, -0.037823,
+    -0.039043, -0.039874, -0.040318, -0.040383, -0.040079, -0.039421, -0.038424, -0.037107,
+    -0.035493, -0.033605, -0.031470, -0.029113, -0.026564, -0.023852, -0.021008, -0.018062,
+    -0.015045, -0.011988, -0.008919, -0.005869, -0.002865, 0.000066, 0.002898, 0.005608,
+    0.008174, 0.010576, 0.012798, 0.014823, 0.016639, 0.018236, 0.019605, 0.020741,
+    0.021641, 0.022305, 0.022733, 0.022929, 0.022900, 0.022654, 0.022200, 0.021550,
+    0.020716, 0.019715, 0.018560, 0.017269, 0.015859, 0.014348, 0.012754, 0.011097,
+    0.009393, 0.007663, 0.005923, 0.004191, 0.002485, 0.000819, -0.000791, -0.002332,
+    -0.003790, -0.005155, -0.006416, -0.007564, -0.008594, -0.009498, -0.010273, -0.010916,
+    -0.011426, -0.011802, -0.012047, -0.012163, -0.012154, -0.012025, -0.011783, -0.011433,
+    -0.010985, -0.010447, -0.009827, -0.009136, -0.008384, -0.007581, -0.006736, -0.005862,
+    -0.004967, -0.004063, -0.003158, -0.002262, -0.001384, -0.000532, 0.000285, 0.001062,
+    0.001791, 0.002468, 0.003086, 0.003644, 0.004137, 0.004563, 0.004921, 0.005210,
+    0.005430, 0.005582, 0.005668, 0.005689, 0.005649, 0.005551, 0.005399, 0.005196,
+    0.004948, 0.004659, 0.004334, 0.003979, 0.003599, 0.003199, 0.002785, 0.002362,
+    0.001935, 0.001508, 0.001088, 0.000677, 0.000280, -0.000099, -0.000458, -0.000792,
+    -0.001100, -0.001379, -0.001628, -0.001846, -0.002031, -0.002184, -0.002304, -0.002391,
+    -0.002447, -0.002472, -0.002467, -0.002435, -0.002377, -0.002295, -0.002191, -0.002068,
+    -0.001928, -0.001774, -0.001608, -0.001432, -0.001250, -0.001064, -0.000876, -0.000688,
+    -0.000503, -0.000323, -0.000149, 0.000016, 0.000172, 0.000317, 0.000449, 0.000568,
+    0.000674, 0.000766, 0.000843, 0.000905, 0.000953, 0.000987, 0.001007, 0.001013,
+    0.001007, 0.000989, 0.000961, 0.000922, 0.000875, 0.000819, 0.000758, 0.000690,
+    0.000619, 0.000544, 0.000468, 0.000390, 0.000313, 0.000236, 0.000162, 0.000090,
+    0.000022, -0.000042, -0.000102, -0.000156, -0.000205, -0.000248, -0.000285, -0.000315,
+    -0.000340, -0.000359, -0.000371, -0.000378, -0.000380, -0.000376, -0.000368, -0.000355,
+    -0.000339, -0.000319, -0.000297, -0.000271, -0.000244, -0.000216, -0.000187, -0.000157,
+    -0.000127, -0.000097, -0.000069, -0.000041, -0.000015, 0.000010, 0.000032, 0.000053,
+    0.000071, 0.000087, 0.000101, 0.000112, 0.000121, 0.000127, 0.000131, 0.000133,
+    0.000132, 0.000130, 0.000126, 0.000121, 0.000114, 0.000106, 0.000097, 0.000087,
+    0.000077, 0.000066, 0.000055, 0.000045, 0.000034, 0.000024, 0.000014, 0.000005,
+    -0.000003, -0.000010, -0.000017, -0.000023, -0.000027, -0.000031, -0.000034, -0.000036,
+    -0.000037, -0.000038, -0.000037, -0.000036, -0.000035, -0.000032, -0.000030, -0.000027,
+    -0.000024, -0.000020, -0.000017, -0.000013, -0.000010, -0.000006, -0.000003, 0.000000,
+    0.000002, 0.000005, 0.000007, 0.000008, 0.000009, 0.000010, 0.000011, 0.000011,
+    0.000011, 0.000011, 0.000011, 0.000010, 0.000010, 0.000009, 0.000008, 0.000007,
+    0.000006, 0.000005, 0.000005, 0.000004, 0.000003, 0.000003, 0.000002, 0.000002,
+    0.000002, 0.000001, 0.000001, 0.000001, 0.000001, 0.000001, 0.000001, 0.000001,
+    0.000001, 0.000001, 0.000001, 0.000001, 0.000000, 0.000000, 0.000000, 0.000000,
+    0.000000,
+];
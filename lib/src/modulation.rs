@@ -0,0 +1,207 @@
+#[allow(unused_imports)]
+use micromath::F32Ext;
+
+use core::f32::consts::PI;
+
+// Deviation is rendered in chunks of this many samples, so it does not need
+// a buffer as long as the one being populated.
+pub const CHUNK: usize = 32;
+
+// Linear frequency modulation of chord voices. The deviation in hertz is
+// shared by all the carriers, so each of them gets the same sidebands, spaced
+// by the frequency of the modulator. With index above one, the deviation can
+// exceed the frequency of the carrier, running it through zero.
+pub struct FrequencyModulation {
+    index: f32,
+    ratio: f32,
+    phase: f32,
+    sample_rate: f32,
+}
+
+impl FrequencyModulation {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            index: 0.0,
+            ratio: 1.0,
+            phase: 0.0,
+            sample_rate: sample_rate as f32,
+        }
+    }
+
+    pub fn set_index(&mut self, index: f32) {
+        self.index = index;
+    }
+
+    pub fn set_ratio(&mut self, ratio: f32) {
+        self.ratio = ratio;
+    }
+
+    // The dedicated modulator is a sine running at the set ratio of the chord
+    // root.
+    pub fn populate_internal(&mut self, deviation: &mut [f32], root_frequency: f32) {
+        let frequency = root_frequency * self.ratio;
+        let interval_in_samples = frequency / self.sample_rate;
+        let depth = self.index * frequency;
+
+        for x in deviation.iter_mut() {
+            *x = depth * f32::sin(2.0 * PI * self.phase);
+            self.phase += interval_in_samples;
+            if self.phase >= 1.0 {
+                self.phase -= 1.0;
+            }
+        }
+    }
+
+    // The output of the solo voice is normalized by its level, so the depth
+    // of the modulation does not change with the amplitude of the voice.
+    pub fn populate_external(
+        &self,
+        deviation: &mut [f32],
+        signal: &[f32],
+        frequency: f32,
+        level: f32,
+    ) {
+        let depth = if level > 0.0 {
+            self.index * frequency / level
+        } else {
+            0.0
+        };
+
+        for (x, s) in deviation.iter_mut().zip(signal) {
+            *x = depth * s;
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode {
+    // Multiplies the chord by the solo voice, leaving only the sum and
+    // difference frequencies.
+    Ring,
+    // Offsets the solo voice to stay positive, keeping the original chord
+    // tones next to the sidebands.
+    Amplitude,
+}
+
+// Modulates the chord bus by the solo bus, mixed with the dry chord. The solo
+// voice is normalized by its level and the modulator never exceeds one, so
+// the result stays within the range of the dry chord.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CrossModulation {
+    mode: Mode,
+    amount: f32,
+}
+
+impl CrossModulation {
+    pub fn new(mode: Mode, amount: f32) -> Self {
+        Self {
+            mode,
+            amount: amount.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn process(&self, chord: &mut [f32], solo: &[f32], level: f32) {
+        if level <= 0.0 {
+            return;
+        }
+
+        for (x, s) in chord.iter_mut().zip(solo) {
+            let modulator = (s / level).clamp(-1.0, 1.0);
+            let wet = match self.mode {
+                Mode::Ring => *x * modulator,
+                Mode::Amplitude => *x * (1.0 + modulator) / 2.0,
+            };
+            *x = *x * (1.0 - self.amount) + wet * self.amount;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODES: [Mode; 2] = [Mode::Ring, Mode::Amplitude];
+
+    #[test]
+    fn scale_internal_deviation_by_index_and_ratio() {
+        let mut modulation = FrequencyModulation::new(400);
+        modulation.set_index(2.0);
+        modulation.set_ratio(0.5);
+
+        let mut deviation = [0.0; 8];
+        modulation.populate_internal(&mut deviation, 200.0);
+
+        assert_relative_eq!(deviation[0], 0.0, epsilon = 0.01);
+        assert_relative_eq!(deviation[1], 200.0, epsilon = 1.0);
+        assert_relative_eq!(deviation[3], -200.0, epsilon = 1.0);
+        assert_relative_eq!(deviation[5], 200.0, epsilon = 1.0);
+    }
+
+    #[test]
+    fn normalize_external_deviation_by_level() {
+        let mut modulation = FrequencyModulation::new(400);
+        modulation.set_index(2.0);
+
+        let mut deviation = [0.0; 3];
+        modulation.populate_external(&mut deviation, &[0.1, -0.05, 0.0], 100.0, 0.1);
+        assert_relative_eq!(deviation[0], 200.0, epsilon = 0.01);
+        assert_relative_eq!(deviation[1], -100.0, epsilon = 0.01);
+        assert_relative_eq!(deviation[2], 0.0);
+    }
+
+    #[test]
+    fn leave_deviation_empty_for_silent_solo() {
+        let mut modulation = FrequencyModulation::new(400);
+        modulation.set_index(2.0);
+
+        let mut deviation = [1.0; 3];
+        modulation.populate_external(&mut deviation, &[0.1, -0.05, 0.0], 100.0, 0.0);
+        assert_eq!(deviation, [0.0; 3]);
+    }
+
+    #[test]
+    fn multiply_chord_by_solo_with_ring_modulation() {
+        let ring = CrossModulation::new(Mode::Ring, 1.0);
+        let mut chord = [0.5, 0.5, 0.5];
+        ring.process(&mut chord, &[0.2, -0.2, 0.0], 0.2);
+        assert_eq!(chord, [0.5, -0.5, 0.0]);
+    }
+
+    #[test]
+    fn keep_carrier_with_amplitude_modulation() {
+        let am = CrossModulation::new(Mode::Amplitude, 1.0);
+        let mut chord = [0.5, 0.5, 0.5];
+        am.process(&mut chord, &[0.2, -0.2, 0.0], 0.2);
+        assert_eq!(chord, [0.5, 0.0, 0.25]);
+    }
+
+    #[test]
+    fn mix_dry_and_wet_chord() {
+        let ring = CrossModulation::new(Mode::Ring, 0.25);
+        let mut chord = [0.8];
+        ring.process(&mut chord, &[-0.2], 0.2);
+        assert_relative_eq!(chord[0], 0.4);
+    }
+
+    #[test]
+    fn bypass_silent_solo() {
+        let ring = CrossModulation::new(Mode::Ring, 1.0);
+        let mut chord = [0.5];
+        ring.process(&mut chord, &[0.0], 0.0);
+        assert_eq!(chord, [0.5]);
+    }
+
+    #[test]
+    fn never_exceed_range_of_dry_chord() {
+        for mode in MODES {
+            for amount in [0.0, 0.3, 1.0, 10.0] {
+                let modulation = CrossModulation::new(mode, amount);
+                for chord_value in [-1.0, -0.3, 0.0, 0.7, 1.0] {
+                    let mut chord = [chord_value; 5];
+                    modulation.process(&mut chord, &[-3.0, -0.1, 0.0, 0.1, 3.0], 0.1);
+                    assert!(chord.iter().all(|x| x.abs() <= 1.0));
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,10 @@
-use super::wavetable::Wavetable;
+#[allow(unused_imports)]
+use micromath::F32Ext;
+
+use super::glide::{Glide, Slide};
+use super::sync::{Blep, Events};
+use super::warp::Warp;
+use super::wavetable::{BandWavetable, Preparation, Wavetable};
 
 // With 44800 hz, it takes 150 cycles to fade in, 3 ms. This deals with most of
 // the pops between chords with sine wavetable, while not feeling delayed.
@@ -15,28 +21,63 @@ pub enum Amplitude {
 }
 use Amplitude::*;
 
+// With hard sync, one oscillator leads and records wraps of its phase, while
+// the others follow, resetting their phase with each of them.
+pub enum Role<'e> {
+    Free,
+    Leading(&'e mut Events),
+    Following(&'e [(usize, f32)]),
+}
+
 pub struct Oscillator<'a> {
     pub frequency: f32,
     pub phase: f32,
-    pub wavetable_bank: &'a [Wavetable<'a>],
+    wavetable_banks: &'a [&'a [Wavetable<'a>]],
+    previous_wavetable_bank: Option<f32>,
+    wavetable_bank: f32,
     previous_wavetable: Option<f32>,
     wavetable: f32,
+    previous_warp: Option<Warp>,
+    warp: Warp,
+    sync_ratio: f32,
+    blep: Blep,
     amplitude: Amplitude,
+    glide: Glide,
+    slide: Slide,
     sample_rate: f32,
 }
 
 impl<'a> Oscillator<'a> {
-    pub fn new(wavetable_bank: &'a [Wavetable], sample_rate: u32) -> Self {
-        assert!(!wavetable_bank.is_empty());
+    pub fn new(wavetable_banks: &'a [&'a [Wavetable]], sample_rate: u32) -> Self {
+        assert!(!wavetable_banks.is_empty());
+        assert!(wavetable_banks.iter().all(|bank| !bank.is_empty()));
         Self {
             frequency: 20.0,
             phase: 0.0,
             sample_rate: sample_rate as f32,
+            previous_wavetable_bank: None,
+            wavetable_bank: 0.0,
             previous_wavetable: None,
             wavetable: 0.0,
+            previous_warp: None,
+            warp: Warp::default(),
+            sync_ratio: 1.0,
+            blep: Blep::new(),
             amplitude: Stable(0.0),
-            wavetable_bank,
+            glide: Glide::default(),
+            slide: Slide::Stable,
+            wavetable_banks,
+        }
+    }
+
+    // Banks are rows of a grid. Whole numbers select a single bank, fractions
+    // crossfade between two adjacent ones.
+    pub fn set_wavetable_bank(&mut self, wavetable_bank: f32) {
+        let wavetable_bank = wavetable_bank.clamp(0.0, (self.wavetable_banks.len() - 1) as f32);
+        if self.previous_wavetable_bank.is_none() {
+            self.previous_wavetable_bank = Some(wavetable_bank);
         }
+        self.wavetable_bank = wavetable_bank;
     }
 
     pub fn set_wavetable(&mut self, wavetable: f32) {
@@ -50,6 +91,45 @@ impl<'a> Oscillator<'a> {
         self.wavetable
     }
 
+    pub fn set_warp(&mut self, warp: Warp) {
+        if self.previous_warp.is_none() {
+            self.previous_warp = Some(warp);
+        }
+        self.warp = warp;
+    }
+
+    // Following oscillators run this many times faster than their frequency,
+    // so the reset cuts their cycle short.
+    pub fn set_sync_ratio(&mut self, sync_ratio: f32) {
+        self.sync_ratio = sync_ratio;
+    }
+
+    pub fn set_glide(&mut self, glide: Glide) {
+        self.glide = glide;
+    }
+
+    // Silent oscillators jump straight to the new frequency, so voices that
+    // are just being enabled do not slide in.
+    pub fn set_frequency(&mut self, frequency: f32) {
+        let target_frequency = self.slide.target_frequency().unwrap_or(self.frequency);
+
+        // There is no processing involved, so the float should be identical
+        #[allow(clippy::float_cmp)]
+        if frequency == target_frequency {
+            return;
+        }
+
+        self.slide = if matches!(self.amplitude, Stable(amplitude) if amplitude == 0.0) {
+            Slide::Stable
+        } else {
+            Slide::new(self.glide, self.frequency, frequency, self.sample_rate)
+        };
+
+        if self.slide == Slide::Stable {
+            self.frequency = frequency;
+        }
+    }
+
     pub fn set_amplitude(&mut self, amplitude: f32) {
         match self.amplitude {
             Stable(current) => {
@@ -80,64 +160,115 @@ impl<'a> Oscillator<'a> {
     }
 
     pub fn populate_add(&mut self, buffer: &mut [f32]) {
-        macro_rules! lookup_wavetable {
-            ( $wavetable:expr ) => {{
-                let scaled_wavetable = $wavetable * (self.wavetable_bank.len() - 1) as f32;
-                let wavetable_a_index = scaled_wavetable as usize;
-                let wavetable_b_index = if wavetable_a_index == self.wavetable_bank.len() - 1 {
-                    wavetable_a_index
-                } else {
-                    wavetable_a_index + 1
-                };
-
-                let band_wavetable_a = self.wavetable_bank[wavetable_a_index].band(self.frequency);
-                let band_wavetable_b = self.wavetable_bank[wavetable_b_index].band(self.frequency);
-                let xfade = scaled_wavetable - wavetable_a_index as f32;
-
-                (band_wavetable_a, band_wavetable_b, xfade)
-            }};
-        }
+        self.populate_add_synced(buffer, Role::Free, None);
+    }
 
-        let (previous_band_wavetable_a, previous_band_wavetable_b, previous_xfade) = {
-            let wavetable = if let Some(previous_wavetable) = self.previous_wavetable {
-                previous_wavetable
-            } else {
-                self.wavetable
-            };
+    // Deviation, if given, offsets the frequency in hertz for each sample of
+    // the buffer. The resulting frequency may go negative, running the phase
+    // backwards.
+    pub fn populate_add_synced(
+        &mut self,
+        buffer: &mut [f32],
+        role: Role,
+        deviation: Option<&[f32]>,
+    ) {
+        if let Some(frequency) = self.slide.advance(buffer.len()) {
+            self.frequency = frequency;
+        }
 
-            lookup_wavetable!(wavetable)
+        let frequency = match role {
+            Role::Following(_) => self.frequency * self.sync_ratio,
+            _ => self.frequency,
         };
 
-        let (current_band_wavetable_a, current_band_wavetable_b, current_xfade) =
-            { lookup_wavetable!(self.wavetable) };
-
+        // Warping, modulation and sync read the table faster than the base
+        // frequency. Bands are picked for the fastest reading within the
+        // buffer to avoid aliasing. The highest band fades out towards the
+        // Nyquist frequency, so readings any faster are muted.
+        let previous_warp = self.previous_warp.unwrap_or(self.warp);
+        let peak_deviation = deviation.map_or(0.0, |deviation| {
+            deviation.iter().fold(0.0, |peak: f32, d| peak.max(d.abs()))
+        });
+        let band_frequency = ((frequency + peak_deviation)
+            * previous_warp.speedup().max(self.warp.speedup()))
+        .min(self.sample_rate / 2.0);
+
+        let previous_lookup = Lookup::new(
+            self.wavetable_banks,
+            self.previous_wavetable_bank.unwrap_or(self.wavetable_bank),
+            self.previous_wavetable.unwrap_or(self.wavetable),
+            band_frequency,
+        );
+        let current_lookup = Lookup::new(
+            self.wavetable_banks,
+            self.wavetable_bank,
+            self.wavetable,
+            band_frequency,
+        );
+
+        self.previous_wavetable_bank = Some(self.wavetable_bank);
         self.previous_wavetable = Some(self.wavetable);
+        self.previous_warp = Some(self.warp);
 
-        let interval_in_samples = self.frequency / self.sample_rate;
+        let interval_in_samples = frequency / self.sample_rate;
         let buffer_len = buffer.len() as f32;
 
+        let (mut leading, mut following) = match role {
+            Role::Free => (None, [].iter().peekable()),
+            Role::Leading(events) => {
+                events.clear();
+                (Some(events), [].iter().peekable())
+            }
+            Role::Following(events) => (None, events.iter().peekable()),
+        };
+
         for (i, x) in buffer.iter_mut().enumerate() {
-            let preparation = current_band_wavetable_a.prepare(self.phase);
+            let mix = i as f32 / buffer_len;
 
-            let previous_value = {
-                let value_a = previous_band_wavetable_a.read(&preparation);
-                let value_b = previous_band_wavetable_b.read(&preparation);
-                value_a * (1.0 - previous_xfade) + value_b * previous_xfade
+            let warp = previous_warp.interpolate(self.warp, mix);
+            let read = |phase: f32| {
+                let preparation = current_lookup.prepare(warp.apply(phase));
+                let previous_value = previous_lookup.read(&preparation);
+                let current_value = current_lookup.read(&preparation);
+                previous_value * (1.0 - mix) + current_value * mix
             };
 
-            let current_value = {
-                let value_a = current_band_wavetable_a.read(&preparation);
-                let value_b = current_band_wavetable_b.read(&preparation);
-                value_a * (1.0 - current_xfade) + value_b * current_xfade
-            };
+            *x += (read(self.phase) + self.blep.tick()) * self.amplitude();
 
-            let mix = i as f32 / buffer_len;
+            let interval_in_samples = match deviation {
+                Some(deviation) => interval_in_samples + deviation[i] / self.sample_rate,
+                None => interval_in_samples,
+            };
+            self.phase += interval_in_samples;
 
-            *x += (previous_value * (1.0 - mix) + current_value * mix) * self.amplitude();
+            // The reset happened the given fraction of a sample ago. The jump
+            // between the interrupted cycle and the new one is band-limited.
+            if let Some((_, offset)) = following.next_if(|(index, _)| *index == i) {
+                let elapsed = offset * interval_in_samples;
+                let interrupted = wrap(self.phase - elapsed);
+                self.blep.add(read(0.0) - read(interrupted), *offset);
+                self.phase = wrap(elapsed);
+            }
 
-            self.phase += interval_in_samples;
+            // Restarting a warped cycle that did not reach the end of the
+            // table is band-limited the same way. Deep modulation may cross
+            // more than a cycle within a single sample, the offset is then
+            // taken from the last crossing.
             if self.phase >= 1.0 {
-                self.phase -= 1.0;
+                self.phase = wrap(self.phase);
+                let offset = self.phase / interval_in_samples;
+                if let Some(events) = leading.as_mut() {
+                    events.push(i, offset);
+                }
+                if warp.end() > 0.0 {
+                    self.blep.add(read(0.0) - read(warp.end()), offset);
+                }
+            } else if self.phase < 0.0 {
+                self.phase = wrap(self.phase);
+                let offset = (self.phase - 1.0) / interval_in_samples;
+                if warp.end() > 0.0 {
+                    self.blep.add(read(warp.end()) - read(0.0), offset);
+                }
             }
         }
     }
@@ -168,10 +299,109 @@ impl<'a> Oscillator<'a> {
     }
 }
 
+// Two adjacent wavetables of a bank, read at a position between them.
+// Brings the phase back into a single cycle. Tiny negative phases would
+// otherwise round up to a whole cycle, past the end of the table.
+fn wrap(phase: f32) -> f32 {
+    let wrapped = phase - phase.floor();
+    if wrapped < 1.0 {
+        wrapped
+    } else {
+        0.0
+    }
+}
+
+struct Row<'a> {
+    band_wavetable_a: BandWavetable<'a>,
+    band_wavetable_b: BandWavetable<'a>,
+    xfade: f32,
+}
+
+impl<'a> Row<'a> {
+    fn new(wavetable_bank: &'a [Wavetable<'a>], wavetable: f32, frequency: f32) -> Self {
+        let scaled_wavetable = wavetable * (wavetable_bank.len() - 1) as f32;
+        let wavetable_a_index = scaled_wavetable as usize;
+        let wavetable_b_index = if wavetable_a_index == wavetable_bank.len() - 1 {
+            wavetable_a_index
+        } else {
+            wavetable_a_index + 1
+        };
+
+        Self {
+            band_wavetable_a: wavetable_bank[wavetable_a_index].band(frequency),
+            band_wavetable_b: wavetable_bank[wavetable_b_index].band(frequency),
+            xfade: scaled_wavetable - wavetable_a_index as f32,
+        }
+    }
+
+    fn read(&self, preparation: &Preparation) -> f32 {
+        let value_a = self.band_wavetable_a.read(preparation);
+        let value_b = self.band_wavetable_b.read(preparation);
+        value_a * (1.0 - self.xfade) + value_b * self.xfade
+    }
+}
+
+// Bilinear lookup across the wavetable position and the bank. The second row
+// is only read while crossfading between banks. All wavetables share the
+// same layout, so a single preparation serves all of them.
+struct Lookup<'a> {
+    row_a: Row<'a>,
+    row_b: Option<Row<'a>>,
+    yfade: f32,
+}
+
+impl<'a> Lookup<'a> {
+    fn new(
+        wavetable_banks: &'a [&'a [Wavetable<'a>]],
+        wavetable_bank: f32,
+        wavetable: f32,
+        frequency: f32,
+    ) -> Self {
+        let row_a_index = (wavetable_bank as usize).min(wavetable_banks.len() - 1);
+        let yfade = wavetable_bank - row_a_index as f32;
+        let row_b = if yfade > 0.0 && row_a_index < wavetable_banks.len() - 1 {
+            Some(Row::new(
+                wavetable_banks[row_a_index + 1],
+                wavetable,
+                frequency,
+            ))
+        } else {
+            None
+        };
+
+        Self {
+            row_a: Row::new(wavetable_banks[row_a_index], wavetable, frequency),
+            row_b,
+            yfade,
+        }
+    }
+
+    fn prepare(&self, phase: f32) -> Preparation {
+        self.row_a.band_wavetable_a.prepare(phase)
+    }
+
+    fn read(&self, preparation: &Preparation) -> f32 {
+        let value_a = self.row_a.read(preparation);
+        if let Some(row_b) = &self.row_b {
+            let value_b = row_b.read(preparation);
+            value_a * (1.0 - self.yfade) + value_b * self.yfade
+        } else {
+            value_a
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[allow(unused_imports)]
+    use micromath::F32Ext;
+
+    use core::f32::consts::PI;
+
+    use crate::warp;
+
     const TENTH: f32 = 2.0 / 10.0;
     const WAVEFORM: [f32; 11] = [
         -1.0 + 0.0 * TENTH,
@@ -190,19 +420,38 @@ mod tests {
     const FACTORS: [&[f32]; 1] = [&WAVEFORM];
     const SAMPLE_RATE: u32 = 22;
 
+    const HIGH: [f32; 11] = [0.5; 11];
+    const HIGH_FACTORS: [&[f32]; 1] = [&HIGH];
+    const LOW: [f32; 11] = [-0.5; 11];
+    const LOW_FACTORS: [&[f32]; 1] = [&LOW];
+
     lazy_static! {
         static ref WAVETABLE_BANK: [Wavetable<'static>; 1] =
             [Wavetable::new(&FACTORS, SAMPLE_RATE)];
+        static ref WAVETABLE_BANKS: [&'static [Wavetable<'static>]; 1] = [&WAVETABLE_BANK[..]];
+        static ref HIGH_BANK: [Wavetable<'static>; 1] =
+            [Wavetable::new(&HIGH_FACTORS, SAMPLE_RATE)];
+        static ref LOW_BANK: [Wavetable<'static>; 1] = [Wavetable::new(&LOW_FACTORS, SAMPLE_RATE)];
+        static ref GRID: [&'static [Wavetable<'static>]; 3] =
+            [&WAVETABLE_BANK[..], &HIGH_BANK[..], &LOW_BANK[..]];
+        static ref SINE: [f32; 256] =
+            core::array::from_fn(|i| f32::sin(2.0 * PI * i as f32 / 256.0));
+        static ref SINE_FACTORS: [&'static [f32]; 1] = [&SINE[..]];
+        static ref SINE_BANK: [Wavetable<'static>; 1] =
+            [Wavetable::new(&SINE_FACTORS[..], SPECTRUM_SAMPLE_RATE)];
+        static ref SINE_BANKS: [&'static [Wavetable<'static>]; 1] = [&SINE_BANK[..]];
     }
 
+    const SPECTRUM_SAMPLE_RATE: u32 = 1024;
+
     #[test]
     fn initialize() {
-        let _oscillator = Oscillator::new(&WAVETABLE_BANK[..], SAMPLE_RATE);
+        let _oscillator = Oscillator::new(&WAVETABLE_BANKS[..], SAMPLE_RATE);
     }
 
     #[test]
     fn populate() {
-        let mut oscillator = Oscillator::new(&WAVETABLE_BANK[..], SAMPLE_RATE);
+        let mut oscillator = Oscillator::new(&WAVETABLE_BANKS[..], SAMPLE_RATE);
         oscillator.amplitude = Stable(1.0);
         let step = 1.0 / 10.0;
 
@@ -238,7 +487,7 @@ mod tests {
 
     #[test]
     fn interpolation() {
-        let mut oscillator = Oscillator::new(&WAVETABLE_BANK[..], SAMPLE_RATE);
+        let mut oscillator = Oscillator::new(&WAVETABLE_BANKS[..], SAMPLE_RATE);
         oscillator.amplitude = Stable(1.0);
         let mut buffer = [0.0; 11];
         let step = 1.0 / 10.0;
@@ -255,9 +504,260 @@ mod tests {
         }
     }
 
+    fn populate_at(wavetable_bank: f32) -> [f32; 11] {
+        let mut oscillator = Oscillator::new(&GRID[..], SAMPLE_RATE);
+        oscillator.amplitude = Stable(1.0);
+        oscillator.frequency = 1.0;
+        oscillator.set_wavetable_bank(wavetable_bank);
+
+        let mut buffer = [0.0; 11];
+        oscillator.populate_add(&mut buffer);
+        buffer
+    }
+
+    #[test]
+    fn crossfade_between_banks() {
+        let buffer = populate_at(0.5);
+        for (i, x) in buffer.iter().enumerate() {
+            let ramp = -1.0 + i as f32 * 0.1;
+            assert_relative_eq!(*x, ramp * 0.5 + 0.25, epsilon = 0.001);
+        }
+
+        let buffer = populate_at(1.5);
+        for x in buffer.iter() {
+            assert_relative_eq!(*x, 0.0, epsilon = 0.001);
+        }
+    }
+
+    #[test]
+    fn stay_continuous_across_bank_boundaries() {
+        for boundary in [1.0, 2.0] {
+            let below = populate_at(boundary - 0.001);
+            let on = populate_at(boundary);
+            let above = populate_at(boundary + 0.001);
+            for i in 0..11 {
+                assert_relative_eq!(below[i], on[i], epsilon = 0.01);
+                assert_relative_eq!(above[i], on[i], epsilon = 0.01);
+            }
+        }
+    }
+
+    #[test]
+    fn glide_between_banks_within_buffer() {
+        let mut oscillator = Oscillator::new(&GRID[..], SAMPLE_RATE);
+        oscillator.amplitude = Stable(1.0);
+        oscillator.frequency = 1.0;
+        oscillator.set_wavetable_bank(1.0);
+        let mut buffer = [0.0; 11];
+        oscillator.populate_add(&mut buffer);
+
+        oscillator.set_wavetable_bank(2.0);
+        let mut buffer = [0.0; 11];
+        oscillator.populate_add(&mut buffer);
+        assert_relative_eq!(buffer[0], 0.5, epsilon = 0.001);
+        for pair in buffer.windows(2) {
+            assert!(pair[1] < pair[0]);
+        }
+    }
+
+    #[test]
+    fn warp_phase_before_lookup() {
+        let mut warped = Oscillator::new(&WAVETABLE_BANKS[..], SAMPLE_RATE);
+        warped.amplitude = Stable(1.0);
+        warped.frequency = 1.0;
+        warped.set_warp(Warp::new(warp::Mode::Sync, 1.0 / 7.0));
+        let mut warped_buffer = [0.0; 22];
+        warped.populate_add(&mut warped_buffer);
+
+        let mut doubled = Oscillator::new(&WAVETABLE_BANKS[..], SAMPLE_RATE);
+        doubled.amplitude = Stable(1.0);
+        doubled.frequency = 2.0;
+        let mut doubled_buffer = [0.0; 22];
+        doubled.populate_add(&mut doubled_buffer);
+
+        for (a, b) in warped_buffer.iter().zip(doubled_buffer.iter()) {
+            assert_relative_eq!(a, b, epsilon = 0.01);
+        }
+    }
+
+    fn oscillator_at(frequency: f32) -> Oscillator<'static> {
+        let mut oscillator = Oscillator::new(&WAVETABLE_BANKS[..], SAMPLE_RATE);
+        oscillator.amplitude = Stable(1.0);
+        oscillator.frequency = frequency;
+        oscillator
+    }
+
+    #[test]
+    fn record_wraps_of_leading_oscillator() {
+        let mut leading = oscillator_at(8.25);
+        let mut events = Events::new();
+        let mut buffer = [0.0; 7];
+        leading.populate_add_synced(&mut buffer, Role::Leading(&mut events), None);
+
+        assert_eq!(events.as_slice().len(), 2);
+        assert_eq!(events.as_slice()[0].0, 2);
+        assert_relative_eq!(events.as_slice()[0].1, 1.0 / 3.0, epsilon = 0.001);
+        assert_eq!(events.as_slice()[1].0, 5);
+        assert_relative_eq!(events.as_slice()[1].1, 2.0 / 3.0, epsilon = 0.001);
+    }
+
+    #[test]
+    fn reset_following_oscillator_on_wrap() {
+        let mut leading = oscillator_at(5.5);
+        let mut events = Events::new();
+        leading.populate_add_synced(&mut [0.0; 22], Role::Leading(&mut events), None);
+
+        let mut following = oscillator_at(2.75);
+        following.populate_add_synced(&mut [0.0; 22], Role::Following(events.as_slice()), None);
+        assert_relative_eq!(following.phase, 0.25);
+
+        let mut free = oscillator_at(2.75);
+        free.populate_add(&mut [0.0; 22]);
+        assert_relative_eq!(free.phase, 0.75);
+    }
+
+    #[test]
+    fn speed_up_following_oscillator_by_ratio() {
+        let mut following = oscillator_at(2.75);
+        following.set_sync_ratio(2.0);
+        following.populate_add_synced(&mut [0.0; 3], Role::Following(&[]), None);
+        assert_relative_eq!(following.phase, 0.75);
+    }
+
+    #[test]
+    fn band_limit_reset_of_following_oscillator() {
+        let mut leading = oscillator_at(5.5);
+        let mut events = Events::new();
+        leading.populate_add_synced(&mut [0.0; 8], Role::Leading(&mut events), None);
+
+        let mut following = oscillator_at(2.75);
+        let mut buffer = [0.0; 8];
+        following.populate_add_synced(&mut buffer, Role::Following(events.as_slice()), None);
+
+        // Without the correction, the ramp would drop from -0.175 straight
+        // to -1.0 right after the reset.
+        assert_relative_eq!(buffer[3], -0.175, epsilon = 0.001);
+        assert_relative_eq!(buffer[4], 0.1, epsilon = 0.01);
+    }
+
+    #[test]
+    fn band_limit_restart_of_synced_warp() {
+        let mut oscillator = oscillator_at(5.5);
+        oscillator.set_warp(Warp::new(warp::Mode::Sync, 0.5 / 7.0));
+        let mut buffer = [0.0; 8];
+        oscillator.populate_add(&mut buffer);
+
+        // The cycle ends halfway through the third reading of the table.
+        // Without the correction, the output would drop straight to -0.5
+        // right after the restart.
+        assert_relative_eq!(buffer[0], -0.5, epsilon = 0.001);
+        assert_relative_eq!(buffer[4], 0.325, epsilon = 0.01);
+    }
+
+    #[test]
+    fn mute_following_oscillator_synced_above_nyquist() {
+        let mut leading = oscillator_at(2.0);
+        let mut events = Events::new();
+        leading.populate_add_synced(&mut [0.0; 22], Role::Leading(&mut events), None);
+
+        let mut following = oscillator_at(5.0);
+        following.set_sync_ratio(4.0);
+        let mut buffer = [0.0; 22];
+        following.populate_add_synced(&mut buffer, Role::Following(events.as_slice()), None);
+        assert!(buffer.iter().all(|x| x.abs() < 0.001));
+    }
+
+    // Magnitude of the given frequency in a second long signal.
+    fn magnitude(signal: &[f32], frequency: f32) -> f32 {
+        let (re, im) = signal
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(re, im), (i, x)| {
+                let angle = 2.0 * PI * frequency * i as f32 / signal.len() as f32;
+                (re + x * f32::cos(angle), im - x * f32::sin(angle))
+            });
+        2.0 * f32::sqrt(re * re + im * im) / signal.len() as f32
+    }
+
+    #[test]
+    fn form_sidebands_with_frequency_modulation() {
+        const CARRIER: f32 = 128.0;
+        const MODULATOR: f32 = 32.0;
+
+        let mut oscillator = Oscillator::new(&SINE_BANKS[..], SPECTRUM_SAMPLE_RATE);
+        oscillator.amplitude = Stable(1.0);
+        oscillator.frequency = CARRIER;
+
+        // Index of 1, the deviation peaks at the frequency of the modulator.
+        let deviation: [f32; 1024] = core::array::from_fn(|i| {
+            MODULATOR * f32::sin(2.0 * PI * MODULATOR * i as f32 / 1024.0)
+        });
+        let mut buffer = [0.0; 1024];
+        oscillator.populate_add_synced(&mut buffer, Role::Free, Some(&deviation));
+
+        // Bessel functions of the first kind for index of 1.
+        const SIDEBANDS: [f32; 4] = [0.7652, 0.4401, 0.1149, 0.0196];
+        for (k, expected) in SIDEBANDS.iter().enumerate() {
+            let offset = k as f32 * MODULATOR;
+            assert_relative_eq!(
+                magnitude(&buffer, CARRIER + offset),
+                expected,
+                epsilon = 0.02
+            );
+            assert_relative_eq!(
+                magnitude(&buffer, CARRIER - offset),
+                expected,
+                epsilon = 0.02
+            );
+        }
+        assert_relative_eq!(magnitude(&buffer, CARRIER + 16.0), 0.0, epsilon = 0.02);
+    }
+
+    #[test]
+    fn run_through_zero_with_negative_frequency() {
+        let mut oscillator = oscillator_at(1.0);
+        let deviation = [-2.0; 11];
+        let mut buffer = [0.0; 11];
+        oscillator.populate_add_synced(&mut buffer, Role::Free, Some(&deviation));
+
+        // The ramp is read backwards, from its end.
+        assert_relative_eq!(oscillator.phase, 0.5, epsilon = 0.001);
+        assert_relative_eq!(buffer[3], 0.9, epsilon = 0.001);
+        assert_relative_eq!(buffer[4], 0.8, epsilon = 0.001);
+    }
+
+    #[test]
+    fn glide_to_new_frequency() {
+        let mut oscillator = Oscillator::new(&WAVETABLE_BANKS[..], SAMPLE_RATE);
+        oscillator.amplitude = Stable(1.0);
+        oscillator.frequency = 1.0;
+        oscillator.set_glide(Glide::ConstantTime(1.0));
+
+        oscillator.set_frequency(4.0);
+        assert_relative_eq!(oscillator.frequency, 1.0);
+
+        let mut buffer = [0.0; 11];
+        oscillator.populate_add(&mut buffer);
+        assert_relative_eq!(oscillator.frequency, 2.0, max_relative = 0.01);
+
+        let mut buffer = [0.0; 11];
+        oscillator.populate_add(&mut buffer);
+        assert_relative_eq!(oscillator.frequency, 4.0);
+    }
+
+    #[test]
+    fn jump_to_new_frequency_when_silent() {
+        let mut oscillator = Oscillator::new(&WAVETABLE_BANKS[..], SAMPLE_RATE);
+        oscillator.frequency = 1.0;
+        oscillator.set_glide(Glide::ConstantTime(1.0));
+
+        oscillator.set_frequency(4.0);
+        assert_relative_eq!(oscillator.frequency, 4.0);
+    }
+
     #[test]
     fn fade_out_after_150_samples() {
-        let mut oscillator = Oscillator::new(&WAVETABLE_BANK[..], SAMPLE_RATE);
+        let mut oscillator = Oscillator::new(&WAVETABLE_BANKS[..], SAMPLE_RATE);
         oscillator.amplitude = Stable(1.0);
         oscillator.frequency = 1.0;
         let step = 1.0 / 10.0;
@@ -1,57 +1,54 @@
 use crate::note::Note;
+use crate::tuning::table::Tuning;
 
-pub fn quantize(voct: f32) -> Note {
-    let offset_voct = voct + (1.0 / 24.0);
-    let position = offset_voct / (1.0 / 12.0);
-    let id = position as u8;
-
-    if id > Note::HIGHEST_NOTE.to_midi_id() {
-        Note::HIGHEST_NOTE
-    } else {
-        Note::from(id)
-    }
+pub fn quantize(tuning: &Tuning, voct: f32) -> Note {
+    tuning.quantize(voct)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    lazy_static! {
+        static ref TUNING: Tuning = Tuning::equal_temperament();
+    }
+
     #[test]
     fn quantize_on_spot_voct_to_note() {
         let voct = 0.0;
-        assert_eq!(quantize(voct), Note::CMinus1);
+        assert_eq!(quantize(&TUNING, voct), Note::CMinus1);
 
         let voct = 3.0;
-        assert_eq!(quantize(voct), Note::C2);
+        assert_eq!(quantize(&TUNING, voct), Note::C2);
 
         let voct = 3.0 + 1.0 / 12.0;
-        assert_eq!(quantize(voct), Note::CSharp2);
+        assert_eq!(quantize(&TUNING, voct), Note::CSharp2);
 
         let voct = 4.0;
-        assert_eq!(quantize(voct), Note::C3);
+        assert_eq!(quantize(&TUNING, voct), Note::C3);
     }
 
     #[test]
     fn quantize_note_over_limit() {
         let voct = 100.0;
-        assert_eq!(quantize(voct), Note::HIGHEST_NOTE);
+        assert_eq!(quantize(&TUNING, voct), Note::HIGHEST_NOTE);
     }
 
     #[test]
     fn quantize_voct_above_to_note() {
         let voct = 3.0 + 0.4 / 12.0;
-        assert_eq!(quantize(voct), Note::C2);
+        assert_eq!(quantize(&TUNING, voct), Note::C2);
 
         let voct = 3.0 + 1.4 / 12.0;
-        assert_eq!(quantize(voct), Note::CSharp2);
+        assert_eq!(quantize(&TUNING, voct), Note::CSharp2);
     }
 
     #[test]
     fn quantize_voct_below_to_note() {
         let voct = 3.0 - 0.4 / 12.0;
-        assert_eq!(quantize(voct), Note::C2);
+        assert_eq!(quantize(&TUNING, voct), Note::C2);
 
         let voct = 3.0 - 1.4 / 12.0;
-        assert_eq!(quantize(voct), Note::B1);
+        assert_eq!(quantize(&TUNING, voct), Note::B1);
     }
 }
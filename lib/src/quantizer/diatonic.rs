@@ -3,22 +3,44 @@ use micromath::F32Ext;
 
 use crate::note::Note;
 use crate::scales;
-use crate::scales::diatonic::{Mode, SEMITONES};
+use crate::scales::scale::Scale;
+use crate::tuning::table::Tuning;
 
 pub type Degree = u8;
 
-#[cfg(not(feature = "white_key_quantization"))]
-pub fn quantize_voct(mode: Mode, root: Note, voct: f32) -> (Note, Degree) {
-    quantize_voct_center(mode, root, voct)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Algorithm {
+    // Snap to the closest note of the scale.
+    Center,
+    // Map white keys of the keyboard to all the notes of the scale.
+    WhiteKeys,
+    // Snap to the closest note of the scale, but stick to the previously
+    // selected note until the input crosses the center between the two by
+    // the given portion (0.0 to 1.0) of the remaining distance.
+    Hysteresis(f32),
 }
 
-#[cfg(feature = "white_key_quantization")]
-pub fn quantize_voct(mode: Mode, root: Note, voct: f32) -> (Note, Degree) {
-    quantize_voct_white_keys(mode, root, voct)
+// The previous note is only consulted by the hysteresis algorithm. White keys
+// are mapped by their position on the keyboard, not by their pitch, so the
+// tuning does not play a role there.
+pub fn quantize_voct(
+    tuning: &Tuning,
+    algorithm: Algorithm,
+    scale: Scale,
+    root: Note,
+    voct: f32,
+    previous: Option<Note>,
+) -> (Note, Degree) {
+    match algorithm {
+        Algorithm::Center => quantize_voct_center(tuning, scale, root, voct),
+        Algorithm::WhiteKeys => quantize_voct_white_keys(scale, root, voct),
+        Algorithm::Hysteresis(hysteresis) => {
+            quantize_voct_hysteresis(tuning, scale, root, voct, hysteresis, previous)
+        }
+    }
 }
 
-#[allow(dead_code)]
-fn quantize_voct_white_keys(mode: Mode, root: Note, mut voct: f32) -> (Note, Degree) {
+fn quantize_voct_white_keys(scale: Scale, root: Note, mut voct: f32) -> (Note, Degree) {
     if voct > to_voct(Note::G9) {
         // One below the highest to allow it to quantize up
         voct = to_voct(Note::Gb9);
@@ -27,186 +49,77 @@ fn quantize_voct_white_keys(mode: Mode, root: Note, mut voct: f32) -> (Note, Deg
     let root_white = voct_to_white_key(to_voct(root));
     let voct_white = voct_to_white_key(voct);
 
-    let white_diff = voct_white as i32 - root_white as i32;
-    let interval = if white_diff > 0 {
-        white_diff + 1
-    } else {
-        white_diff - 1
-    };
-
-    let note = scales::diatonic::lookup_degree(root, mode, root, interval).unwrap();
+    white_key_to_degree(scale, root, voct_white as i32 - root_white as i32)
+}
 
-    (note, white_diff.rem_euclid(7) as u8 + 1)
+fn quantize_voct_center(tuning: &Tuning, scale: Scale, root: Note, voct: f32) -> (Note, Degree) {
+    quantize_voct_hysteresis(tuning, scale, root, voct, 0.0, None)
 }
 
-fn quantize_voct_center(mode: Mode, root: Note, voct: f32) -> (Note, Degree) {
+fn quantize_voct_hysteresis(
+    tuning: &Tuning,
+    scale: Scale,
+    root: Note,
+    voct: f32,
+    hysteresis: f32,
+    previous: Option<Note>,
+) -> (Note, Degree) {
     // XXX: This is making the method simpler by sacrificing a part of the
     // lowest octave.
-    let lowest_tonic = lowest_note(root);
-    if voct < lowest_tonic.to_voct() {
+    let lowest_tonic = Note::from_u8(root.to_midi_id() % tuning.octave_keys());
+    if voct < tuning.voct(lowest_tonic) {
         return (lowest_tonic, 0);
     }
 
-    let closest_tonic = find_closest_tonic(lowest_tonic, voct);
+    let closest_tonic = find_closest_tonic(tuning, lowest_tonic, voct);
     if closest_tonic.is_none() {
         return (lowest_tonic, 0);
     }
     let closest_tonic = closest_tonic.unwrap();
 
-    let surrounding_notes = find_surrounding_notes_ascending(mode, voct, closest_tonic);
+    let surrounding_notes = find_surrounding_notes_ascending(tuning, scale, voct, closest_tonic);
     if surrounding_notes.is_none() {
         return (lowest_tonic, 0);
     }
     let ((below_note, below_degree), (above_note, above_degree)) = surrounding_notes.unwrap();
 
-    let center_voct = (below_note.to_voct() + above_note.to_voct()) / 2.0;
-    if voct < center_voct {
+    let below_voct = tuning.voct(below_note);
+    let above_voct = tuning.voct(above_note);
+    let center_voct = (below_voct + above_voct) / 2.0;
+    let margin = (above_voct - below_voct) / 2.0 * hysteresis.clamp(0.0, 1.0);
+    let threshold = if previous == Some(below_note) {
+        center_voct + margin
+    } else if previous == Some(above_note) {
+        center_voct - margin
+    } else {
+        center_voct
+    };
+
+    if voct < threshold {
         (below_note, below_degree)
     } else {
         (above_note, above_degree)
     }
 }
 
-fn lowest_note(note: Note) -> Note {
-    match note {
-        Note::A0
-        | Note::A1
-        | Note::A2
-        | Note::A3
-        | Note::A4
-        | Note::A5
-        | Note::A6
-        | Note::A7
-        | Note::A8
-        | Note::AMinus1 => Note::AMinus1,
-        Note::Ab0
-        | Note::Ab1
-        | Note::Ab2
-        | Note::Ab3
-        | Note::Ab4
-        | Note::Ab5
-        | Note::Ab6
-        | Note::Ab7
-        | Note::Ab8
-        | Note::AbMinus1 => Note::AbMinus1,
-        Note::B0
-        | Note::B1
-        | Note::B2
-        | Note::B3
-        | Note::B4
-        | Note::B5
-        | Note::B6
-        | Note::B7
-        | Note::B8
-        | Note::BMinus1 => Note::BMinus1,
-        Note::Bb0
-        | Note::Bb1
-        | Note::Bb2
-        | Note::Bb3
-        | Note::Bb4
-        | Note::Bb5
-        | Note::Bb6
-        | Note::Bb7
-        | Note::Bb8
-        | Note::BbMinus1 => Note::BbMinus1,
-        Note::C0
-        | Note::C1
-        | Note::C2
-        | Note::C3
-        | Note::C4
-        | Note::C5
-        | Note::C6
-        | Note::C7
-        | Note::C8
-        | Note::C9
-        | Note::CMinus1 => Note::CMinus1,
-        Note::D0
-        | Note::D1
-        | Note::D2
-        | Note::D3
-        | Note::D4
-        | Note::D5
-        | Note::D6
-        | Note::D7
-        | Note::D8
-        | Note::D9
-        | Note::DMinus1 => Note::DMinus1,
-        Note::Db0
-        | Note::Db1
-        | Note::Db2
-        | Note::Db3
-        | Note::Db4
-        | Note::Db5
-        | Note::Db6
-        | Note::Db7
-        | Note::Db8
-        | Note::Db9
-        | Note::DbMinus1 => Note::DbMinus1,
-        Note::E0
-        | Note::E1
-        | Note::E2
-        | Note::E3
-        | Note::E4
-        | Note::E5
-        | Note::E6
-        | Note::E7
-        | Note::E8
-        | Note::E9
-        | Note::EMinus1 => Note::EMinus1,
-        Note::Eb0
-        | Note::Eb1
-        | Note::Eb2
-        | Note::Eb3
-        | Note::Eb4
-        | Note::Eb5
-        | Note::Eb6
-        | Note::Eb7
-        | Note::Eb8
-        | Note::Eb9
-        | Note::EbMinus1 => Note::EbMinus1,
-        Note::F0
-        | Note::F1
-        | Note::F2
-        | Note::F3
-        | Note::F4
-        | Note::F5
-        | Note::F6
-        | Note::F7
-        | Note::F8
-        | Note::F9
-        | Note::FMinus1 => Note::FMinus1,
-        Note::G0
-        | Note::G1
-        | Note::G2
-        | Note::G3
-        | Note::G4
-        | Note::G5
-        | Note::G6
-        | Note::G7
-        | Note::G8
-        | Note::G9
-        | Note::GMinus1 => Note::GMinus1,
-        Note::Gb0
-        | Note::Gb1
-        | Note::Gb2
-        | Note::Gb3
-        | Note::Gb4
-        | Note::Gb5
-        | Note::Gb6
-        | Note::Gb7
-        | Note::Gb8
-        | Note::Gb9
-        | Note::GbMinus1 => Note::GbMinus1,
+// Octaves of microtonal tunings may be stretched, so the closest tonic is
+// searched by its tuned pitch instead of being calculated. Tonics repeat with
+// the formal octave of the keyboard mapping.
+fn find_closest_tonic(tuning: &Tuning, lowest_tonic: Note, voct: f32) -> Option<Note> {
+    let octave_keys = tuning.octave_keys();
+    let mut closest_tonic = None;
+    for octave in 0..=Note::HIGHEST_NOTE as u8 / octave_keys {
+        match Note::try_from_u8(lowest_tonic.to_midi_id() + octave_keys * octave) {
+            Some(tonic) if tuning.voct(tonic) <= voct => closest_tonic = Some(tonic),
+            _ => break,
+        }
     }
-}
-
-fn find_closest_tonic(lowest_tonic: Note, voct: f32) -> Option<Note> {
-    let distance_in_full_octaves = (voct - lowest_tonic.to_voct()) as u8;
-    Note::try_from_u8(lowest_tonic.to_midi_id() + 12 * distance_in_full_octaves.min(11))
+    closest_tonic
 }
 
 fn find_surrounding_notes_ascending(
-    mode: Mode,
+    tuning: &Tuning,
+    scale: Scale,
     voct: f32,
     closest_tonic: Note,
 ) -> Option<((Note, Degree), (Note, Degree))> {
@@ -215,19 +128,17 @@ fn find_surrounding_notes_ascending(
     let mut above_note = None;
     let mut above_degree = None;
 
-    let mut distance = 0;
-    let semitones = SEMITONES[mode as usize];
-    // TODO: Move this to a function
-    let mut steps = [0; 7];
-    for i in 0..steps.len() {
-        steps[i] = semitones.get(i + 1).unwrap_or(&12) - semitones[i];
-    }
-    for (i, step) in steps.iter().enumerate() {
-        distance += step;
-        let note = Note::try_from_u8(closest_tonic.to_midi_id() + distance as u8)?;
-        let index = i as u8;
-        let degree = if index == 6 { 1 } else { index + 2 };
-        if note.to_voct() > voct {
+    let semitones = scale.semitones();
+    let last_index = semitones.len() - 1;
+    for i in 0..semitones.len() {
+        let distance = semitones
+            .get(i + 1)
+            .map_or(tuning.octave_keys(), |semitone| {
+                tuning.keys(*semitone as u8)
+            });
+        let note = Note::try_from_u8(closest_tonic.to_midi_id() + distance)?;
+        let degree = if i == last_index { 1 } else { i as u8 + 2 };
+        if tuning.voct(note) > voct {
             above_note = Some(note);
             above_degree = Some(degree);
             break;
@@ -239,7 +150,7 @@ fn find_surrounding_notes_ascending(
     Some(((below_note, below_degree), (above_note?, above_degree?)))
 }
 
-pub fn quantize_linear(mode: Mode, root: Note, mut value: f32) -> (Note, Degree) {
+pub fn quantize_linear(scale: Scale, root: Note, mut value: f32) -> (Note, Degree) {
     if value > 10.0 {
         value = 10.0
     }
@@ -247,16 +158,26 @@ pub fn quantize_linear(mode: Mode, root: Note, mut value: f32) -> (Note, Degree)
     let root_white = voct_to_white_key(to_voct(root));
     let value_white = linear_to_white_key(value);
 
-    let white_diff = value_white as i32 - root_white as i32;
-    let interval = if white_diff > 0 {
-        white_diff + 1
-    } else {
-        white_diff - 1
-    };
+    white_key_to_degree(scale, root, value_white as i32 - root_white as i32)
+}
 
-    let note = scales::diatonic::lookup_degree(root, mode, root, interval).unwrap();
+// Scales with less than seven notes span more octaves over the same white
+// keys, so they run out of notes at the edges of the range. Keys past the
+// range stick to the last degree that is still available.
+fn white_key_to_degree(scale: Scale, root: Note, mut white_diff: i32) -> (Note, Degree) {
+    loop {
+        let interval = if white_diff > 0 {
+            white_diff + 1
+        } else {
+            white_diff - 1
+        };
+
+        if let Some(note) = scales::scale::lookup_degree(root, scale, root, interval) {
+            return (note, white_diff.rem_euclid(scale.size() as i32) as u8 + 1);
+        }
 
-    (note, white_diff.rem_euclid(7) as u8 + 1)
+        white_diff -= white_diff.signum();
+    }
 }
 
 fn to_voct(note: Note) -> f32 {
@@ -314,7 +235,13 @@ fn linear_to_white_key(value: f32) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+
     use crate::scales::diatonic::Mode::*;
+    use crate::scales::library::{BLUES, MAJOR_PENTATONIC, WHOLE_TONE};
+
+    lazy_static! {
+        static ref TUNING: Tuning = Tuning::equal_temperament();
+    }
 
     #[test]
     fn convert_midi_note_to_voct() {
@@ -363,90 +290,282 @@ mod tests {
 
     fn quantize_voct_white_keys_in_c_major_with_root(root: Note) {
         let voct = 2.0;
-        assert_eq!(quantize_voct_white_keys(Ionian, root, voct), (Note::C1, 1));
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
+            (Note::C1, 1)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.2 / 12.0,
+                None
+            ),
             (Note::C1, 1)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.2 / 12.0,
+                None
+            ),
             (Note::C1, 1)
         );
 
         let voct = 2.0 + 2.0 / 12.0;
-        assert_eq!(quantize_voct_white_keys(Ionian, root, voct), (Note::D1, 2));
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
+            (Note::D1, 2)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.2 / 12.0,
+                None
+            ),
             (Note::D1, 2)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.2 / 12.0,
+                None
+            ),
             (Note::D1, 2)
         );
 
         let voct = 2.0 + 4.0 / 12.0;
-        assert_eq!(quantize_voct_white_keys(Ionian, root, voct), (Note::E1, 3));
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
             (Note::E1, 3)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.2 / 12.0,
+                None
+            ),
+            (Note::E1, 3)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.2 / 12.0,
+                None
+            ),
             (Note::E1, 3)
         );
 
         let voct = 2.0 + 5.0 / 12.0;
-        assert_eq!(quantize_voct_white_keys(Ionian, root, voct), (Note::F1, 4));
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
             (Note::F1, 4)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.2 / 12.0,
+                None
+            ),
+            (Note::F1, 4)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.2 / 12.0,
+                None
+            ),
             (Note::F1, 4)
         );
 
         let voct = 2.0 + 7.0 / 12.0;
-        assert_eq!(quantize_voct_white_keys(Ionian, root, voct), (Note::G1, 5));
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
+            (Note::G1, 5)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.2 / 12.0,
+                None
+            ),
             (Note::G1, 5)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.2 / 12.0,
+                None
+            ),
             (Note::G1, 5)
         );
 
         let voct = 2.0 + 9.0 / 12.0;
-        assert_eq!(quantize_voct_white_keys(Ionian, root, voct), (Note::A1, 6));
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
+            (Note::A1, 6)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.2 / 12.0,
+                None
+            ),
             (Note::A1, 6)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.2 / 12.0,
+                None
+            ),
             (Note::A1, 6)
         );
 
         let voct = 2.0 + 11.0 / 12.0;
-        assert_eq!(quantize_voct_white_keys(Ionian, root, voct), (Note::B1, 7));
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
             (Note::B1, 7)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.2 / 12.0,
+                None
+            ),
+            (Note::B1, 7)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.2 / 12.0,
+                None
+            ),
             (Note::B1, 7)
         );
 
         let voct = 3.0;
-        assert_eq!(quantize_voct_white_keys(Ionian, root, voct), (Note::C2, 1));
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
+            (Note::C2, 1)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.2 / 12.0,
+                None
+            ),
             (Note::C2, 1)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.2 / 12.0,
+                None
+            ),
             (Note::C2, 1)
         );
     }
@@ -463,92 +582,384 @@ mod tests {
 
     fn quantize_voct_center_in_c_major_with_root(root: Note) {
         let voct = 2.0;
-        assert_eq!(quantize_voct_center(Ionian, root, voct), (Note::C1, 1));
         assert_eq!(
-            quantize_voct_center(Ionian, root, voct - 0.4 / 12.0),
+            quantize_voct(&TUNING, Algorithm::Center, Ionian.scale(), root, voct, None),
             (Note::C1, 1)
         );
         assert_eq!(
-            quantize_voct_center(Ionian, root, voct + 0.9 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                root,
+                voct - 0.4 / 12.0,
+                None
+            ),
+            (Note::C1, 1)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                root,
+                voct + 0.9 / 12.0,
+                None
+            ),
             (Note::C1, 1)
         );
 
         let voct = 2.0 + 2.0 / 12.0;
-        assert_eq!(quantize_voct_center(Ionian, root, voct), (Note::D1, 2));
         assert_eq!(
-            quantize_voct_center(Ionian, root, voct - 0.9 / 12.0),
+            quantize_voct(&TUNING, Algorithm::Center, Ionian.scale(), root, voct, None),
+            (Note::D1, 2)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                root,
+                voct - 0.9 / 12.0,
+                None
+            ),
             (Note::D1, 2)
         );
         assert_eq!(
-            quantize_voct_center(Ionian, root, voct + 0.9 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                root,
+                voct + 0.9 / 12.0,
+                None
+            ),
             (Note::D1, 2)
         );
 
         let voct = 2.0 + 4.0 / 12.0;
-        assert_eq!(quantize_voct_center(Ionian, root, voct), (Note::E1, 3));
         assert_eq!(
-            quantize_voct_center(Ionian, root, voct - 0.9 / 12.0),
+            quantize_voct(&TUNING, Algorithm::Center, Ionian.scale(), root, voct, None),
             (Note::E1, 3)
         );
         assert_eq!(
-            quantize_voct_center(Ionian, root, voct + 0.4 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                root,
+                voct - 0.9 / 12.0,
+                None
+            ),
+            (Note::E1, 3)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                root,
+                voct + 0.4 / 12.0,
+                None
+            ),
             (Note::E1, 3)
         );
 
         let voct = 2.0 + 5.0 / 12.0;
-        assert_eq!(quantize_voct_center(Ionian, root, voct), (Note::F1, 4));
         assert_eq!(
-            quantize_voct_center(Ionian, root, voct - 0.4 / 12.0),
+            quantize_voct(&TUNING, Algorithm::Center, Ionian.scale(), root, voct, None),
             (Note::F1, 4)
         );
         assert_eq!(
-            quantize_voct_center(Ionian, root, voct + 0.9 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                root,
+                voct - 0.4 / 12.0,
+                None
+            ),
+            (Note::F1, 4)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                root,
+                voct + 0.9 / 12.0,
+                None
+            ),
             (Note::F1, 4)
         );
 
         let voct = 2.0 + 7.0 / 12.0;
-        assert_eq!(quantize_voct_center(Ionian, root, voct), (Note::G1, 5));
         assert_eq!(
-            quantize_voct_center(Ionian, root, voct - 0.9 / 12.0),
+            quantize_voct(&TUNING, Algorithm::Center, Ionian.scale(), root, voct, None),
             (Note::G1, 5)
         );
         assert_eq!(
-            quantize_voct_center(Ionian, root, voct + 0.9 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                root,
+                voct - 0.9 / 12.0,
+                None
+            ),
+            (Note::G1, 5)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                root,
+                voct + 0.9 / 12.0,
+                None
+            ),
             (Note::G1, 5)
         );
 
         let voct = 2.0 + 9.0 / 12.0;
-        assert_eq!(quantize_voct_center(Ionian, root, voct), (Note::A1, 6));
         assert_eq!(
-            quantize_voct_center(Ionian, root, voct - 0.9 / 12.0),
+            quantize_voct(&TUNING, Algorithm::Center, Ionian.scale(), root, voct, None),
             (Note::A1, 6)
         );
         assert_eq!(
-            quantize_voct_center(Ionian, root, voct + 0.9 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                root,
+                voct - 0.9 / 12.0,
+                None
+            ),
+            (Note::A1, 6)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                root,
+                voct + 0.9 / 12.0,
+                None
+            ),
             (Note::A1, 6)
         );
 
         let voct = 2.0 + 11.0 / 12.0;
-        assert_eq!(quantize_voct_center(Ionian, root, voct), (Note::B1, 7));
         assert_eq!(
-            quantize_voct_center(Ionian, root, voct - 0.9 / 12.0),
+            quantize_voct(&TUNING, Algorithm::Center, Ionian.scale(), root, voct, None),
+            (Note::B1, 7)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                root,
+                voct - 0.9 / 12.0,
+                None
+            ),
             (Note::B1, 7)
         );
         assert_eq!(
-            quantize_voct_center(Ionian, root, voct + 0.4 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                root,
+                voct + 0.4 / 12.0,
+                None
+            ),
             (Note::B1, 7)
         );
 
         let voct = 3.0;
-        assert_eq!(quantize_voct_center(Ionian, root, voct), (Note::C2, 1));
         assert_eq!(
-            quantize_voct_center(Ionian, root, voct - 0.4 / 12.0),
+            quantize_voct(&TUNING, Algorithm::Center, Ionian.scale(), root, voct, None),
             (Note::C2, 1)
         );
         assert_eq!(
-            quantize_voct_center(Ionian, root, voct + 0.9 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                root,
+                voct - 0.4 / 12.0,
+                None
+            ),
             (Note::C2, 1)
         );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                root,
+                voct + 0.9 / 12.0,
+                None
+            ),
+            (Note::C2, 1)
+        );
+    }
+
+    #[test]
+    fn quantize_voct_center_to_tuned_pitches() {
+        // Equal temperament with a narrow major third
+        let scl = "Flat third\n12\n100.0\n200.0\n300.0\n350.0\n500.0\n600.0\n700.0\n800.0\n900.0\n1000.0\n1100.0\n2/1\n";
+        let tuning = Tuning::parse(scl, None).unwrap();
+        let voct = Note::C4.to_voct() + 4.3 / 12.0;
+
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                Ionian.scale(),
+                Note::C0,
+                voct,
+                None
+            ),
+            (Note::E4, 3)
+        );
+        assert_eq!(
+            quantize_voct(
+                &tuning,
+                Algorithm::Center,
+                Ionian.scale(),
+                Note::C0,
+                voct,
+                None
+            ),
+            (Note::F4, 4)
+        );
+    }
+
+    #[test]
+    fn quantize_voct_center_with_octave_of_nineteen_keys() {
+        let scl = "19-TET\n19\n63.15789\n126.31579\n189.47368\n252.63158\n315.78947\n378.94737\n442.10526\n505.26316\n568.42105\n631.57895\n694.73684\n757.89474\n821.05263\n884.21053\n947.36842\n1010.52632\n1073.68421\n1136.84211\n2/1\n";
+        let tuning = Tuning::parse(scl, None).unwrap();
+        let quantize = |key: u8, offset: f32| {
+            let voct = tuning.voct(Note::from_u8(key)) + offset / 19.0;
+            quantize_voct(
+                &tuning,
+                Algorithm::Center,
+                Ionian.scale(),
+                Note::C4,
+                voct,
+                None,
+            )
+        };
+
+        assert_eq!(quantize(60, 0.0), (Note::C4, 1));
+        assert_eq!(quantize(62, 0.0), (Note::from_u8(63), 2));
+        assert_eq!(quantize(66, 0.4), (Note::from_u8(66), 3));
+        assert_eq!(quantize(77, 0.6), (Note::from_u8(77), 7));
+        assert_eq!(quantize(78, 0.2), (Note::from_u8(79), 1));
+        assert_eq!(quantize(79, 0.4), (Note::from_u8(79), 1));
+        assert_eq!(quantize(41, 0.0), (Note::from_u8(41), 1));
+    }
+
+    #[test]
+    fn quantize_voct_center_in_pentatonic() {
+        let voct = Note::C4.to_voct();
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                MAJOR_PENTATONIC,
+                Note::C0,
+                voct + 5.4 / 12.0,
+                None
+            ),
+            (Note::E4, 3)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                MAJOR_PENTATONIC,
+                Note::C0,
+                voct + 5.6 / 12.0,
+                None
+            ),
+            (Note::G4, 4)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::Center,
+                MAJOR_PENTATONIC,
+                Note::C0,
+                voct + 10.6 / 12.0,
+                None
+            ),
+            (Note::C5, 1)
+        );
+    }
+
+    #[test]
+    fn quantize_voct_hysteresis_sticks_to_previous_note() {
+        let hysteresis = Algorithm::Hysteresis(0.5);
+        let c4 = Note::C4.to_voct();
+        let quantize = |voct, previous| {
+            quantize_voct(
+                &TUNING,
+                hysteresis,
+                Ionian.scale(),
+                Note::C0,
+                voct,
+                Some(previous),
+            )
+        };
+
+        assert_eq!(quantize(c4 + 1.2 / 12.0, Note::C4), (Note::C4, 1));
+        assert_eq!(quantize(c4 + 1.6 / 12.0, Note::C4), (Note::D4, 2));
+        assert_eq!(quantize(c4 + 0.8 / 12.0, Note::D4), (Note::D4, 2));
+        assert_eq!(quantize(c4 + 0.4 / 12.0, Note::D4), (Note::C4, 1));
+    }
+
+    #[test]
+    fn quantize_voct_hysteresis_ignores_distant_previous_note() {
+        let voct = Note::C4.to_voct() + 1.2 / 12.0;
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::Hysteresis(1.0),
+                Ionian.scale(),
+                Note::C0,
+                voct,
+                Some(Note::G4)
+            ),
+            (Note::D4, 2)
+        );
+    }
+
+    #[test]
+    fn quantize_voct_without_hysteresis_matches_center() {
+        for i in 0..48 {
+            let voct = 4.0 + i as f32 / 48.0;
+            assert_eq!(
+                quantize_voct(
+                    &TUNING,
+                    Algorithm::Hysteresis(0.0),
+                    Ionian.scale(),
+                    Note::C0,
+                    voct,
+                    Some(Note::C4)
+                ),
+                quantize_voct(
+                    &TUNING,
+                    Algorithm::Center,
+                    Ionian.scale(),
+                    Note::C0,
+                    voct,
+                    None
+                )
+            );
+        }
     }
 
     #[test]
@@ -564,107 +975,281 @@ mod tests {
     fn quantize_voct_white_keys_in_f_sharp_major_with_root(root: Note) {
         let voct = 2.0;
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
             (Note::CSharp1, 5)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.2 / 12.0,
+                None
+            ),
             (Note::CSharp1, 5)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.2 / 12.0,
+                None
+            ),
             (Note::CSharp1, 5)
         );
 
         let voct = 2.0 + 2.0 / 12.0;
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
             (Note::DSharp1, 6)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.2 / 12.0,
+                None
+            ),
             (Note::DSharp1, 6)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.2 / 12.0,
+                None
+            ),
             (Note::DSharp1, 6)
         );
 
         let voct = 2.0 + 4.0 / 12.0;
-        assert_eq!(quantize_voct_white_keys(Ionian, root, voct), (Note::F1, 7));
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
+            (Note::F1, 7)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.2 / 12.0,
+                None
+            ),
             (Note::F1, 7)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.2 / 12.0,
+                None
+            ),
             (Note::F1, 7)
         );
 
         let voct = 2.0 + 5.0 / 12.0;
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
             (Note::FSharp1, 1)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.2 / 12.0,
+                None
+            ),
             (Note::FSharp1, 1)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.2 / 12.0,
+                None
+            ),
             (Note::FSharp1, 1)
         );
 
         let voct = 2.0 + 7.0 / 12.0;
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
             (Note::GSharp1, 2)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.2 / 12.0,
+                None
+            ),
             (Note::GSharp1, 2)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.2 / 12.0,
+                None
+            ),
             (Note::GSharp1, 2)
         );
 
         let voct = 2.0 + 9.0 / 12.0;
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
             (Note::ASharp1, 3)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.2 / 12.0,
+                None
+            ),
             (Note::ASharp1, 3)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.2 / 12.0,
+                None
+            ),
             (Note::ASharp1, 3)
         );
 
         let voct = 2.0 + 11.0 / 12.0;
-        assert_eq!(quantize_voct_white_keys(Ionian, root, voct), (Note::B1, 4));
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
+            (Note::B1, 4)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.2 / 12.0,
+                None
+            ),
             (Note::B1, 4)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.2 / 12.0,
+                None
+            ),
             (Note::B1, 4)
         );
 
         let voct = 3.0;
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
             (Note::CSharp2, 5)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.2 / 12.0,
+                None
+            ),
             (Note::CSharp2, 5)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.2 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.2 / 12.0,
+                None
+            ),
             (Note::CSharp2, 5)
         );
     }
@@ -681,57 +1266,177 @@ mod tests {
 
     fn quantize_voct_black_keys_in_c_major_with_root(root: Note) {
         let voct = 2.0 + 1.0 / 12.0;
-        assert_eq!(quantize_voct_white_keys(Ionian, root, voct), (Note::C1, 1));
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
             (Note::C1, 1)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.05 / 12.0,
+                None
+            ),
+            (Note::C1, 1)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.05 / 12.0,
+                None
+            ),
             (Note::C1, 1)
         );
 
         let voct = 2.0 + 3.0 / 12.0;
-        assert_eq!(quantize_voct_white_keys(Ionian, root, voct), (Note::D1, 2));
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
+            (Note::D1, 2)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.05 / 12.0,
+                None
+            ),
             (Note::D1, 2)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.05 / 12.0,
+                None
+            ),
             (Note::D1, 2)
         );
 
         let voct = 2.0 + 6.0 / 12.0;
-        assert_eq!(quantize_voct_white_keys(Ionian, root, voct), (Note::F1, 4));
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
+            (Note::F1, 4)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.05 / 12.0,
+                None
+            ),
             (Note::F1, 4)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.05 / 12.0,
+                None
+            ),
             (Note::F1, 4)
         );
 
         let voct = 2.0 + 8.0 / 12.0;
-        assert_eq!(quantize_voct_white_keys(Ionian, root, voct), (Note::G1, 5));
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
+            (Note::G1, 5)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.05 / 12.0,
+                None
+            ),
             (Note::G1, 5)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.05 / 12.0,
+                None
+            ),
             (Note::G1, 5)
         );
 
         let voct = 2.0 + 10.0 / 12.0;
-        assert_eq!(quantize_voct_white_keys(Ionian, root, voct), (Note::A1, 6));
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
             (Note::A1, 6)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.05 / 12.0,
+                None
+            ),
+            (Note::A1, 6)
+        );
+        assert_eq!(
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.05 / 12.0,
+                None
+            ),
             (Note::A1, 6)
         );
     }
@@ -749,71 +1454,176 @@ mod tests {
     fn quantize_voct_black_keys_in_f_sharp_major_with_root(root: Note) {
         let voct = 2.0 + 1.0 / 12.0;
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
             (Note::CSharp1, 5)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.05 / 12.0,
+                None
+            ),
             (Note::CSharp1, 5)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.05 / 12.0,
+                None
+            ),
             (Note::CSharp1, 5)
         );
 
         let voct = 2.0 + 3.0 / 12.0;
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
             (Note::DSharp1, 6)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.05 / 12.0,
+                None
+            ),
             (Note::DSharp1, 6)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.05 / 12.0,
+                None
+            ),
             (Note::DSharp1, 6)
         );
 
         let voct = 2.0 + 6.0 / 12.0;
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
             (Note::FSharp1, 1)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.05 / 12.0,
+                None
+            ),
             (Note::FSharp1, 1)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.05 / 12.0,
+                None
+            ),
             (Note::FSharp1, 1)
         );
 
         let voct = 2.0 + 8.0 / 12.0;
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
             (Note::GSharp1, 2)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.05 / 12.0,
+                None
+            ),
             (Note::GSharp1, 2)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.05 / 12.0,
+                None
+            ),
             (Note::GSharp1, 2)
         );
 
         let voct = 2.0 + 10.0 / 12.0;
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct,
+                None
+            ),
             (Note::ASharp1, 3)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct - 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct - 0.05 / 12.0,
+                None
+            ),
             (Note::ASharp1, 3)
         );
         assert_eq!(
-            quantize_voct_white_keys(Ionian, root, voct + 0.05 / 12.0),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                root,
+                voct + 0.05 / 12.0,
+                None
+            ),
             (Note::ASharp1, 3)
         );
     }
@@ -822,7 +1632,14 @@ mod tests {
     fn quantize_voct_with_note_below_the_lowest_root() {
         let voct = 1.0 / 12.0;
         assert_eq!(
-            quantize_voct_white_keys(Ionian, Note::A0, voct),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                Note::A0,
+                voct,
+                None
+            ),
             (Note::CSharpMinus1, 3)
         );
     }
@@ -831,7 +1648,14 @@ mod tests {
     fn quantize_voct_note_over_limit_and_stay_in_scale() {
         let voct = 100.0;
         assert_eq!(
-            quantize_voct_white_keys(Ionian, Note::B0, voct),
+            quantize_voct(
+                &TUNING,
+                Algorithm::WhiteKeys,
+                Ionian.scale(),
+                Note::B0,
+                voct,
+                None
+            ),
             (Note::FSharp9, 5)
         );
     }
@@ -840,7 +1664,7 @@ mod tests {
     fn quantize_linear_with_note_below_the_lowest_root() {
         let value = 0.5 / 7.0;
         assert_eq!(
-            quantize_linear(Ionian, Note::A0, value),
+            quantize_linear(Ionian.scale(), Note::A0, value),
             (Note::CSharpMinus1, 3)
         );
     }
@@ -848,7 +1672,10 @@ mod tests {
     #[test]
     fn quantize_linear_note_over_limit_and_stay_in_scale() {
         let value = 100.0;
-        assert_eq!(quantize_linear(Ionian, Note::B0, value), (Note::CSharp9, 2));
+        assert_eq!(
+            quantize_linear(Ionian.scale(), Note::B0, value),
+            (Note::CSharp9, 2)
+        );
     }
 
     #[test]
@@ -863,27 +1690,61 @@ mod tests {
 
     fn quantize_linear_c_major_with_root(root: Note) {
         let value = 2.0 + 0.5 / 7.0;
-        assert_eq!(quantize_linear(Ionian, root, value), (Note::C1, 1));
+        assert_eq!(quantize_linear(Ionian.scale(), root, value), (Note::C1, 1));
 
         let value = 2.0 + 1.5 / 7.0;
-        assert_eq!(quantize_linear(Ionian, root, value), (Note::D1, 2));
+        assert_eq!(quantize_linear(Ionian.scale(), root, value), (Note::D1, 2));
 
         let value = 2.0 + 2.5 / 7.0;
-        assert_eq!(quantize_linear(Ionian, root, value), (Note::E1, 3));
+        assert_eq!(quantize_linear(Ionian.scale(), root, value), (Note::E1, 3));
 
         let value = 2.0 + 3.5 / 7.0;
-        assert_eq!(quantize_linear(Ionian, root, value), (Note::F1, 4));
+        assert_eq!(quantize_linear(Ionian.scale(), root, value), (Note::F1, 4));
 
         let value = 2.0 + 4.5 / 7.0;
-        assert_eq!(quantize_linear(Ionian, root, value), (Note::G1, 5));
+        assert_eq!(quantize_linear(Ionian.scale(), root, value), (Note::G1, 5));
 
         let value = 2.0 + 5.5 / 7.0;
-        assert_eq!(quantize_linear(Ionian, root, value), (Note::A1, 6));
+        assert_eq!(quantize_linear(Ionian.scale(), root, value), (Note::A1, 6));
 
         let value = 2.0 + 6.5 / 7.0;
-        assert_eq!(quantize_linear(Ionian, root, value), (Note::B1, 7));
+        assert_eq!(quantize_linear(Ionian.scale(), root, value), (Note::B1, 7));
 
         let value = 3.0 + 0.5 / 7.0;
-        assert_eq!(quantize_linear(Ionian, root, value), (Note::C2, 1));
+        assert_eq!(quantize_linear(Ionian.scale(), root, value), (Note::C2, 1));
+    }
+
+    #[test]
+    fn quantize_linear_in_pentatonic() {
+        let value = 2.0 + 5.5 / 7.0;
+        assert_eq!(
+            quantize_linear(MAJOR_PENTATONIC, Note::C0, value),
+            (Note::E2, 3)
+        );
+    }
+
+    #[test]
+    fn quantize_white_keys_of_small_scales_over_the_full_range() {
+        let user_pentatonic = Scale::from_mask(0b0010_1001_0101).unwrap();
+        for scale in [user_pentatonic, MAJOR_PENTATONIC, WHOLE_TONE, BLUES] {
+            for root in [Note::CMinus1, Note::C4, Note::B8] {
+                let mut previous_linear = (Note::CMinus1, 1);
+                let mut previous_voct = (Note::CMinus1, 1);
+                for i in 0..=1100 {
+                    let value = i as f32 / 100.0;
+
+                    let linear = quantize_linear(scale, root, value);
+                    assert!(linear.0 >= previous_linear.0);
+                    assert!(linear.1 >= 1 && linear.1 as usize <= scale.size());
+                    previous_linear = linear;
+
+                    let voct =
+                        quantize_voct(&TUNING, Algorithm::WhiteKeys, scale, root, value, None);
+                    assert!(voct.0 >= previous_voct.0);
+                    assert!(voct.1 >= 1 && voct.1 as usize <= scale.size());
+                    previous_voct = voct;
+                }
+            }
+        }
     }
 }
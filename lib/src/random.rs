@@ -0,0 +1,67 @@
+// Xorshift generator, cheap enough to be used within the DSP loop and
+// deterministic for a given seed, so it can be reproduced in tests.
+pub struct Random {
+    state: u32,
+}
+
+// Xorshift gets stuck on zero, this is used instead
+const DEFAULT_SEED: u32 = 0x9E37_79B9;
+
+impl Random {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            state: if seed == 0 { DEFAULT_SEED } else { seed },
+        }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+
+    // Uniformly distributed in range of 0.0 to 1.0, excluding 1.0.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1 << 24) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_produces_same_sequence() {
+        let mut random_a = Random::new(42);
+        let mut random_b = Random::new(42);
+        for _ in 0..100 {
+            assert_eq!(random_a.next_u32(), random_b.next_u32());
+        }
+    }
+
+    #[test]
+    fn different_seeds_produce_different_sequences() {
+        let mut random_a = Random::new(1);
+        let mut random_b = Random::new(2);
+        assert!(random_a.next_u32() != random_b.next_u32());
+    }
+
+    #[test]
+    fn float_stays_within_range() {
+        let mut random = Random::new(1);
+        for _ in 0..1000 {
+            let value = random.next_f32();
+            assert!((0.0..1.0).contains(&value));
+        }
+    }
+
+    #[test]
+    fn zero_seed_does_not_get_stuck() {
+        let mut random = Random::new(0);
+        assert!(random.next_u32() != 0);
+        assert!(random.next_u32() != 0);
+    }
+}
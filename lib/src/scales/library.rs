@@ -0,0 +1,22 @@
+// Scales beyond the diatonic modes. These are not selectable on the module
+// yet, but all the scale arithmetic, quantizers and chord builders accept them.
+#![allow(dead_code)]
+
+use crate::scales::scale::Scale;
+
+pub const MAJOR_PENTATONIC: Scale = Scale::new(&[0, 2, 4, 7, 9]);
+pub const MINOR_PENTATONIC: Scale = Scale::new(&[0, 3, 5, 7, 10]);
+pub const WHOLE_TONE: Scale = Scale::new(&[0, 2, 4, 6, 8, 10]);
+pub const AUGMENTED: Scale = Scale::new(&[0, 3, 4, 7, 8, 11]);
+pub const BLUES: Scale = Scale::new(&[0, 3, 5, 6, 7, 10]);
+pub const MELODIC_MINOR: Scale = Scale::new(&[0, 2, 3, 5, 7, 9, 11]);
+pub const DORIAN_FLAT_2: Scale = Scale::new(&[0, 1, 3, 5, 7, 9, 10]);
+pub const LYDIAN_AUGMENTED: Scale = Scale::new(&[0, 2, 4, 6, 8, 9, 11]);
+pub const LYDIAN_DOMINANT: Scale = Scale::new(&[0, 2, 4, 6, 7, 9, 10]);
+pub const MIXOLYDIAN_FLAT_6: Scale = Scale::new(&[0, 2, 4, 5, 7, 8, 10]);
+pub const LOCRIAN_NATURAL_2: Scale = Scale::new(&[0, 2, 3, 5, 6, 8, 10]);
+pub const ALTERED: Scale = Scale::new(&[0, 1, 3, 4, 6, 8, 10]);
+pub const BEBOP_DOMINANT: Scale = Scale::new(&[0, 2, 4, 5, 7, 9, 10, 11]);
+pub const DIMINISHED_WHOLE_HALF: Scale = Scale::new(&[0, 2, 3, 5, 6, 8, 9, 11]);
+pub const DIMINISHED_HALF_WHOLE: Scale = Scale::new(&[0, 1, 3, 4, 6, 7, 9, 10]);
+pub const CHROMATIC: Scale = Scale::new(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
@@ -1 +1,3 @@
 pub mod diatonic;
+pub mod library;
+pub mod scale;
@@ -0,0 +1,465 @@
+use core::cmp::Ordering;
+
+use crate::note::Note;
+
+pub const MIN_SIZE: usize = 5;
+pub const MAX_SIZE: usize = 12;
+
+// Scale given by semitone distances of its degrees from the tonic. It can hold
+// anything from pentatonics up to the full chromatic scale. The first degree
+// is always the tonic itself.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Scale {
+    semitones: [i8; MAX_SIZE],
+    size: u8,
+}
+
+impl Scale {
+    pub const fn new(semitones: &[i8]) -> Self {
+        assert!(semitones.len() >= MIN_SIZE && semitones.len() <= MAX_SIZE);
+        assert!(semitones[0] == 0);
+
+        let mut stored = [0; MAX_SIZE];
+        let mut i = 0;
+        while i < semitones.len() {
+            assert!(i == 0 || semitones[i] > semitones[i - 1]);
+            assert!(semitones[i] < 12);
+            stored[i] = semitones[i];
+            i += 1;
+        }
+
+        Self {
+            semitones: stored,
+            size: semitones.len() as u8,
+        }
+    }
+
+    // Each of the lower 12 bits stands for a pitch class, the least
+    // significant one being the tonic. Masks without the tonic or with less
+    // than `MIN_SIZE` pitch classes do not form a valid scale.
+    pub fn from_mask(mask: u16) -> Option<Self> {
+        let mask = mask & 0x0fff;
+        if mask & 1 == 0 || (mask.count_ones() as usize) < MIN_SIZE {
+            return None;
+        }
+
+        let mut semitones = [0; MAX_SIZE];
+        let mut size = 0;
+        for semitone in 0..12 {
+            if mask & (1 << semitone) != 0 {
+                semitones[size] = semitone;
+                size += 1;
+            }
+        }
+
+        Some(Self {
+            semitones,
+            size: size as u8,
+        })
+    }
+
+    pub const fn mask(&self) -> u16 {
+        let mut mask = 0;
+        let mut i = 0;
+        while i < self.size as usize {
+            mask |= 1 << self.semitones[i];
+            i += 1;
+        }
+        mask
+    }
+
+    pub fn semitones(&self) -> &[i8] {
+        &self.semitones[..self.size as usize]
+    }
+
+    pub fn size(&self) -> usize {
+        self.size as usize
+    }
+}
+
+// Scale arithmetics. Find a note from the given scale, defined by its `root`
+// and `scale`, that forms and `interval` from given `note`.
+pub fn lookup_degree(root: Note, scale: Scale, note: Note, interval: i32) -> Option<Note> {
+    let semitones = scale.semitones();
+    let size = semitones.len() as i32;
+
+    let distance = match interval.cmp(&0) {
+        Ordering::Greater => interval - 1,
+        Ordering::Less => interval + 1,
+        Ordering::Equal => return None,
+    };
+
+    let note_index = find_index_in_scale(root, scale, note);
+
+    let octave_distance = if distance < 0 {
+        // Start an octave lower, suboctave distance is always adding to right
+        ((distance + 1) / size - 1) * 12
+    } else {
+        (distance / size) * 12
+    };
+
+    let suboctave_distance = {
+        let delta = (semitones[(note_index as i32 + distance).rem_euclid(size) as usize]
+            - semitones[note_index]) as i32;
+        if delta < 0 {
+            // Treat the scale as circular
+            delta + 12
+        } else {
+            delta
+        }
+    };
+
+    let total_semitone_distance = octave_distance + suboctave_distance;
+
+    Note::try_from_i16(note.to_midi_id() as i16 + total_semitone_distance as i16)
+}
+
+fn find_index_in_scale(root: Note, scale: Scale, note: Note) -> usize {
+    let scale_root_to_note_distance = {
+        let scale_id = (root.to_midi_id() % 12) as i32;
+        let note_id = (note.to_midi_id() % 12) as i32;
+        (note_id - scale_id).rem_euclid(12)
+    };
+
+    scale
+        .semitones()
+        .iter()
+        .enumerate()
+        .find(|(_, x)| **x as i32 == scale_root_to_note_distance)
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::scales::diatonic::{Ionian, Locrian};
+    use crate::scales::library::*;
+
+    #[test]
+    fn lookup_root_identical_degree() {
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::C3, 1);
+        assert_eq!(note.unwrap(), Note::C3);
+    }
+
+    #[test]
+    fn lookup_root_identical_degree_octave_above() {
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::C3, 8);
+        assert_eq!(note.unwrap(), Note::C4);
+    }
+
+    #[test]
+    fn lookup_root_identical_degree_octave_below() {
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::C3, -8);
+        assert_eq!(note.unwrap(), Note::C2);
+    }
+
+    #[test]
+    fn lookup_degree_near_above_root() {
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::C3, 2);
+        assert_eq!(note.unwrap(), Note::D3);
+
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::C3, 5);
+        assert_eq!(note.unwrap(), Note::G3);
+    }
+
+    #[test]
+    fn lookup_degree_near_above_root_with_root_above() {
+        let note = lookup_degree(Note::C5, Ionian.scale(), Note::C3, 2);
+        assert_eq!(note.unwrap(), Note::D3);
+
+        let note = lookup_degree(Note::C5, Ionian.scale(), Note::C3, 5);
+        assert_eq!(note.unwrap(), Note::G3);
+    }
+
+    #[test]
+    fn lookup_degree_far_above_root() {
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::C3, 7 + 2);
+        assert_eq!(note.unwrap(), Note::D4);
+
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::C3, 2 * 7 + 5);
+        assert_eq!(note.unwrap(), Note::G5);
+    }
+
+    #[test]
+    fn lookup_degree_near_below_root() {
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::C3, -2);
+        assert_eq!(note.unwrap(), Note::B2);
+
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::C3, -4);
+        assert_eq!(note.unwrap(), Note::G2);
+    }
+
+    #[test]
+    fn lookup_degree_far_below_root() {
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::C3, -7 - 2);
+        assert_eq!(note.unwrap(), Note::B1);
+
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::C3, 2 * -7 - 4);
+        assert_eq!(note.unwrap(), Note::G0);
+    }
+
+    #[test]
+    fn lookup_non_root_identical_degree() {
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::D3, 1);
+        assert_eq!(note.unwrap(), Note::D3);
+    }
+
+    #[test]
+    fn lookup_non_root_identical_degree_octave_above() {
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::D3, 8);
+        assert_eq!(note.unwrap(), Note::D4);
+    }
+
+    #[test]
+    fn lookup_non_root_identical_degree_octave_below() {
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::D3, -8);
+        assert_eq!(note.unwrap(), Note::D2);
+    }
+
+    #[test]
+    fn lookup_degree_near_above_non_root() {
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::D3, 2);
+        assert_eq!(note.unwrap(), Note::E3);
+
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::D3, 7);
+        assert_eq!(note.unwrap(), Note::C4);
+    }
+
+    #[test]
+    fn lookup_degree_far_above_non_root() {
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::D3, 7 + 2);
+        assert_eq!(note.unwrap(), Note::E4);
+
+        let note = lookup_degree(Note::C1, Ionian.scale(), Note::D3, 7 + 7);
+        assert_eq!(note.unwrap(), Note::C5);
+    }
+
+    #[test]
+    fn lookup_degree_near_below_non_root() {
+        let note = lookup_degree(Note::C3, Ionian.scale(), Note::D3, -2);
+        assert_eq!(note.unwrap(), Note::C3);
+
+        let note = lookup_degree(Note::C3, Ionian.scale(), Note::D3, -7);
+        assert_eq!(note.unwrap(), Note::E2);
+    }
+
+    #[test]
+    fn lookup_degree_far_below_non_root() {
+        let note = lookup_degree(Note::C3, Ionian.scale(), Note::D3, -7 - 2);
+        assert_eq!(note.unwrap(), Note::C2);
+
+        let note = lookup_degree(Note::C3, Ionian.scale(), Note::D3, -7 - 7);
+        assert_eq!(note.unwrap(), Note::E1);
+    }
+
+    #[test]
+    fn find_index_of_arbitrary_note_in_scale_in_octave() {
+        let index = find_index_in_scale(Note::C3, Ionian.scale(), Note::F3);
+        assert_eq!(index, 3);
+    }
+
+    #[test]
+    fn find_index_of_arbitrary_note_in_scale_higher_octave() {
+        let index = find_index_in_scale(Note::C3, Ionian.scale(), Note::A4);
+        assert_eq!(index, 5);
+    }
+
+    #[test]
+    fn find_index_of_arbitrary_note_in_scale_lower_octave() {
+        let index = find_index_in_scale(Note::C3, Ionian.scale(), Note::A2);
+        assert_eq!(index, 5);
+    }
+
+    #[test]
+    fn find_index_of_unison_in_scale_in_octave() {
+        let index = find_index_in_scale(Note::C3, Ionian.scale(), Note::C3);
+        assert_eq!(index, 0);
+    }
+
+    #[test]
+    fn find_index_of_unison_in_scale_higher_octave() {
+        let index = find_index_in_scale(Note::C3, Ionian.scale(), Note::C4);
+        assert_eq!(index, 0);
+    }
+
+    #[test]
+    fn find_index_of_unison_in_scale_lower_octave() {
+        let index = find_index_in_scale(Note::C3, Ionian.scale(), Note::C2);
+        assert_eq!(index, 0);
+    }
+
+    #[test]
+    fn find_index_of_out_of_scale_note() {
+        let index = find_index_in_scale(Note::C3, Ionian.scale(), Note::FSharp3);
+        assert_eq!(index, 0);
+    }
+
+    #[test]
+    fn find_index_of_note_below_root_pitch_class() {
+        let index = find_index_in_scale(Note::D3, Ionian.scale(), Note::CSharp3);
+        assert_eq!(index, 6);
+    }
+
+    #[test]
+    fn lookup_degree_below_root_pitch_class() {
+        let note = lookup_degree(Note::D1, Ionian.scale(), Note::CSharp3, 2);
+        assert_eq!(note.unwrap(), Note::D3);
+
+        let note = lookup_degree(Note::D1, Ionian.scale(), Note::CSharp3, 3);
+        assert_eq!(note.unwrap(), Note::E3);
+    }
+
+    #[test]
+    fn lookup_degree_in_pentatonic() {
+        let note = lookup_degree(Note::C1, MAJOR_PENTATONIC, Note::C3, 3);
+        assert_eq!(note.unwrap(), Note::E3);
+
+        let note = lookup_degree(Note::C1, MAJOR_PENTATONIC, Note::C3, 4);
+        assert_eq!(note.unwrap(), Note::G3);
+
+        let note = lookup_degree(Note::C1, MAJOR_PENTATONIC, Note::C3, 6);
+        assert_eq!(note.unwrap(), Note::C4);
+
+        let note = lookup_degree(Note::C1, MAJOR_PENTATONIC, Note::C3, -2);
+        assert_eq!(note.unwrap(), Note::A2);
+    }
+
+    #[test]
+    fn lookup_degree_in_pentatonic_from_non_root() {
+        let note = lookup_degree(Note::C1, MINOR_PENTATONIC, Note::Eb3, 2);
+        assert_eq!(note.unwrap(), Note::F3);
+
+        let note = lookup_degree(Note::C1, MINOR_PENTATONIC, Note::Bb2, 2);
+        assert_eq!(note.unwrap(), Note::C3);
+
+        let note = lookup_degree(Note::C1, MINOR_PENTATONIC, Note::Bb2, -6);
+        assert_eq!(note.unwrap(), Note::Bb1);
+    }
+
+    #[test]
+    fn lookup_degree_in_whole_tone() {
+        let note = lookup_degree(Note::C1, WHOLE_TONE, Note::C3, 4);
+        assert_eq!(note.unwrap(), Note::FSharp3);
+
+        let note = lookup_degree(Note::C1, WHOLE_TONE, Note::C3, 7);
+        assert_eq!(note.unwrap(), Note::C4);
+
+        let note = lookup_degree(Note::C1, WHOLE_TONE, Note::C3, -7);
+        assert_eq!(note.unwrap(), Note::C2);
+    }
+
+    #[test]
+    fn lookup_degree_in_blues() {
+        let note = lookup_degree(Note::C1, BLUES, Note::C3, 4);
+        assert_eq!(note.unwrap(), Note::Gb3);
+
+        let note = lookup_degree(Note::C1, BLUES, Note::C3, -3);
+        assert_eq!(note.unwrap(), Note::G2);
+    }
+
+    #[test]
+    fn lookup_degree_in_melodic_minor_from_non_root() {
+        let note = lookup_degree(Note::C1, MELODIC_MINOR, Note::D3, 3);
+        assert_eq!(note.unwrap(), Note::F3);
+
+        let note = lookup_degree(Note::C1, MELODIC_MINOR, Note::D3, 7);
+        assert_eq!(note.unwrap(), Note::C4);
+    }
+
+    #[test]
+    fn lookup_degree_in_octatonic() {
+        let note = lookup_degree(Note::C1, DIMINISHED_HALF_WHOLE, Note::C3, 3);
+        assert_eq!(note.unwrap(), Note::Eb3);
+
+        let note = lookup_degree(Note::C1, DIMINISHED_HALF_WHOLE, Note::C3, 9);
+        assert_eq!(note.unwrap(), Note::C4);
+
+        let note = lookup_degree(Note::C1, DIMINISHED_HALF_WHOLE, Note::C3, -2);
+        assert_eq!(note.unwrap(), Note::Bb2);
+    }
+
+    #[test]
+    fn lookup_degree_in_chromatic() {
+        let note = lookup_degree(Note::C1, CHROMATIC, Note::C3, 2);
+        assert_eq!(note.unwrap(), Note::CSharp3);
+
+        let note = lookup_degree(Note::C1, CHROMATIC, Note::C3, 13);
+        assert_eq!(note.unwrap(), Note::C4);
+
+        let note = lookup_degree(Note::C1, CHROMATIC, Note::C3, -13);
+        assert_eq!(note.unwrap(), Note::C2);
+    }
+
+    #[test]
+    fn get_scale_size_and_semitones() {
+        assert_eq!(MAJOR_PENTATONIC.size(), 5);
+        assert_eq!(MAJOR_PENTATONIC.semitones(), &[0, 2, 4, 7, 9]);
+        assert_eq!(CHROMATIC.size(), 12);
+    }
+
+    #[test]
+    #[should_panic]
+    fn reject_scale_with_too_few_notes() {
+        Scale::new(&[0, 2, 4, 7]);
+    }
+
+    #[test]
+    fn create_scale_from_mask() {
+        let scale = Scale::from_mask(0b1010_1011_0101).unwrap();
+        assert_eq!(scale, Ionian.scale());
+
+        let scale = Scale::from_mask(0b0010_1001_0101).unwrap();
+        assert_eq!(scale, MAJOR_PENTATONIC);
+    }
+
+    #[test]
+    fn create_chromatic_scale_from_mask() {
+        let scale = Scale::from_mask(0b1111_1111_1111).unwrap();
+        assert_eq!(scale, CHROMATIC);
+    }
+
+    #[test]
+    fn ignore_bits_above_the_octave_in_mask() {
+        let scale = Scale::from_mask(0b1111_0010_1001_0101).unwrap();
+        assert_eq!(scale, MAJOR_PENTATONIC);
+    }
+
+    #[test]
+    fn reject_mask_without_tonic() {
+        assert!(Scale::from_mask(0b1010_1011_0100).is_none());
+    }
+
+    #[test]
+    fn reject_mask_with_too_few_notes() {
+        assert!(Scale::from_mask(0b0000_1001_0001).is_none());
+        assert!(Scale::from_mask(0).is_none());
+    }
+
+    #[test]
+    fn convert_scale_to_mask_and_back() {
+        for scale in [
+            MINOR_PENTATONIC,
+            BLUES,
+            DIMINISHED_WHOLE_HALF,
+            Locrian.scale(),
+        ] {
+            assert_eq!(Scale::from_mask(scale.mask()), Some(scale));
+        }
+    }
+
+    #[test]
+    fn lookup_degree_in_scale_from_mask() {
+        let scale = Scale::from_mask(0b0100_1010_1001).unwrap();
+
+        let note = lookup_degree(Note::C1, scale, Note::C3, 2);
+        assert_eq!(note.unwrap(), Note::Eb3);
+
+        let note = lookup_degree(Note::C1, scale, Note::C3, 5);
+        assert_eq!(note.unwrap(), Note::Bb3);
+
+        let note = lookup_degree(Note::C1, scale, Note::C3, 6);
+        assert_eq!(note.unwrap(), Note::C4);
+    }
+}
@@ -0,0 +1,242 @@
+pub const STEPS: usize = 16;
+
+// Marks steps that keep the scale mode selected on the module.
+const ANY_SCALE_MODE: u8 = 0xFF;
+
+// State of the chord captured by a step. It is a plain structure of bytes, so
+// it can be persisted together with the rest of parameters.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct Step {
+    chord_root: i8,
+    chord: u8,
+    style: u8,
+    scale_mode: u8,
+}
+
+impl Step {
+    // Chord root is given as a number of scale steps from the scale root,
+    // spanning octaves, so the sequence follows changes of the scale.
+    pub(crate) fn new(chord_root: i8, chord: u8, style: u8, scale_mode: Option<u8>) -> Self {
+        Self {
+            chord_root,
+            chord,
+            style,
+            scale_mode: scale_mode.unwrap_or(ANY_SCALE_MODE),
+        }
+    }
+
+    pub fn chord_root(&self) -> i8 {
+        self.chord_root
+    }
+
+    pub fn chord(&self) -> u8 {
+        self.chord
+    }
+
+    pub fn style(&self) -> u8 {
+        self.style
+    }
+
+    pub fn scale_mode(&self) -> Option<u8> {
+        if self.scale_mode == ANY_SCALE_MODE {
+            None
+        } else {
+            Some(self.scale_mode)
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct Sequence {
+    steps: [Step; STEPS],
+    length: u8,
+}
+
+impl Sequence {
+    pub fn len(&self) -> usize {
+        (self.length as usize).min(STEPS)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, position: usize) -> Option<Step> {
+        if position < self.len() {
+            Some(self.steps[position])
+        } else {
+            None
+        }
+    }
+
+    fn push(&mut self, step: Step) -> Option<usize> {
+        let position = self.len();
+        if position < STEPS {
+            self.steps[position] = step;
+            self.length += 1;
+            Some(position)
+        } else {
+            None
+        }
+    }
+
+    fn clear(&mut self) {
+        self.length = 0;
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum State {
+    Idle,
+    Recording,
+    Playing(usize),
+}
+
+// Recording starts a new sequence and appends a step on each call. A trigger
+// ends the recording and starts the playback from the first step, each
+// following trigger moves to the next step, wrapping around at the end.
+pub struct Sequencer {
+    sequence: Sequence,
+    state: State,
+}
+
+impl Sequencer {
+    pub fn new(sequence: Sequence) -> Self {
+        Self {
+            sequence,
+            state: State::Idle,
+        }
+    }
+
+    pub fn sequence(&self) -> Sequence {
+        self.sequence
+    }
+
+    // Returns the position of the recorded step, or None if the sequence is
+    // already full.
+    pub fn record(&mut self, step: Step) -> Option<usize> {
+        if self.state != State::Recording {
+            self.sequence.clear();
+            self.state = State::Recording;
+        }
+        self.sequence.push(step)
+    }
+
+    // Returns the position of the step to be played.
+    pub fn trigger(&mut self) -> Option<usize> {
+        if self.sequence.is_empty() {
+            return None;
+        }
+
+        let position = match self.state {
+            State::Idle | State::Recording => 0,
+            State::Playing(position) => (position + 1) % self.sequence.len(),
+        };
+        self.state = State::Playing(position);
+
+        Some(position)
+    }
+
+    pub fn stop(&mut self) {
+        self.state = State::Idle;
+    }
+
+    pub fn recording(&self) -> bool {
+        self.state == State::Recording
+    }
+
+    pub fn step(&self) -> Option<Step> {
+        if let State::Playing(position) = self.state {
+            self.sequence.get(position)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(chord_root: i8) -> Step {
+        Step::new(chord_root, 0, 0, None)
+    }
+
+    #[test]
+    fn start_idle_with_stored_sequence() {
+        let mut sequencer = Sequencer::new(Sequence::default());
+        sequencer.record(step(1));
+        sequencer.record(step(2));
+
+        let mut sequencer = Sequencer::new(sequencer.sequence());
+        assert!(sequencer.step().is_none());
+        assert_eq!(sequencer.trigger(), Some(0));
+        assert_eq!(sequencer.step(), Some(step(1)));
+    }
+
+    #[test]
+    fn record_and_play_back_in_loop() {
+        let mut sequencer = Sequencer::new(Sequence::default());
+        assert_eq!(sequencer.record(step(0)), Some(0));
+        assert_eq!(sequencer.record(step(3)), Some(1));
+        assert_eq!(sequencer.record(step(4)), Some(2));
+        assert!(sequencer.recording());
+        assert!(sequencer.step().is_none());
+
+        for expected in [0, 3, 4, 0, 3] {
+            sequencer.trigger();
+            assert_eq!(sequencer.step(), Some(step(expected)));
+        }
+        assert!(!sequencer.recording());
+    }
+
+    #[test]
+    fn start_new_sequence_when_recording_again() {
+        let mut sequencer = Sequencer::new(Sequence::default());
+        sequencer.record(step(0));
+        sequencer.record(step(3));
+        sequencer.trigger();
+
+        assert_eq!(sequencer.record(step(5)), Some(0));
+        assert_eq!(sequencer.sequence().len(), 1);
+        sequencer.trigger();
+        sequencer.trigger();
+        assert_eq!(sequencer.step(), Some(step(5)));
+    }
+
+    #[test]
+    fn ignore_steps_past_the_last_one() {
+        let mut sequencer = Sequencer::new(Sequence::default());
+        for i in 0..STEPS {
+            assert_eq!(sequencer.record(step(i as i8)), Some(i));
+        }
+        assert_eq!(sequencer.record(step(100)), None);
+        assert_eq!(sequencer.sequence().len(), STEPS);
+    }
+
+    #[test]
+    fn ignore_triggers_without_sequence() {
+        let mut sequencer = Sequencer::new(Sequence::default());
+        assert_eq!(sequencer.trigger(), None);
+        assert!(sequencer.step().is_none());
+    }
+
+    #[test]
+    fn restart_playback_after_stop() {
+        let mut sequencer = Sequencer::new(Sequence::default());
+        sequencer.record(step(0));
+        sequencer.record(step(3));
+        sequencer.trigger();
+        sequencer.trigger();
+
+        sequencer.stop();
+        assert!(sequencer.step().is_none());
+        assert_eq!(sequencer.trigger(), Some(0));
+    }
+
+    #[test]
+    fn keep_scale_mode_optional() {
+        assert_eq!(Step::new(0, 0, 0, None).scale_mode(), None);
+        assert_eq!(Step::new(0, 0, 0, Some(3)).scale_mode(), Some(3));
+    }
+}
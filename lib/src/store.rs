@@ -7,6 +7,8 @@ use core::mem;
 use crc::{Crc, CRC_16_USB};
 
 use crate::config::Config;
+use crate::instrument::{USER_SCALES, USER_SCALES_DEFAULT};
+use crate::sequencer::Sequence;
 
 const CRC: Crc<u16> = Crc::<u16>::new(&CRC_16_USB);
 
@@ -33,6 +35,20 @@ pub struct Parameters {
     pub cv5_calibration_ratio: f32,
     pub cv5_calibration_offset: f32,
     pub config: Config,
+    pub user_scales: [u16; USER_SCALES],
+    pub quantization_hysteresis: f32,
+    pub glide: f32,
+    pub strum: f32,
+    pub warp: f32,
+    pub sync_ratio: f32,
+    pub modulation_index: f32,
+    pub modulation_ratio: f32,
+    pub cross_modulation: f32,
+    pub attack: f32,
+    pub decay: f32,
+    pub sustain: f32,
+    pub release: f32,
+    pub sequence: Sequence,
 }
 
 impl Default for Parameters {
@@ -59,6 +75,25 @@ impl Default for Parameters {
             cv5_calibration_ratio: 1.0,
             cv5_calibration_offset: 0.0,
             config: Config::default(),
+            user_scales: [
+                USER_SCALES_DEFAULT[0].mask(),
+                USER_SCALES_DEFAULT[1].mask(),
+                USER_SCALES_DEFAULT[2].mask(),
+                USER_SCALES_DEFAULT[3].mask(),
+            ],
+            quantization_hysteresis: 0.5,
+            glide: 0.0,
+            strum: 0.0,
+            warp: 0.0,
+            sync_ratio: 0.0,
+            modulation_index: 0.0,
+            modulation_ratio: 0.2,
+            cross_modulation: 1.0,
+            attack: 0.0,
+            decay: 0.3,
+            sustain: 1.0,
+            release: 0.3,
+            sequence: Sequence::default(),
         }
     }
 }
@@ -101,6 +136,20 @@ impl Parameters {
             && self.solo_enabled == other.solo_enabled
             && self.chord_quantization == other.chord_quantization
             && self.config == other.config
+            && self.user_scales == other.user_scales
+            && f32_close(self.quantization_hysteresis, other.quantization_hysteresis)
+            && f32_close(self.glide, other.glide)
+            && f32_close(self.strum, other.strum)
+            && f32_close(self.warp, other.warp)
+            && f32_close(self.sync_ratio, other.sync_ratio)
+            && f32_close(self.modulation_index, other.modulation_index)
+            && f32_close(self.modulation_ratio, other.modulation_ratio)
+            && f32_close(self.cross_modulation, other.cross_modulation)
+            && f32_close(self.attack, other.attack)
+            && f32_close(self.decay, other.decay)
+            && f32_close(self.sustain, other.sustain)
+            && f32_close(self.release, other.release)
+            && self.sequence == other.sequence
     }
 }
 
@@ -123,7 +172,7 @@ lazy_static! {
 }
 
 // This constant is used to invalidate data when needed
-const TOKEN: u16 = 102;
+const TOKEN: u16 = 116;
 
 pub struct InvalidData;
 
@@ -222,6 +271,8 @@ impl Store {
 mod tests {
     use super::*;
 
+    use crate::sequencer::{Sequencer, Step};
+
     #[test]
     fn initialize_store() {
         let _store = Store::new(Parameters::default(), 0);
@@ -273,6 +324,10 @@ mod tests {
 
     #[test]
     fn convert_parameters_to_and_from_bytes() {
+        let mut sequencer = Sequencer::new(Sequence::default());
+        sequencer.record(Step::new(-3, 2, 1, Some(9)));
+        sequencer.record(Step::new(4, 0, 2, None));
+
         let parameters = Parameters {
             note: 0.1,
             wavetable: 0.2,
@@ -295,6 +350,25 @@ mod tests {
             cv5_calibration_offset: 0.97,
             chord_quantization: true,
             config: Config::from(1),
+            user_scales: [
+                0b1010_1011_0101,
+                0b0100_1010_1001,
+                0b0100_1110_1001,
+                0b0101_0101_0101,
+            ],
+            quantization_hysteresis: 0.98,
+            glide: 0.99,
+            strum: 0.995,
+            warp: 0.5,
+            sync_ratio: 0.25,
+            modulation_index: 0.3,
+            modulation_ratio: 0.7,
+            cross_modulation: 0.4,
+            attack: 0.11,
+            decay: 0.12,
+            sustain: 0.13,
+            release: 0.14,
+            sequence: sequencer.sequence(),
         };
         let bytes = parameters.to_bytes();
         assert!(Parameters::from_bytes(bytes) == parameters);
@@ -0,0 +1,181 @@
+use crate::random::Random;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Order {
+    Up,
+    Down,
+    Random,
+}
+
+// Staggers onsets of chord voices. Once started, each voice waits for its turn
+// given by the order of its pitch within the chord. Voices are counted down in
+// samples, so the spacing holds on any sample rate.
+pub struct Strum<const N: usize> {
+    sample_rate: f32,
+    spacing: u32,
+    order: Order,
+    random: Random,
+    countdowns: [u32; N],
+}
+
+impl<const N: usize> Strum<N> {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate: sample_rate as f32,
+            spacing: 0,
+            order: Order::Up,
+            random: Random::new(0),
+            countdowns: [0; N],
+        }
+    }
+
+    pub fn set_spacing(&mut self, milliseconds: f32) {
+        self.spacing = (milliseconds * self.sample_rate / 1000.0) as u32;
+    }
+
+    pub fn set_order(&mut self, order: Order) {
+        self.order = order;
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.spacing > 0
+    }
+
+    // Schedule onsets of voices playing the given frequencies. The first one
+    // in the order starts right away.
+    pub fn start(&mut self, frequencies: [Option<f32>; N]) {
+        let mut order = [0; N];
+        let mut count = 0;
+        for (i, frequency) in frequencies.iter().enumerate() {
+            if let Some(frequency) = frequency {
+                let mut position = count;
+                while position > 0 && frequencies[order[position - 1]].unwrap() > *frequency {
+                    order[position] = order[position - 1];
+                    position -= 1;
+                }
+                order[position] = i;
+                count += 1;
+            }
+        }
+
+        match self.order {
+            Order::Up => (),
+            Order::Down => order[..count].reverse(),
+            Order::Random => {
+                for i in (1..count).rev() {
+                    let j = self.random.next_u32() as usize % (i + 1);
+                    order.swap(i, j);
+                }
+            }
+        }
+
+        self.countdowns = [0; N];
+        for (position, voice) in order[..count].iter().enumerate() {
+            self.countdowns[*voice] = position as u32 * self.spacing;
+        }
+    }
+
+    pub fn pending(&self, voice: usize) -> bool {
+        self.countdowns[voice] > 0
+    }
+
+    // Returns true if any of the voices reached its onset.
+    pub fn advance(&mut self, samples: usize) -> bool {
+        let mut onset = false;
+        for countdown in self.countdowns.iter_mut().filter(|c| **c > 0) {
+            *countdown = countdown.saturating_sub(samples as u32);
+            onset |= *countdown == 0;
+        }
+        onset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 1000;
+    const CHORD: [Option<f32>; 4] = [Some(200.0), Some(100.0), None, Some(300.0)];
+
+    fn onsets(strum: &mut Strum<4>) -> [Option<usize>; 4] {
+        let mut onsets = [None; 4];
+        for sample in 0..100 {
+            for (voice, onset) in onsets.iter_mut().enumerate() {
+                if onset.is_none() && !strum.pending(voice) {
+                    *onset = Some(sample);
+                }
+            }
+            strum.advance(1);
+        }
+        onsets
+    }
+
+    #[test]
+    fn strum_up() {
+        let mut strum = Strum::new(SAMPLE_RATE);
+        strum.set_spacing(10.0);
+        strum.start(CHORD);
+        assert_eq!(onsets(&mut strum), [Some(10), Some(0), Some(0), Some(20)]);
+    }
+
+    #[test]
+    fn strum_down() {
+        let mut strum = Strum::new(SAMPLE_RATE);
+        strum.set_spacing(10.0);
+        strum.set_order(Order::Down);
+        strum.start(CHORD);
+        assert_eq!(onsets(&mut strum), [Some(10), Some(20), Some(0), Some(0)]);
+    }
+
+    #[test]
+    fn strum_in_random_order() {
+        let mut strum = Strum::new(SAMPLE_RATE);
+        strum.set_spacing(10.0);
+        strum.set_order(Order::Random);
+
+        let mut orders_seen = 0;
+        let mut previous = None;
+        for _ in 0..10 {
+            strum.start(CHORD);
+            let mut onsets = onsets(&mut strum);
+            assert_eq!(onsets[2], Some(0));
+            if previous != Some(onsets) {
+                orders_seen += 1;
+            }
+            previous = Some(onsets);
+            onsets.sort_unstable();
+            assert_eq!(onsets, [Some(0), Some(0), Some(10), Some(20)]);
+        }
+        assert!(orders_seen > 1);
+    }
+
+    #[test]
+    fn scale_spacing_with_sample_rate() {
+        let mut strum = Strum::new(48_000);
+        strum.set_spacing(10.0);
+        strum.start(CHORD);
+        assert!(strum.pending(0));
+        assert!(!strum.advance(479));
+        assert!(strum.advance(1));
+        assert!(!strum.pending(0));
+    }
+
+    #[test]
+    fn report_onset_once() {
+        let mut strum = Strum::new(SAMPLE_RATE);
+        strum.set_spacing(10.0);
+        strum.start(CHORD);
+        assert!(strum.advance(15));
+        assert!(!strum.advance(4));
+        assert!(strum.advance(1));
+        assert!(!strum.advance(100));
+    }
+
+    #[test]
+    fn keep_all_voices_without_spacing() {
+        let mut strum = Strum::new(SAMPLE_RATE);
+        strum.start(CHORD);
+        assert!(!strum.enabled());
+        assert!((0..4).all(|voice| !strum.pending(voice)));
+    }
+}
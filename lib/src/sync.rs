@@ -0,0 +1,128 @@
+use super::minblep::{LENGTH, OVERSAMPLING, RESIDUAL};
+
+// The firmware renders 32 samples at a time. The oscillator wraps at most
+// once per sample, so this leaves plenty of room. Any wraps past the capacity
+// are dropped.
+const CAPACITY: usize = 64;
+
+// Wraps of the phase of the leading oscillator within a single buffer. Each
+// is stored as the index of the sample after which it happened, and the time
+// that passed since the wrap until the next sample, as a fraction of a sample.
+pub struct Events {
+    events: [(usize, f32); CAPACITY],
+    len: usize,
+}
+
+impl Events {
+    pub fn new() -> Self {
+        Self {
+            events: [(0, 0.0); CAPACITY],
+            len: 0,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    pub fn push(&mut self, index: usize, offset: f32) {
+        if self.len < CAPACITY {
+            self.events[self.len] = (index, offset);
+            self.len += 1;
+        }
+    }
+
+    pub fn as_slice(&self) -> &[(usize, f32)] {
+        &self.events[..self.len]
+    }
+}
+
+// Corrections of discontinuities caused by resetting the phase. Each step in
+// the output is smoothed by the minimum-phase band-limited step, spread over
+// the following samples.
+#[derive(Clone, Copy)]
+pub struct Blep {
+    pending: [f32; LENGTH],
+    position: usize,
+}
+
+impl Blep {
+    pub fn new() -> Self {
+        Self {
+            pending: [0.0; LENGTH],
+            position: 0,
+        }
+    }
+
+    // Offset is the time between the step and the next sample, as a fraction
+    // of a sample.
+    pub fn add(&mut self, step: f32, offset: f32) {
+        for i in 0..LENGTH {
+            let slot = (self.position + i) % LENGTH;
+            self.pending[slot] += step * residual(offset + i as f32);
+        }
+    }
+
+    pub fn tick(&mut self) -> f32 {
+        let value = self.pending[self.position];
+        self.pending[self.position] = 0.0;
+        self.position = (self.position + 1) % LENGTH;
+        value
+    }
+}
+
+fn residual(time: f32) -> f32 {
+    let scaled = time * OVERSAMPLING as f32;
+    let index = scaled as usize;
+    if index >= RESIDUAL.len() - 1 {
+        return 0.0;
+    }
+    let fraction = scaled - index as f32;
+    RESIDUAL[index] * (1.0 - fraction) + RESIDUAL[index + 1] * fraction
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drop_events_over_capacity() {
+        let mut events = Events::new();
+        for i in 0..CAPACITY + 10 {
+            events.push(i, 0.5);
+        }
+        assert_eq!(events.as_slice().len(), CAPACITY);
+
+        events.clear();
+        assert!(events.as_slice().is_empty());
+    }
+
+    #[test]
+    fn cancel_step_right_after_it() {
+        let mut blep = Blep::new();
+        blep.add(1.0, 0.0);
+        assert_relative_eq!(blep.tick(), -1.0, epsilon = 0.001);
+    }
+
+    #[test]
+    fn settle_after_the_step() {
+        let mut blep = Blep::new();
+        blep.add(1.0, 0.5);
+        for _ in 0..LENGTH {
+            blep.tick();
+        }
+        for _ in 0..LENGTH {
+            assert_relative_eq!(blep.tick(), 0.0);
+        }
+    }
+
+    #[test]
+    fn smooth_step_into_gradual_rise() {
+        let mut blep = Blep::new();
+        blep.add(1.0, 0.0);
+        let output: [f32; 8] = core::array::from_fn(|_| 1.0 + blep.tick());
+        assert!(output[0] < 0.01);
+        assert!(output[4] > 0.5);
+        assert_relative_eq!(output[7], 1.0, epsilon = 0.2);
+    }
+}
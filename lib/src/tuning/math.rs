@@ -0,0 +1,94 @@
+// Tuning tables are calculated rarely, but their precision is audible. The
+// approximations offered by micromath are too coarse for that, so these
+// double precision alternatives are used instead.
+
+use core::f64::consts::LN_2;
+
+const MANTISSA_MASK: u64 = (1 << 52) - 1;
+const EXPONENT_BIAS: i64 = 1023;
+
+pub fn exp2(x: f64) -> f64 {
+    let mut integer = x as i64;
+    if (integer as f64) > x {
+        integer -= 1;
+    }
+    let fraction = x - integer as f64;
+
+    // Taylor series of e^y converges quickly for y in [0, ln 2)
+    let y = fraction * LN_2;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for i in 1..20 {
+        term *= y / i as f64;
+        sum += term;
+    }
+
+    sum * power_of_two(integer)
+}
+
+pub fn log2(x: f64) -> f64 {
+    debug_assert!(x > 0.0);
+
+    let bits = x.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as i64 - EXPONENT_BIAS;
+    let mantissa = f64::from_bits((bits & MANTISSA_MASK) | ((EXPONENT_BIAS as u64) << 52));
+
+    // ln(m) = 2 * atanh((m - 1) / (m + 1)), where the argument is within
+    // [0, 1/3) for m in [1, 2)
+    let t = (mantissa - 1.0) / (mantissa + 1.0);
+    let t_squared = t * t;
+    let mut term = t;
+    let mut sum = 0.0;
+    for i in 0..30 {
+        sum += term / (2 * i + 1) as f64;
+        term *= t_squared;
+    }
+
+    exponent as f64 + 2.0 * sum / LN_2
+}
+
+fn power_of_two(exponent: i64) -> f64 {
+    let exponent = exponent.clamp(-EXPONENT_BIAS + 1, EXPONENT_BIAS);
+    f64::from_bits(((exponent + EXPONENT_BIAS) as u64) << 52)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calculate_exp2_of_integers() {
+        assert_relative_eq!(exp2(0.0), 1.0);
+        assert_relative_eq!(exp2(1.0), 2.0);
+        assert_relative_eq!(exp2(10.0), 1024.0);
+        assert_relative_eq!(exp2(-2.0), 0.25);
+    }
+
+    #[test]
+    fn calculate_exp2_of_fractions() {
+        assert_relative_eq!(exp2(0.5), core::f64::consts::SQRT_2, epsilon = 1e-12);
+        assert_relative_eq!(exp2(7.0 / 12.0), 1.498_307_076_876_681_5, epsilon = 1e-12);
+        assert_relative_eq!(
+            exp2(-0.5),
+            core::f64::consts::FRAC_1_SQRT_2,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn calculate_log2() {
+        assert_relative_eq!(log2(1.0), 0.0);
+        assert_relative_eq!(log2(2.0), 1.0);
+        assert_relative_eq!(log2(0.25), -2.0);
+        assert_relative_eq!(log2(1.5), 0.584_962_500_721_156_2, epsilon = 1e-12);
+        assert_relative_eq!(log2(440.0), 8.781_359_713_524_66, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn log2_reverts_exp2() {
+        for i in -100..100 {
+            let x = i as f64 * 0.123;
+            assert_relative_eq!(log2(exp2(x)), x, epsilon = 1e-12);
+        }
+    }
+}
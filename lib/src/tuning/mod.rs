@@ -0,0 +1,4 @@
+mod math;
+pub mod scala;
+pub mod table;
+pub mod temperament;
@@ -0,0 +1,456 @@
+// Parsers of Scala scale (.scl) and keyboard mapping (.kbm) files.
+//
+// The format is documented at https://www.huygens-fokker.org/scala/scl_format.html
+// and https://www.huygens-fokker.org/scala/help.htm#mappings. Both parsers
+// work on a borrowed string and keep their results in fixed-size arrays, so
+// they can be used without an allocator.
+
+use super::math;
+
+pub const MAX_PITCHES: usize = 128;
+pub const MAX_MAP_SIZE: usize = 128;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParseError {
+    UnexpectedEnd,
+    InvalidNumber,
+    InvalidPitch,
+    TooManyPitches,
+    InvalidMapping,
+}
+
+// Scale as it is defined in a .scl file. The implicit unison is not stored,
+// the last pitch is the period of the scale, usually an octave.
+pub struct Scale {
+    ratios: [f64; MAX_PITCHES],
+    len: usize,
+}
+
+impl Scale {
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let mut lines = source.lines().filter(|l| !is_comment(l));
+
+        // The first line is a description and it may be empty
+        lines.next().ok_or(ParseError::UnexpectedEnd)?;
+        let mut lines = lines.filter(|l| !l.trim().is_empty());
+
+        let len: usize = parse_number(lines.next())?;
+        if len == 0 {
+            return Err(ParseError::InvalidPitch);
+        }
+        if len > MAX_PITCHES {
+            return Err(ParseError::TooManyPitches);
+        }
+
+        let mut ratios = [1.0; MAX_PITCHES];
+        for ratio in ratios.iter_mut().take(len) {
+            *ratio = parse_pitch(lines.next().ok_or(ParseError::UnexpectedEnd)?)?;
+        }
+
+        Ok(Self { ratios, len })
+    }
+
+    pub fn size(&self) -> usize {
+        self.len
+    }
+
+    pub fn period(&self) -> f64 {
+        self.ratios[self.len - 1]
+    }
+
+    // Degree 0 is the unison. Degrees outside of the first period are
+    // repeated using the last pitch of the scale.
+    pub fn ratio(&self, degree: i32) -> f64 {
+        let len = self.len as i32;
+        let periods = degree.div_euclid(len);
+        let index = degree.rem_euclid(len);
+
+        let within_period = if index == 0 {
+            1.0
+        } else {
+            self.ratios[index as usize - 1]
+        };
+
+        within_period * math::exp2(periods as f64 * math::log2(self.period()))
+    }
+}
+
+// Keyboard mapping as it is defined in a .kbm file. Map size of 0 stands for
+// a linear mapping, where every key plays the following degree of the scale.
+pub struct KeyboardMapping {
+    map: [Option<i32>; MAX_MAP_SIZE],
+    map_size: usize,
+    first_note: u8,
+    last_note: u8,
+    middle_note: u8,
+    reference_note: u8,
+    reference_frequency: f64,
+    formal_octave: i32,
+}
+
+impl Default for KeyboardMapping {
+    // Linear mapping with the unison on C4, tuned the same as in 12-TET.
+    fn default() -> Self {
+        Self {
+            map: [None; MAX_MAP_SIZE],
+            map_size: 0,
+            first_note: 0,
+            last_note: 127,
+            middle_note: 60,
+            reference_note: 60,
+            reference_frequency: 261.625_565_300_598_6,
+            formal_octave: 0,
+        }
+    }
+}
+
+impl KeyboardMapping {
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let mut lines = source
+            .lines()
+            .filter(|l| !is_comment(l) && !l.trim().is_empty());
+
+        let map_size: usize = parse_number(lines.next())?;
+        if map_size > MAX_MAP_SIZE {
+            return Err(ParseError::TooManyPitches);
+        }
+        let first_note = parse_key(lines.next())?;
+        let last_note = parse_key(lines.next())?;
+        let middle_note = parse_key(lines.next())?;
+        let reference_note = parse_key(lines.next())?;
+        let reference_frequency: f64 = parse_number(lines.next())?;
+        if reference_frequency <= 0.0 {
+            return Err(ParseError::InvalidNumber);
+        }
+        let formal_octave: i32 = parse_number(lines.next())?;
+
+        // Missing entries at the end of the map are left unmapped
+        let mut map = [None; MAX_MAP_SIZE];
+        for (entry, line) in map.iter_mut().take(map_size).zip(lines) {
+            let token = first_token(line);
+            *entry = if token == "x" || token == "X" {
+                None
+            } else {
+                Some(token.parse().map_err(|_| ParseError::InvalidNumber)?)
+            };
+        }
+
+        if first_note > last_note {
+            return Err(ParseError::InvalidMapping);
+        }
+
+        Ok(Self {
+            map,
+            map_size,
+            first_note,
+            last_note,
+            middle_note,
+            reference_note,
+            reference_frequency,
+            formal_octave,
+        })
+    }
+
+    pub fn reference_note(&self) -> u8 {
+        self.reference_note
+    }
+
+    pub fn reference_frequency(&self) -> f64 {
+        self.reference_frequency
+    }
+
+    pub fn retunes(&self, key: u8) -> bool {
+        key >= self.first_note && key <= self.last_note
+    }
+
+    // Returns the number of formal octaves and the scale degree played by the
+    // given key, or None if the key is unmapped.
+    pub fn lookup(&self, key: u8) -> Option<(i32, i32)> {
+        let offset = key as i32 - self.middle_note as i32;
+        if self.map_size == 0 {
+            return Some((0, offset));
+        }
+
+        let map_size = self.map_size as i32;
+        let octave = offset.div_euclid(map_size);
+        let index = offset.rem_euclid(map_size) as usize;
+        self.map[index].map(|degree| (octave, degree))
+    }
+
+    // Number of keys between repetitions of the map, spanning a single formal
+    // octave. Linear mappings play a degree per key.
+    pub fn octave_keys(&self, scale: &Scale) -> i32 {
+        if self.map_size == 0 {
+            self.formal_octave(scale)
+        } else {
+            self.map_size as i32
+        }
+    }
+
+    // Degree of the scale that is considered an octave between repetitions
+    // of the map. Zero stands for the period of the scale.
+    pub fn formal_octave(&self, scale: &Scale) -> i32 {
+        if self.formal_octave == 0 {
+            scale.size() as i32
+        } else {
+            self.formal_octave
+        }
+    }
+}
+
+fn is_comment(line: &str) -> bool {
+    line.starts_with('!')
+}
+
+fn first_token(line: &str) -> &str {
+    line.split_whitespace().next().unwrap_or("")
+}
+
+fn parse_number<T: core::str::FromStr>(line: Option<&str>) -> Result<T, ParseError> {
+    let line = line.ok_or(ParseError::UnexpectedEnd)?;
+    first_token(line)
+        .parse()
+        .map_err(|_| ParseError::InvalidNumber)
+}
+
+fn parse_key(line: Option<&str>) -> Result<u8, ParseError> {
+    let key: u8 = parse_number(line)?;
+    if key > 127 {
+        return Err(ParseError::InvalidNumber);
+    }
+    Ok(key)
+}
+
+// Pitches containing a period are in cents, all the others are ratios, with
+// an optional denominator.
+fn parse_pitch(line: &str) -> Result<f64, ParseError> {
+    let token = first_token(line);
+
+    let ratio = if token.contains('.') {
+        let cents: f64 = token.parse().map_err(|_| ParseError::InvalidPitch)?;
+        math::exp2(cents / 1200.0)
+    } else {
+        let (numerator, denominator) = match token.split_once('/') {
+            Some((numerator, denominator)) => (numerator, denominator),
+            None => (token, "1"),
+        };
+        let numerator: u64 = numerator.parse().map_err(|_| ParseError::InvalidPitch)?;
+        let denominator: u64 = denominator.parse().map_err(|_| ParseError::InvalidPitch)?;
+        if denominator == 0 {
+            return Err(ParseError::InvalidPitch);
+        }
+        numerator as f64 / denominator as f64
+    };
+
+    if ratio > 0.0 {
+        Ok(ratio)
+    } else {
+        Err(ParseError::InvalidPitch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JUST_MAJOR: &str = "! just.scl
+!
+Just major scale
+ 7
+!
+ 9/8
+ 5/4
+ 4/3
+ 3/2
+ 5/3
+ 15/8
+ 2/1
+";
+
+    const TWELVE_TET: &str = "! 12tet.scl
+12 tone equal temperament
+12
+100.0
+200.
+300.0
+400.0
+500.0
+600.0
+700.0
+800.0
+900.0
+1000.0
+1100.0
+1200.0
+";
+
+    const WHITE_KEYS: &str = "! white.kbm
+! Map size
+12
+! First and last MIDI note
+0
+127
+! Middle note
+60
+! Reference note and frequency
+69
+440.0
+! Formal octave
+7
+! Mapping
+0
+x
+1
+x
+2
+3
+x
+4
+x
+5
+x
+6
+";
+
+    #[test]
+    fn parse_scale_with_ratios() {
+        let scale = Scale::parse(JUST_MAJOR).unwrap();
+
+        assert_eq!(scale.size(), 7);
+        assert_relative_eq!(scale.ratio(0), 1.0);
+        assert_relative_eq!(scale.ratio(2), 5.0 / 4.0);
+        assert_relative_eq!(scale.ratio(4), 3.0 / 2.0);
+        assert_relative_eq!(scale.period(), 2.0);
+    }
+
+    #[test]
+    fn parse_scale_with_cents() {
+        let scale = Scale::parse(TWELVE_TET).unwrap();
+
+        assert_eq!(scale.size(), 12);
+        assert_relative_eq!(scale.ratio(7), 1.498_307_076_876_681_5, epsilon = 1e-9);
+        assert_relative_eq!(scale.period(), 2.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn parse_scale_with_integer_ratio_and_trailing_text() {
+        let scale = Scale::parse("\n2\n3/2 perfect fifth\n2 octave\n")
+            .ok()
+            .unwrap();
+
+        assert_relative_eq!(scale.ratio(1), 1.5);
+        assert_relative_eq!(scale.ratio(2), 2.0);
+    }
+
+    #[test]
+    fn repeat_scale_ratio_over_periods() {
+        let scale = Scale::parse(JUST_MAJOR).unwrap();
+
+        assert_relative_eq!(scale.ratio(7), 2.0, epsilon = 1e-9);
+        assert_relative_eq!(scale.ratio(11), 3.0, epsilon = 1e-9);
+        assert_relative_eq!(scale.ratio(-3), 3.0 / 4.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn fail_parsing_scale_with_missing_pitches() {
+        assert_eq!(
+            Scale::parse("Short\n3\n9/8\n2/1\n").err(),
+            Some(ParseError::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn fail_parsing_scale_with_invalid_pitch() {
+        assert_eq!(
+            Scale::parse("Invalid\n1\nthree\n").err(),
+            Some(ParseError::InvalidPitch)
+        );
+        assert_eq!(
+            Scale::parse("Invalid\n1\n3/0\n").err(),
+            Some(ParseError::InvalidPitch)
+        );
+        assert_eq!(
+            Scale::parse("Invalid\n1\n-3/2\n").err(),
+            Some(ParseError::InvalidPitch)
+        );
+    }
+
+    #[test]
+    fn fail_parsing_scale_with_invalid_count() {
+        assert_eq!(
+            Scale::parse("Invalid\nmany\n").err(),
+            Some(ParseError::InvalidNumber)
+        );
+        assert_eq!(
+            Scale::parse("Invalid\n0\n").err(),
+            Some(ParseError::InvalidPitch)
+        );
+        assert_eq!(
+            Scale::parse("Invalid\n1000\n").err(),
+            Some(ParseError::TooManyPitches)
+        );
+    }
+
+    #[test]
+    fn parse_keyboard_mapping() {
+        let mapping = KeyboardMapping::parse(WHITE_KEYS).unwrap();
+
+        assert_eq!(mapping.reference_note(), 69);
+        assert_relative_eq!(mapping.reference_frequency(), 440.0);
+        assert_eq!(mapping.lookup(60), Some((0, 0)));
+        assert_eq!(mapping.lookup(61), None);
+        assert_eq!(mapping.lookup(64), Some((0, 2)));
+        assert_eq!(mapping.lookup(72), Some((1, 0)));
+        assert_eq!(mapping.lookup(59), Some((-1, 6)));
+    }
+
+    #[test]
+    fn lookup_in_linear_keyboard_mapping() {
+        let mapping = KeyboardMapping::default();
+
+        assert_eq!(mapping.lookup(60), Some((0, 0)));
+        assert_eq!(mapping.lookup(79), Some((0, 19)));
+        assert_eq!(mapping.lookup(40), Some((0, -20)));
+    }
+
+    #[test]
+    fn resolve_formal_octave() {
+        let scale = Scale::parse(TWELVE_TET).unwrap();
+
+        assert_eq!(KeyboardMapping::default().formal_octave(&scale), 12);
+        let mapping = KeyboardMapping::parse(WHITE_KEYS).unwrap();
+        assert_eq!(mapping.formal_octave(&scale), 7);
+    }
+
+    #[test]
+    fn retune_only_keys_within_range() {
+        let mapping = KeyboardMapping::parse("0\n10\n20\n15\n15\n100.0\n0\n")
+            .ok()
+            .unwrap();
+
+        assert!(!mapping.retunes(9));
+        assert!(mapping.retunes(10));
+        assert!(mapping.retunes(20));
+        assert!(!mapping.retunes(21));
+    }
+
+    #[test]
+    fn fail_parsing_incomplete_keyboard_mapping() {
+        assert_eq!(
+            KeyboardMapping::parse("12\n0\n127\n60\n").err(),
+            Some(ParseError::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn fail_parsing_keyboard_mapping_with_invalid_range() {
+        assert_eq!(
+            KeyboardMapping::parse("0\n100\n10\n60\n69\n440.0\n0\n").err(),
+            Some(ParseError::InvalidMapping)
+        );
+        assert_eq!(
+            KeyboardMapping::parse("0\n0\n200\n60\n69\n440.0\n0\n").err(),
+            Some(ParseError::InvalidNumber)
+        );
+    }
+}
@@ -0,0 +1,362 @@
+use super::math;
+use super::scala::{KeyboardMapping, ParseError, Scale};
+use crate::note::Note;
+
+const KEYS: usize = Note::HIGHEST_NOTE as usize + 1;
+
+// Frequency of every MIDI key. This is what the instrument consults whenever
+// it needs to turn a note into a pitch. Keys left unmapped by the keyboard
+// mapping borrow the pitch of their closest mapped neighbour, quantizers and
+// chord builders are expected to snap to mapped keys using `snap`.
+#[derive(Clone)]
+pub struct Tuning {
+    frequencies: [f32; KEYS],
+    vocts: [f32; KEYS],
+    mapped: [bool; KEYS],
+    octave_keys: u8,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self::equal_temperament()
+    }
+}
+
+impl Tuning {
+    pub fn equal_temperament() -> Self {
+        let mut frequencies = [0.0; KEYS];
+        let mut vocts = [0.0; KEYS];
+        for key in 0..KEYS {
+            let note = Note::from_u8(key as u8);
+            frequencies[key] = note.to_freq_f32();
+            vocts[key] = note.to_voct();
+        }
+
+        Self {
+            frequencies,
+            vocts,
+            mapped: [true; KEYS],
+            octave_keys: 12,
+        }
+    }
+
+    // Convenience wrapper, loading the linear keyboard mapping when no .kbm
+    // source is given.
+    pub fn parse(scl: &str, kbm: Option<&str>) -> Result<Self, ParseError> {
+        let scale = Scale::parse(scl)?;
+        let mapping = match kbm {
+            Some(kbm) => KeyboardMapping::parse(kbm)?,
+            None => KeyboardMapping::default(),
+        };
+        Self::from_scala(&scale, &mapping)
+    }
+
+    pub fn from_scala(scale: &Scale, mapping: &KeyboardMapping) -> Result<Self, ParseError> {
+        let formal_octave = scale.ratio(mapping.formal_octave(scale));
+        let ratio = |key: u8| -> Option<f64> {
+            mapping.lookup(key).map(|(octave, degree)| {
+                scale.ratio(degree) * math::exp2(octave as f64 * math::log2(formal_octave))
+            })
+        };
+
+        let reference_ratio = ratio(mapping.reference_note()).ok_or(ParseError::InvalidMapping)?;
+        let lowest_frequency = Note::CMinus1.to_freq_f32() as f64;
+
+        let octave_keys = mapping.octave_keys(scale);
+        if !(1..KEYS as i32).contains(&octave_keys) {
+            return Err(ParseError::InvalidMapping);
+        }
+
+        let mut tuning = Self::equal_temperament();
+        tuning.octave_keys = octave_keys as u8;
+        for key in 0..KEYS {
+            if !mapping.retunes(key as u8) {
+                continue;
+            }
+            match ratio(key as u8) {
+                Some(ratio) => {
+                    let frequency = mapping.reference_frequency() * ratio / reference_ratio;
+                    tuning.frequencies[key] = frequency as f32;
+                    tuning.vocts[key] = math::log2(frequency / lowest_frequency) as f32;
+                }
+                None => tuning.mapped[key] = false,
+            }
+        }
+
+        for key in 0..KEYS {
+            if !tuning.mapped[key] {
+                let closest = tuning.closest_mapped(key);
+                tuning.frequencies[key] = tuning.frequencies[closest];
+                tuning.vocts[key] = tuning.vocts[closest];
+            }
+        }
+
+        Ok(tuning)
+    }
+
+    #[inline(always)]
+    pub fn frequency(&self, note: Note) -> f32 {
+        self.frequencies[note as usize]
+    }
+
+    #[inline(always)]
+    pub fn voct(&self, note: Note) -> f32 {
+        self.vocts[note as usize]
+    }
+
+    #[inline(always)]
+    pub fn octave_keys(&self) -> u8 {
+        self.octave_keys
+    }
+
+    // Scales are defined in semitones of 12-TET. On mappings whose formal
+    // octave spans a different number of keys, their steps are spread over
+    // the keys proportionally.
+    pub fn keys(&self, semitones: u8) -> u8 {
+        ((semitones as u16 * self.octave_keys as u16 + 6) / 12) as u8
+    }
+
+    // The closest mapped key, preferring the lower one when there are two.
+    pub fn snap(&self, note: Note) -> Note {
+        Note::from_u8(self.closest_mapped(note as usize) as u8)
+    }
+
+    // Find the mapped key whose pitch is the closest to the given voltage.
+    pub fn quantize(&self, voct: f32) -> Note {
+        let mut closest = 0;
+        let mut closest_distance = f32::MAX;
+        for key in (0..KEYS).filter(|k| self.mapped[*k]) {
+            let distance = (self.vocts[key] - voct).abs();
+            if distance < closest_distance {
+                closest = key;
+                closest_distance = distance;
+            }
+        }
+        Note::from_u8(closest as u8)
+    }
+
+    fn closest_mapped(&self, key: usize) -> usize {
+        for distance in 0..KEYS {
+            if key >= distance && self.mapped[key - distance] {
+                return key - distance;
+            }
+            if key + distance < KEYS && self.mapped[key + distance] {
+                return key + distance;
+            }
+        }
+        // Reference note is always mapped, so this is never reached
+        key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWELVE_TET: &str = "12 tone equal temperament
+12
+100.0
+200.0
+300.0
+400.0
+500.0
+600.0
+700.0
+800.0
+900.0
+1000.0
+1100.0
+2/1
+";
+
+    const NINETEEN_TET: &str = "19 tone equal temperament
+19
+63.15789
+126.31579
+189.47368
+252.63158
+315.78947
+378.94737
+442.10526
+505.26316
+568.42105
+631.57895
+694.73684
+757.89474
+821.05263
+884.21053
+947.36842
+1010.52632
+1073.68421
+1136.84211
+2/1
+";
+
+    const JUST_MAJOR: &str = "Just major scale
+7
+9/8
+5/4
+4/3
+3/2
+5/3
+15/8
+2/1
+";
+
+    const WHITE_KEYS: &str = "12
+0
+127
+60
+69
+440.0
+7
+0
+x
+1
+x
+2
+3
+x
+4
+x
+5
+x
+6
+";
+
+    #[test]
+    fn equal_temperament_matches_notes() {
+        let tuning = Tuning::equal_temperament();
+
+        assert_relative_eq!(tuning.frequency(Note::A4), 440.0);
+        assert_relative_eq!(tuning.voct(Note::C4), Note::C4.to_voct());
+        assert_eq!(tuning.snap(Note::CSharp4), Note::CSharp4);
+    }
+
+    #[test]
+    fn twelve_tone_scale_matches_equal_temperament() {
+        let tuning = Tuning::parse(TWELVE_TET, None).unwrap();
+        let reference = Tuning::equal_temperament();
+
+        for key in 0..KEYS {
+            let note = Note::from_u8(key as u8);
+            assert_relative_eq!(
+                tuning.frequency(note),
+                reference.frequency(note),
+                max_relative = 0.0001
+            );
+            assert_relative_eq!(tuning.voct(note), reference.voct(note), epsilon = 0.0001);
+        }
+    }
+
+    #[test]
+    fn nineteen_tone_scale_is_mapped_linearly() {
+        let tuning = Tuning::parse(NINETEEN_TET, None).unwrap();
+
+        assert_relative_eq!(
+            tuning.frequency(Note::C4),
+            261.625_55,
+            max_relative = 0.0001
+        );
+        assert_relative_eq!(
+            tuning.voct(Note::CSharp4) - tuning.voct(Note::C4),
+            1.0 / 19.0,
+            epsilon = 0.0001
+        );
+        assert_relative_eq!(
+            tuning.frequency(Note::from_u8(60 + 19)),
+            2.0 * 261.625_55,
+            max_relative = 0.0001
+        );
+    }
+
+    #[test]
+    fn octave_spans_keys_of_the_mapping() {
+        assert_eq!(Tuning::equal_temperament().octave_keys(), 12);
+        assert_eq!(Tuning::parse(TWELVE_TET, None).unwrap().octave_keys(), 12);
+        assert_eq!(
+            Tuning::parse(JUST_MAJOR, Some(WHITE_KEYS))
+                .unwrap()
+                .octave_keys(),
+            12
+        );
+
+        let tuning = Tuning::parse(NINETEEN_TET, None).unwrap();
+        assert_eq!(tuning.octave_keys(), 19);
+        assert_eq!(tuning.keys(4), 6);
+        assert_eq!(tuning.keys(7), 11);
+        assert_eq!(tuning.keys(12), 19);
+
+        let tuning = Tuning::parse(JUST_MAJOR, None).unwrap();
+        assert_eq!(tuning.octave_keys(), 7);
+    }
+
+    #[test]
+    fn just_scale_on_white_keys() {
+        let tuning = Tuning::parse(JUST_MAJOR, Some(WHITE_KEYS)).unwrap();
+
+        assert_relative_eq!(tuning.frequency(Note::A4), 440.0, max_relative = 0.0001);
+        assert_relative_eq!(
+            tuning.frequency(Note::C4),
+            440.0 * 3.0 / 5.0,
+            max_relative = 0.0001
+        );
+        assert_relative_eq!(
+            tuning.frequency(Note::G4) / tuning.frequency(Note::C4),
+            1.5,
+            max_relative = 0.0001
+        );
+        assert_relative_eq!(
+            tuning.frequency(Note::C5) / tuning.frequency(Note::C4),
+            2.0,
+            max_relative = 0.0001
+        );
+    }
+
+    #[test]
+    fn unmapped_keys_snap_to_closest_mapped() {
+        let tuning = Tuning::parse(JUST_MAJOR, Some(WHITE_KEYS)).unwrap();
+
+        assert_eq!(tuning.snap(Note::C4), Note::C4);
+        assert_eq!(tuning.snap(Note::CSharp4), Note::C4);
+        assert_eq!(tuning.snap(Note::FSharp4), Note::F4);
+        assert_relative_eq!(tuning.frequency(Note::CSharp4), tuning.frequency(Note::C4));
+    }
+
+    #[test]
+    fn quantize_to_closest_tuned_key() {
+        let tuning = Tuning::parse(JUST_MAJOR, Some(WHITE_KEYS)).unwrap();
+
+        let voct = tuning.voct(Note::E4);
+        assert_eq!(tuning.quantize(voct), Note::E4);
+        assert_eq!(tuning.quantize(voct + 0.3 / 12.0), Note::E4);
+        assert_eq!(tuning.quantize(voct - 0.3 / 12.0), Note::E4);
+        assert_eq!(tuning.quantize(Note::CSharp4.to_voct()), Note::C4);
+    }
+
+    #[test]
+    fn quantize_beyond_limits() {
+        let tuning = Tuning::equal_temperament();
+
+        assert_eq!(tuning.quantize(-1.0), Note::CMinus1);
+        assert_eq!(tuning.quantize(100.0), Note::HIGHEST_NOTE);
+    }
+
+    #[test]
+    fn keys_outside_of_mapped_range_stay_in_equal_temperament() {
+        let kbm = "0\n60\n127\n60\n60\n300.0\n0\n";
+        let tuning = Tuning::parse(TWELVE_TET, Some(kbm)).unwrap();
+
+        assert_relative_eq!(tuning.frequency(Note::C4), 300.0, max_relative = 0.0001);
+        assert_relative_eq!(tuning.frequency(Note::A3), 220.0, max_relative = 0.0001);
+    }
+
+    #[test]
+    fn fail_when_reference_note_is_unmapped() {
+        let kbm = "2\n0\n127\n60\n61\n440.0\n0\n0\nx\n";
+        assert_eq!(
+            Tuning::parse(TWELVE_TET, Some(kbm)).err(),
+            Some(ParseError::InvalidMapping)
+        );
+    }
+}
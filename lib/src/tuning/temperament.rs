@@ -0,0 +1,138 @@
+use super::table::Tuning;
+
+// Built-in tunings selectable on the module. All of them keep 12 keys per
+// octave and the pitch of C, so scales and chords map the same way as in
+// equal temperament.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Temperament {
+    Equal,
+    Pythagorean,
+    Meantone,
+    Werckmeister,
+}
+
+const PYTHAGOREAN: &str = "Pythagorean tuning
+12
+256/243
+9/8
+32/27
+81/64
+4/3
+729/512
+3/2
+128/81
+27/16
+16/9
+243/128
+2/1
+";
+
+const MEANTONE: &str = "Quarter-comma meantone, Eb to G#
+12
+76.04900
+193.15686
+310.26471
+386.31371
+503.42157
+579.47057
+696.57843
+772.62743
+889.73529
+1006.84314
+1082.89214
+2/1
+";
+
+const WERCKMEISTER: &str = "Werckmeister III
+12
+90.22500
+192.18000
+294.13500
+390.22500
+498.04500
+588.27000
+696.09000
+792.18000
+888.27000
+996.09000
+1092.18000
+2/1
+";
+
+impl Temperament {
+    pub fn tuning(self) -> Tuning {
+        let scl = match self {
+            Self::Equal => return Tuning::equal_temperament(),
+            Self::Pythagorean => PYTHAGOREAN,
+            Self::Meantone => MEANTONE,
+            Self::Werckmeister => WERCKMEISTER,
+        };
+        // Built-in scales are known to be valid
+        Tuning::parse(scl, None).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::note::Note;
+
+    fn ratio(tuning: &Tuning, low: Note, high: Note) -> f32 {
+        tuning.frequency(high) / tuning.frequency(low)
+    }
+
+    #[test]
+    fn all_temperaments_keep_c_and_octaves() {
+        for temperament in [
+            Temperament::Equal,
+            Temperament::Pythagorean,
+            Temperament::Meantone,
+            Temperament::Werckmeister,
+        ] {
+            let tuning = temperament.tuning();
+            assert_eq!(tuning.octave_keys(), 12);
+            assert_relative_eq!(
+                tuning.frequency(Note::C4),
+                Note::C4.to_freq_f32(),
+                max_relative = 0.0001
+            );
+            assert_relative_eq!(
+                ratio(&tuning, Note::A3, Note::A4),
+                2.0,
+                max_relative = 0.0001
+            );
+        }
+    }
+
+    #[test]
+    fn pythagorean_has_pure_fifths() {
+        let tuning = Temperament::Pythagorean.tuning();
+        assert_relative_eq!(
+            ratio(&tuning, Note::D4, Note::A4),
+            1.5,
+            max_relative = 0.0001
+        );
+    }
+
+    #[test]
+    fn meantone_has_pure_major_thirds() {
+        let tuning = Temperament::Meantone.tuning();
+        assert_relative_eq!(
+            ratio(&tuning, Note::F4, Note::A4),
+            1.25,
+            max_relative = 0.0001
+        );
+    }
+
+    #[test]
+    fn werckmeister_has_pure_fifth_between_e_and_b() {
+        let tuning = Temperament::Werckmeister.tuning();
+        assert_relative_eq!(
+            ratio(&tuning, Note::E4, Note::B4),
+            1.5,
+            max_relative = 0.0001
+        );
+        assert!(ratio(&tuning, Note::C4, Note::G4) < 1.5);
+    }
+}
@@ -0,0 +1,175 @@
+// Amounts close to one would squeeze the cycle into a single sample.
+const MAX_AMOUNT: f32 = 0.95;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode {
+    // Casio-style phase distortion. The first half of the table is read
+    // faster and the second half slower, moving the knee towards the start.
+    Distortion,
+    // Reads the table faster and restarts it with each cycle, like an
+    // oscillator hard synced to the one with the base frequency.
+    Sync,
+    // Compresses the whole table into a window at the start of the cycle,
+    // holding its first sample for the rest, like pulse width modulation.
+    Window,
+}
+
+// Reshapes the phase of the oscillator before it is looked up in the
+// wavetable. With no amount, the phase is passed through unchanged.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Warp {
+    mode: Mode,
+    amount: f32,
+}
+
+impl Default for Warp {
+    fn default() -> Self {
+        Self::new(Mode::Distortion, 0.0)
+    }
+}
+
+impl Warp {
+    pub fn new(mode: Mode, amount: f32) -> Self {
+        Self {
+            mode,
+            amount: amount.clamp(0.0, MAX_AMOUNT),
+        }
+    }
+
+    pub fn apply(&self, phase: f32) -> f32 {
+        match self.mode {
+            Mode::Distortion => {
+                let knee = 0.5 * (1.0 - self.amount);
+                if phase < knee {
+                    0.5 * phase / knee
+                } else {
+                    0.5 + 0.5 * (phase - knee) / (1.0 - knee)
+                }
+            }
+            Mode::Sync => {
+                let warped = phase * self.ratio();
+                warped - (warped as usize) as f32
+            }
+            Mode::Window => {
+                let width = 1.0 - self.amount;
+                if phase < width {
+                    phase / width
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    // Steepest slope of the warped phase. The table is read this many times
+    // faster than the base frequency, so harmonics of the band must be
+    // limited accordingly to stay below Nyquist.
+    pub fn speedup(&self) -> f32 {
+        match self.mode {
+            Mode::Distortion => 1.0 / (1.0 - self.amount),
+            Mode::Sync => self.ratio(),
+            Mode::Window => 1.0 / (1.0 - self.amount),
+        }
+    }
+
+    // Warped phase reached at the very end of the cycle. Sync with fractional
+    // ratios cuts the last reading of the table short, so the output jumps
+    // when the cycle restarts. Other modes finish at the start of the table.
+    pub fn end(&self) -> f32 {
+        match self.mode {
+            Mode::Sync => self.apply(1.0),
+            Mode::Distortion | Mode::Window => 0.0,
+        }
+    }
+
+    // Amount changes are spread over the buffer. A change of the mode is
+    // applied right away.
+    pub fn interpolate(&self, target: Warp, mix: f32) -> Self {
+        if self.mode == target.mode {
+            Self::new(
+                target.mode,
+                self.amount + (target.amount - self.amount) * mix,
+            )
+        } else {
+            target
+        }
+    }
+
+    fn ratio(&self) -> f32 {
+        1.0 + 7.0 * self.amount
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODES: [Mode; 3] = [Mode::Distortion, Mode::Sync, Mode::Window];
+
+    #[test]
+    fn pass_phase_through_without_amount() {
+        for mode in MODES {
+            let warp = Warp::new(mode, 0.0);
+            for i in 0..10 {
+                let phase = i as f32 / 10.0;
+                assert_relative_eq!(warp.apply(phase), phase, epsilon = 0.0001);
+            }
+            assert_relative_eq!(warp.speedup(), 1.0);
+        }
+    }
+
+    #[test]
+    fn keep_warped_phase_within_cycle() {
+        for mode in MODES {
+            for amount in [0.2, 0.5, 1.0, 10.0] {
+                let warp = Warp::new(mode, amount);
+                for i in 0..100 {
+                    let warped = warp.apply(i as f32 / 100.0);
+                    assert!((0.0..1.0).contains(&warped));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn move_knee_of_phase_distortion() {
+        let warp = Warp::new(Mode::Distortion, 0.5);
+        assert_relative_eq!(warp.apply(0.25), 0.5);
+        assert_relative_eq!(warp.apply(0.625), 0.75);
+        assert_relative_eq!(warp.speedup(), 2.0);
+    }
+
+    #[test]
+    fn restart_table_with_sync() {
+        let warp = Warp::new(Mode::Sync, 1.0 / 7.0);
+        assert_relative_eq!(warp.apply(0.25), 0.5, epsilon = 0.0001);
+        assert_relative_eq!(warp.apply(0.75), 0.5, epsilon = 0.0001);
+        assert_relative_eq!(warp.speedup(), 2.0, epsilon = 0.0001);
+    }
+
+    #[test]
+    fn compress_table_into_window() {
+        let warp = Warp::new(Mode::Window, 0.75);
+        assert_relative_eq!(warp.apply(0.125), 0.5);
+        assert_relative_eq!(warp.apply(0.5), 0.0);
+        assert_relative_eq!(warp.speedup(), 4.0);
+    }
+
+    #[test]
+    fn interpolate_amount_of_same_mode() {
+        let from = Warp::new(Mode::Window, 0.0);
+        let to = Warp::new(Mode::Window, 0.5);
+        assert_eq!(from.interpolate(to, 0.5), Warp::new(Mode::Window, 0.25));
+
+        let to = Warp::new(Mode::Sync, 0.5);
+        assert_eq!(from.interpolate(to, 0.1), to);
+    }
+
+    #[test]
+    fn end_cycle_at_the_start_of_table_unless_synced_by_fraction() {
+        assert_relative_eq!(Warp::new(Mode::Sync, 0.5 / 7.0).end(), 0.5);
+        assert_relative_eq!(Warp::new(Mode::Sync, 1.0 / 7.0).end(), 0.0);
+        assert_eq!(Warp::new(Mode::Distortion, 0.5).end(), 0.0);
+        assert_eq!(Warp::new(Mode::Window, 0.5).end(), 0.0);
+    }
+}